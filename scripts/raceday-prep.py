@@ -42,6 +42,15 @@ def parse_int(value: str) -> int:
 def centiseconds_to_seconds(raw: int) -> float:
     return raw / 10000.0 if raw > 0 else 0.0
 
+def parse_lap_times(value: str) -> list:
+    """Tempos volta a volta ("123456,124001,...") convertidos para segundos."""
+    tempos = []
+    for raw in re.split(r"[,;\s]+", value.strip()):
+        t = centiseconds_to_seconds(parse_int(raw))
+        if t > 0:
+            tempos.append(t)
+    return tempos
+
 def read_ini_with_fallback(path: str) -> configparser.ConfigParser:
     config = configparser.ConfigParser(strict=False, interpolation=None)
 
@@ -119,7 +128,8 @@ def main() -> None:
         },
         "metadata": {
             "slots": max_slot,
-            "generated_at": datetime.now(timezone.utc).isoformat(),
+            # Hora do .INI, não da execução: o mesmo arquivo gera o mesmo JSON
+            "generated_at": datetime.fromtimestamp(os.path.getmtime(args.input), timezone.utc).isoformat(),
             "report_filename": report_filename
 
         },
@@ -131,7 +141,8 @@ def main() -> None:
             "best_times": dict(config["gp_result_best_times"]),
             "gaps": dict(config["gp_result_gap"]) if "gp_result_gap" in config else {},
             "zones": dict(config["gp_result_zone"]) if "gp_result_zone" in config else {},
-            "penaltys": dict(config["gp_result_penaltys"]) if "gp_result_penaltys" in config else {}
+            "penaltys": dict(config["gp_result_penaltys"]) if "gp_result_penaltys" in config else {},
+            "deslots": dict(config["gp_result_deslots"]) if "gp_result_deslots" in config else {}
         }
     }
 
@@ -171,7 +182,10 @@ def main() -> None:
                         "p_id": p_id,
                         "name": pname,
                         "laps": parse_int(sec.get(prefix + "laps", "0")),
-                        "best": centiseconds_to_seconds(best_raw)
+                        "best": centiseconds_to_seconds(best_raw),
+                        # Saídas de pista registradas pelos fiscais no cronometrador
+                        "deslots": parse_int(sec.get(prefix + "deslots", "0")),
+                        "lap_times": parse_lap_times(sec.get(prefix + "lap_times", ""))
                     }
             race_obj["sessions"].append(session_obj)
         result["races"].append(race_obj)
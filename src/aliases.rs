@@ -0,0 +1,173 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::pilots::slug_piloto;
+use crate::registry::Cadastro;
+
+// --- NOMES DOS PILOTOS (APELIDOS E GRAFIAS) ---
+// O mesmo piloto aparece como "J. Silva", "Joao Silva" e "JOÃO SILVA" de uma prova
+// para outra, e a temporada se divide. Antes de montar o ranking cada nome passa
+// pelo mapa de apelidos e vira o nome canônico:
+//   1. mapa de apelidos: {"J. Silva": "João Silva"} em PILOT_ALIASES (padrão
+//      history/aliases.json) ou, sem o arquivo local, em ALIASES_KEY no bucket
+//      (padrão config/aliases.json); no raceday.toml, a tabela [aliases]
+//   2. cadastro de pilotos (registry.rs): o nome e os "aliases" de cada piloto
+// A comparação ignora acentos, maiúsculas e espaços repetidos. `raceday aliases`
+// lista os nomes das provas publicadas que não casam com nenhum dos dois.
+
+const CHAVE_PADRAO: &str = "config/aliases.json";
+
+pub struct Mapa {
+    /// slug do apelido -> nome canônico
+    apelidos: HashMap<String, String>,
+    cadastro: Cadastro,
+}
+
+pub fn caminho_local() -> PathBuf {
+    env::var_os("PILOT_ALIASES").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("history").join("aliases.json"))
+}
+
+pub fn chave_bucket() -> String {
+    env::var("ALIASES_KEY").unwrap_or_else(|_| CHAVE_PADRAO.to_string())
+}
+
+/// Espaços nas pontas e repetidos fora.
+fn limpar(nome: &str) -> String {
+    nome.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Forma de comparação: sem acentos, maiúsculas nem espaços repetidos.
+pub fn chave(nome: &str) -> String {
+    slug_piloto(&limpar(nome))
+}
+
+/// Apelidos do raceday.toml ([aliases], repassados em PILOT_ALIASES_MAP "apelido=nome,...").
+fn apelidos_config() -> Vec<(String, String)> {
+    env::var("PILOT_ALIASES_MAP").unwrap_or_default().split(',')
+        .filter_map(|par| par.split_once('='))
+        .map(|(a, n)| (a.trim().to_string(), n.trim().to_string()))
+        .filter(|(a, n)| !a.is_empty() && !n.is_empty())
+        .collect()
+}
+
+/// Carrega o mapa: arquivo local, senão o do bucket; mais os apelidos do raceday.toml.
+pub async fn carregar() -> Mapa {
+    let texto = match fs::read_to_string(caminho_local()) {
+        Ok(t) => Some(t),
+        Err(_) => match crate::storage::baixar_r2(&chave_bucket()).await {
+            Ok(bytes) => bytes.map(|b| String::from_utf8_lossy(&b).to_string()),
+            Err(e) => {
+                erro!("⚠️ Mapa de apelidos do bucket indisponível ({}): {}", chave_bucket(), e);
+                None
+            }
+        },
+    };
+    let mut lidos: BTreeMap<String, String> = match texto.as_deref().map(serde_json::from_str) {
+        Some(Ok(m)) => m,
+        Some(Err(e)) => {
+            erro!("⚠️ Mapa de apelidos inválido (esperado {{\"apelido\": \"nome\"}}): {}", e);
+            BTreeMap::new()
+        }
+        None => BTreeMap::new(),
+    };
+    lidos.extend(apelidos_config());
+    Mapa {
+        apelidos: lidos.into_iter().map(|(apelido, nome)| (chave(&apelido), limpar(&nome))).collect(),
+        cadastro: Cadastro::carregar(),
+    }
+}
+
+impl Mapa {
+    /// Nome canônico, se o nome casar com um apelido ou com o cadastro.
+    pub fn canonico(&self, nome: &str) -> Option<String> {
+        let slug = chave(nome);
+        if let Some(canonico) = self.apelidos.get(&slug) {
+            return Some(canonico.clone());
+        }
+        self.cadastro.pilotos.iter()
+            .find(|(canonico, c)| chave(canonico) == slug || c.aliases.iter().any(|a| chave(a) == slug))
+            .map(|(canonico, _)| canonico.clone())
+    }
+
+    /// Nome que está no cadastro de pilotos (exatamente como cadastrado).
+    pub fn cadastrado(&self, nome: &str) -> bool {
+        self.cadastro.pilotos.contains_key(nome)
+    }
+
+    /// Nome canônico ou o próprio nome, sem espaços sobrando.
+    pub fn nome(&self, nome: &str) -> String {
+        self.canonico(nome).unwrap_or_else(|| limpar(nome))
+    }
+}
+
+/// Troca os nomes da prova pelos canônicos: pilotos, ranking oficial e baterias.
+/// Retorna quantos pilotos mudaram de nome.
+pub fn aplicar(data: &mut Value, mapa: &Mapa) -> usize {
+    let mut trocados = 0;
+    for info in data["pilots"].as_object_mut().into_iter().flat_map(|p| p.values_mut()) {
+        let Some(atual) = info["name"].as_str().map(|s| s.to_string()) else { continue };
+        let novo = mapa.nome(&atual);
+        if novo != atual {
+            saida!("🔤 {} -> {}", atual, novo);
+            info["name"] = Value::String(novo);
+            trocados += 1;
+        }
+    }
+    for linha in data["official_ranking"].as_array_mut().into_iter().flatten() {
+        if let Some(atual) = linha["name"].as_str().map(|s| s.to_string()) {
+            linha["name"] = Value::String(mapa.nome(&atual));
+        }
+    }
+    for bateria in data["races"].as_array_mut().into_iter().flatten() {
+        for sessao in bateria["sessions"].as_array_mut().into_iter().flatten() {
+            for slot in sessao["slots"].as_object_mut().into_iter().flat_map(|s| s.values_mut()) {
+                if let Some(atual) = slot["name"].as_str().map(|s| s.to_string()) {
+                    slot["name"] = Value::String(mapa.nome(&atual));
+                }
+            }
+        }
+    }
+    trocados
+}
+
+/// Nomes dos pilotos nas provas publicadas do clube (sem espaços sobrando), com as
+/// chaves das provas em que aparecem. `ano` filtra pelo timestamp da prova.
+pub async fn nomes_publicados(ano: Option<&str>, club: &str) -> Result<BTreeMap<String, BTreeSet<String>>, Box<dyn Error>> {
+    let prefixo = format!("races/{}_", crate::storage::slug_chave(club));
+    let mut nomes: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for key in crate::storage::listar_r2(&prefixo).await? {
+        let Some(ts) = crate::season::timestamp_da_chave(&key) else { continue };
+        if ano.is_some_and(|a| !ts.starts_with(a)) { continue; }
+        let Some(bytes) = crate::storage::baixar_r2(&key).await? else { continue };
+        let Ok(data) = crate::schema::ler_publicada(&key, &bytes) else { continue };
+        for info in data["pilots"].as_object().into_iter().flat_map(|p| p.values()) {
+            let Some(nome) = info["name"].as_str() else { continue };
+            nomes.entry(limpar(nome)).or_default().insert(key.clone());
+        }
+    }
+    Ok(nomes)
+}
+
+/// `raceday aliases [--year 2025]`: nomes das provas publicadas do clube que não
+/// casam com o mapa de apelidos nem com o cadastro, com o número de provas.
+pub async fn executar(ano: Option<&str>, club: &str) -> Result<(), Box<dyn Error>> {
+    let mapa = carregar().await;
+    let nomes = nomes_publicados(ano, club).await?;
+    let provas: BTreeSet<&String> = nomes.values().flatten().collect();
+    let sem_par: Vec<(&String, usize)> = nomes.iter()
+        .filter(|(nome, _)| mapa.canonico(nome).is_none())
+        .map(|(nome, chaves)| (nome, chaves.len()))
+        .collect();
+    saida!("🔤 {} prova(s) lida(s); {} nome(s) sem correspondência no mapa de apelidos ou no cadastro:", provas.len(), sem_par.len());
+    for (nome, n) in &sem_par {
+        println!("{}\t{}", nome, n);
+    }
+    if !sem_par.is_empty() {
+        saida!("   Acrescente os apelidos em {} (ou {} no bucket) ou no cadastro de pilotos", caminho_local().display(), chave_bucket());
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tera::{Tera, Value};
+
+use crate::refresh::base_url;
+
+// --- ASSETS COM HASH NO NOME (CACHE BUSTING) ---
+// Cada arquivo da pasta assets/ (ou ASSETS_DIR) é publicado como
+// assets/<nome>.<hash>.<ext>. Os templates pedem o endereço com
+// {{ asset(name="branding.css") }}, então o HTML gerado sempre aponta para a
+// versão atual e o CDN pode guardar os assets para sempre.
+
+static MANIFESTO: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+pub fn pasta_assets() -> PathBuf {
+    env::var_os("ASSETS_DIR").map(PathBuf::from).unwrap_or_else(|| crate::recurso(Path::new("assets")))
+}
+
+/// FNV-1a de 64 bits: estável entre versões do compilador, ao contrário do DefaultHasher.
+fn hash_conteudo(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)[..8].to_string()
+}
+
+/// 'branding.css' + 'a1b2c3d4' -> 'branding.a1b2c3d4.css'
+fn nome_com_hash(nome: &str, hash: &str) -> String {
+    match nome.rsplit_once('.') {
+        Some((base, ext)) => format!("{}.{}.{}", base, hash, ext),
+        None => format!("{}.{}", nome, hash),
+    }
+}
+
+/// Publica os assets (só os que ainda não existem no bucket) e guarda o manifesto
+/// usado pela função `asset` dos templates. Chamado uma vez por execução.
+pub async fn publicar() -> Result<(), Box<dyn Error>> {
+    if MANIFESTO.get().is_some() { return Ok(()); }
+
+    let mut arquivos: Vec<(String, PathBuf)> = Vec::new();
+    let pasta = pasta_assets();
+    if pasta.is_dir() {
+        let mut lista: Vec<PathBuf> = fs::read_dir(&pasta)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.is_file())
+            .collect();
+        lista.sort();
+        arquivos.extend(lista.into_iter()
+            .filter_map(|p| p.file_name().and_then(|n| n.to_str()).map(|n| (n.to_string(), p.clone()))));
+    }
+    // Logo do tema fora da pasta de assets (CLUB_LOGO com caminho local)
+    if let Some((nome, caminho)) = crate::theme::logo_local() {
+        arquivos.push((nome, caminho));
+    }
+
+    let mut manifesto = HashMap::new();
+    for (nome, arquivo) in arquivos {
+        let key = format!("assets/{}", nome_com_hash(&nome, &hash_conteudo(&fs::read(&arquivo)?)));
+        // O nome muda junto com o conteúdo: se a chave já existe, o arquivo é o mesmo
+        crate::storage::upload_se_mudou(&arquivo, &key).await?;
+        manifesto.insert(nome, format!("{}/{}", base_url(), key));
+    }
+
+    let _ = MANIFESTO.set(manifesto);
+    Ok(())
+}
+
+/// Endereço publicado do asset, se existir.
+pub fn url(nome: &str) -> Option<String> {
+    MANIFESTO.get().and_then(|m| m.get(nome)).cloned()
+}
+
+/// Registra `asset(name="...")` no Tera. Devolve "" para assets inexistentes,
+/// então os templates podem testar com {% if %}. Registra também o `t()` do i18n
+/// e os filtros de formatação (filters.rs).
+pub fn registrar(tera: &mut Tera) {
+    crate::i18n::registrar(tera);
+    crate::filters::registrar(tera);
+    tera.register_function("asset", |args: &HashMap<String, Value>| {
+        let nome = args.get("name").and_then(|v| v.as_str())
+            .ok_or_else(|| tera::Error::msg("asset() requer o argumento name"))?;
+        Ok(Value::String(url(nome).unwrap_or_default()))
+    });
+}
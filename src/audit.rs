@@ -0,0 +1,33 @@
+use serde_json::{json, Value};
+use std::env;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::history::agora_timestamp;
+
+// --- TRILHA DE AUDITORIA ---
+// Toda alteração feita pela API (correções, penalidades) vira uma linha JSON em
+// AUDIT_LOG_PATH (padrão history/audit.jsonl). O arquivo só cresce: nada é reescrito.
+
+pub fn caminho_auditoria() -> PathBuf {
+    env::var_os("AUDIT_LOG_PATH").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("history").join("audit.jsonl"))
+}
+
+pub fn registrar(usuario: &str, acao: &str, prova: &str, detalhes: &Value) -> Result<(), Box<dyn Error>> {
+    let path = caminho_auditoria();
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let linha = json!({
+        "at": agora_timestamp(),
+        "user": usuario,
+        "action": acao,
+        "race": prova,
+        "details": detalhes,
+    });
+    let mut arquivo = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(arquivo, "{}", linha)?;
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::env;
+
+// --- AUTENTICAÇÃO DO MODO SERVE/API ---
+//   SERVE_USERS   usuários para Basic auth: "publico:senha,diretor:senha:control"
+//   SERVE_TOKENS  tokens para "Authorization: Bearer <token>": "read=abc,control=xyz"
+// Sem papel (ou com "read") o acesso é somente leitura; "control" também pode
+// lançar correções e penalidades. Sem credenciais o servidor só aceita conexões
+// locais (127.0.0.1), e somente leitura.
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Papel {
+    Leitura,
+    Controle,
+}
+
+pub struct Identidade {
+    pub nome: String,
+    pub papel: Papel,
+}
+
+pub struct Credenciais {
+    usuarios: Vec<(String, String, Papel)>,
+    tokens: Vec<(String, Papel)>,
+}
+
+fn papel(nome: &str) -> Option<Papel> {
+    match nome.trim() {
+        "read" => Some(Papel::Leitura),
+        "control" => Some(Papel::Controle),
+        _ => None,
+    }
+}
+
+fn lista(var: &str) -> Vec<String> {
+    env::var(var).unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Comparação em tempo constante, para não vazar o segredo pelo tempo de resposta.
+fn iguais(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+impl Credenciais {
+    pub fn carregar() -> Credenciais {
+        Credenciais {
+            usuarios: lista("SERVE_USERS").into_iter()
+                .filter_map(|u| {
+                    let (nome, resto) = u.split_once(':')?;
+                    // "nome:senha:control" — o papel só é reconhecido no último campo
+                    let (senha, p) = match resto.rsplit_once(':').and_then(|(s, p)| papel(p).map(|p| (s, p))) {
+                        Some((senha, p)) => (senha.to_string(), p),
+                        None => (resto.to_string(), Papel::Leitura),
+                    };
+                    Some((nome.to_string(), senha, p))
+                })
+                .collect(),
+            tokens: lista("SERVE_TOKENS").into_iter()
+                .map(|t| match t.split_once('=').and_then(|(p, v)| papel(p).map(|p| (v.to_string(), p))) {
+                    Some(par) => par,
+                    None => (t, Papel::Leitura),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn configuradas(&self) -> bool {
+        !self.usuarios.is_empty() || !self.tokens.is_empty()
+    }
+
+    /// Valida o cabeçalho Authorization e devolve quem se autenticou e com qual papel.
+    pub fn autenticar(&self, authorization: Option<&str>) -> Option<Identidade> {
+        if !self.configuradas() {
+            return Some(Identidade { nome: "local".to_string(), papel: Papel::Leitura });
+        }
+        let (esquema, valor) = authorization?.trim().split_once(' ')?;
+
+        if esquema.eq_ignore_ascii_case("Bearer") {
+            let valor = valor.trim();
+            // Identifica o token pelo prefixo no log, nunca pelo valor inteiro
+            return self.tokens.iter().find(|(t, _)| iguais(t, valor)).map(|(_, p)| Identidade {
+                nome: format!("token:{}…", valor.chars().take(4).collect::<String>()),
+                papel: *p,
+            });
+        }
+        if esquema.eq_ignore_ascii_case("Basic") {
+            let decodificado = String::from_utf8(STANDARD.decode(valor.trim()).ok()?).ok()?;
+            let (nome, senha) = decodificado.split_once(':')?;
+            return self.usuarios.iter()
+                .find(|(n, s, _)| iguais(n, nome) && iguais(s, senha))
+                .map(|(_, _, p)| Identidade { nome: nome.to_string(), papel: *p });
+        }
+        None
+    }
+}
@@ -0,0 +1,660 @@
+use async_trait::async_trait;
+use aws_sdk_s3::primitives::ByteStream;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::error::RacedayError;
+
+// --- BACKENDS DE ARMAZENAMENTO ---
+// Onde os artefatos publicados ficam, escolhido por STORAGE_BACKEND
+// (ou [storage] backend no raceday.toml):
+//   r2 / s3                         padrão: bucket S3/R2 de R2_ENDPOINT e R2_BUCKET
+//   dir:/mnt/nas/raceday            pasta local ou compartilhamento de rede (NAS)
+//   sftp://usuario@host[:porta]/public_html/raceday
+//                                   SFTP_PASSWORD ou SFTP_KEY (chave privada); sem nenhum, usa o ssh-agent
+//   ftp://usuario@host[:porta]/public_html/raceday
+//                                   FTP_PASSWORD (hospedagens compartilhadas)
+//   gcs://<bucket>                  Google Cloud Storage; GCS_ACCESS_TOKEN (ex.: `gcloud auth print-access-token`)
+//   pages:<projeto>                 Cloudflare Pages: monta o site numa pasta local e faz o deploy (ver pages.rs)
+//   github:<dono>/<repo>@<branch>/<pasta>
+//                                   commit em um repositório (GitHub Pages); GITHUB_TOKEN com permissão "contents: write"
+// Em todos eles as chaves (races/..., reports/...) viram caminhos relativos à base.
+
+pub type ErroStorage = Box<dyn Error + Send + Sync>;
+
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Nome para mensagens e para o resumo de destinos (ex.: "sftp:host").
+    fn nome(&self) -> String;
+    async fn enviar(&self, file_path: &Path, key: &str, content_type: &str, cache_control: Option<&str>) -> Result<(), ErroStorage>;
+    /// Chaves que começam com o prefixo, como no list_objects do S3.
+    async fn listar(&self, prefix: &str) -> Result<Vec<String>, ErroStorage>;
+    /// Conteúdo da chave. `None` quando ela ainda não existe.
+    async fn baixar(&self, key: &str) -> Result<Option<Vec<u8>>, ErroStorage>;
+
+    /// Conteúdo e versão (ETag) da chave, para usar em `enviar_se_versao`.
+    /// Sem ETag nativo, a versão é o hash do conteúdo.
+    async fn baixar_versionado(&self, key: &str) -> Result<Option<(Vec<u8>, String)>, ErroStorage> {
+        Ok(self.baixar(key).await?.map(|c| { let versao = blake3::hash(&c).to_hex().to_string(); (c, versao) }))
+    }
+
+    /// Hash blake3 do conteúdo publicado na chave, para pular envios sem alteração.
+    /// Sem metadado com o hash, baixa a chave e calcula.
+    async fn hash_publicado(&self, key: &str) -> Result<Option<String>, ErroStorage> {
+        Ok(self.baixar(key).await?.map(|c| blake3::hash(&c).to_hex().to_string()))
+    }
+
+    /// Grava só se a chave ainda estiver na `versao` lida (`None`: só se ainda não existir).
+    /// `Ok(false)` quando outro processo gravou antes. Sem suporte nativo a escrita
+    /// condicional a checagem é feita relendo a chave, o que reduz mas não elimina a janela.
+    async fn enviar_se_versao(&self, file_path: &Path, key: &str, content_type: &str, versao: Option<&str>) -> Result<bool, ErroStorage> {
+        let atual = self.baixar_versionado(key).await?.map(|(_, v)| v);
+        if atual.as_deref() != versao { return Ok(false); }
+        self.enviar(file_path, key, content_type, None).await?;
+        Ok(true)
+    }
+}
+
+/// Backend em uso no processo. O modo offline sempre grava na pasta local.
+/// STORAGE_BACKEND que não se entende é erro de entrada.
+pub fn atual() -> Result<&'static dyn Storage, RacedayError> {
+    static ATUAL: OnceLock<Option<Box<dyn Storage>>> = OnceLock::new();
+    let spec = env::var("STORAGE_BACKEND").unwrap_or_default();
+    ATUAL.get_or_init(|| {
+        if crate::offline::ativo() {
+            return Some(Box::new(Pasta(crate::offline::pasta())));
+        }
+        interpretar(spec.trim())
+    }).as_deref().ok_or_else(|| RacedayError::Entrada(format!("❌ STORAGE_BACKEND inválido: {}", spec)))
+}
+
+/// Verdadeiro quando a publicação vai para um bucket S3/R2 (necessário para URLs assinadas).
+pub fn usa_s3() -> bool {
+    !crate::offline::ativo() && matches!(env::var("STORAGE_BACKEND").as_deref().map(str::trim), Err(_) | Ok("" | "r2" | "s3"))
+}
+
+fn interpretar(spec: &str) -> Option<Box<dyn Storage>> {
+    if spec.is_empty() || spec == "r2" || spec == "s3" {
+        return Some(Box::new(S3));
+    }
+    if let Some(dir) = spec.strip_prefix("dir:") {
+        return Some(Box::new(Pasta(PathBuf::from(dir))));
+    }
+    if let Some(projeto) = spec.strip_prefix("pages:") {
+        return Some(Box::new(Pages { projeto: projeto.to_string(), site: Pasta(crate::pages::pasta_site()) }));
+    }
+    if let Some(resto) = spec.strip_prefix("github:") {
+        return Some(Box::new(GitHub::interpretar(resto)?));
+    }
+    if let Some(bucket) = spec.strip_prefix("gcs://") {
+        return Some(Box::new(Gcs { bucket: bucket.trim_end_matches('/').to_string() }));
+    }
+    if let Some(resto) = spec.strip_prefix("sftp://") {
+        let remoto = Remoto::interpretar(resto, 22, "SFTP_PASSWORD")?;
+        let chave = env::var_os("SFTP_KEY").map(PathBuf::from);
+        return Some(Box::new(Sftp { remoto, chave }));
+    }
+    if let Some(resto) = spec.strip_prefix("ftp://") {
+        return Some(Box::new(Ftp(Remoto::interpretar(resto, 21, "FTP_PASSWORD")?)));
+    }
+    None
+}
+
+// --- S3 / R2 ---
+
+/// Metadado com o hash blake3 do conteúdo (x-amz-meta-blake3).
+const META_HASH: &str = "blake3";
+
+struct S3;
+
+#[async_trait]
+impl Storage for S3 {
+    fn nome(&self) -> String {
+        "r2".to_string()
+    }
+
+    async fn enviar(&self, file_path: &Path, key: &str, content_type: &str, cache_control: Option<&str>) -> Result<(), ErroStorage> {
+        let (client, bucket) = crate::storage::cliente_r2().await?;
+        let conteudo = fs::read(file_path)?;
+        client.put_object()
+            .bucket(bucket)
+            .key(key)
+            // O ETag do R2 é MD5 (ou outro formato em multipart): o hash vai num metadado próprio
+            .metadata(META_HASH, blake3::hash(&conteudo).to_hex().to_string())
+            .body(ByteStream::from(conteudo))
+            .content_type(content_type)
+            .set_cache_control(cache_control.map(|c| c.to_string()))
+            .send().await?;
+        Ok(())
+    }
+
+    async fn hash_publicado(&self, key: &str) -> Result<Option<String>, ErroStorage> {
+        let (client, bucket) = crate::storage::cliente_r2().await?;
+        match client.head_object().bucket(bucket).key(key).send().await {
+            Ok(obj) => match obj.metadata().and_then(|m| m.get(META_HASH)) {
+                Some(hash) => Ok(Some(hash.clone())),
+                // Publicado antes do metadado: compara pelo conteúdo
+                None => Ok(self.baixar(key).await?.map(|c| blake3::hash(&c).to_hex().to_string())),
+            },
+            Err(e) if e.as_service_error().map(|se| se.is_not_found()).unwrap_or(false) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn listar(&self, prefix: &str) -> Result<Vec<String>, ErroStorage> {
+        let (client, bucket) = crate::storage::cliente_r2().await?;
+        let mut chaves = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            crate::throttle::aguardar().await;
+            let resp = client.list_objects_v2()
+                .bucket(&bucket)
+                .prefix(prefix)
+                .set_continuation_token(token.clone())
+                .send().await?;
+            chaves.extend(resp.contents().iter().filter_map(|o| o.key().map(|k| k.to_string())));
+            match resp.next_continuation_token() {
+                Some(t) => token = Some(t.to_string()),
+                None => break,
+            }
+        }
+        Ok(chaves)
+    }
+
+    async fn baixar(&self, key: &str) -> Result<Option<Vec<u8>>, ErroStorage> {
+        Ok(self.baixar_versionado(key).await?.map(|(c, _)| c))
+    }
+
+    async fn baixar_versionado(&self, key: &str) -> Result<Option<(Vec<u8>, String)>, ErroStorage> {
+        let (client, bucket) = crate::storage::cliente_r2().await?;
+        match client.get_object().bucket(bucket).key(key).send().await {
+            Ok(obj) => {
+                let etag = obj.e_tag().unwrap_or_default().to_string();
+                Ok(Some((obj.body.collect().await?.into_bytes().to_vec(), etag)))
+            }
+            Err(e) if e.as_service_error().map(|se| se.is_no_such_key()).unwrap_or(false) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn enviar_se_versao(&self, file_path: &Path, key: &str, content_type: &str, versao: Option<&str>) -> Result<bool, ErroStorage> {
+        let (client, bucket) = crate::storage::cliente_r2().await?;
+        let conteudo = fs::read(file_path)?;
+        let requisicao = client.put_object()
+            .bucket(bucket)
+            .key(key)
+            .metadata(META_HASH, blake3::hash(&conteudo).to_hex().to_string())
+            .body(ByteStream::from(conteudo))
+            .content_type(content_type);
+        // If-Match com o ETag lido, ou If-None-Match: * para uma chave nova
+        let requisicao = match versao {
+            Some(etag) => requisicao.if_match(etag),
+            None => requisicao.if_none_match("*"),
+        };
+        match requisicao.send().await {
+            Ok(_) => Ok(true),
+            // 412 Precondition Failed; 409 quando duas escritas condicionais se cruzam
+            Err(e) if matches!(e.raw_response().map(|r| r.status().as_u16()), Some(412 | 409)) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+// --- PASTA LOCAL / NAS ---
+
+struct Pasta(PathBuf);
+
+impl Pasta {
+    fn caminho(&self, key: &str) -> PathBuf {
+        // Chaves usam '/', o PathBuf converte para o separador do sistema
+        key.split('/').fold(self.0.clone(), |p, parte| p.join(parte))
+    }
+}
+
+#[async_trait]
+impl Storage for Pasta {
+    fn nome(&self) -> String {
+        format!("dir:{}", self.0.display())
+    }
+
+    async fn enviar(&self, file_path: &Path, key: &str, _content_type: &str, _cache_control: Option<&str>) -> Result<(), ErroStorage> {
+        let alvo = self.caminho(key);
+        if let Some(pai) = alvo.parent() { fs::create_dir_all(pai)?; }
+        fs::copy(file_path, alvo)?;
+        Ok(())
+    }
+
+    async fn listar(&self, prefix: &str) -> Result<Vec<String>, ErroStorage> {
+        let mut chaves = Vec::new();
+        coletar_pasta(&self.0, &self.0, &mut chaves);
+        chaves.retain(|k| k.starts_with(prefix));
+        chaves.sort();
+        Ok(chaves)
+    }
+
+    async fn baixar(&self, key: &str) -> Result<Option<Vec<u8>>, ErroStorage> {
+        let path = self.caminho(key);
+        Ok(if path.exists() { Some(fs::read(path)?) } else { None })
+    }
+}
+
+fn coletar_pasta(raiz: &Path, dir: &Path, chaves: &mut Vec<String>) {
+    let Ok(entradas) = fs::read_dir(dir) else { return };
+    for entrada in entradas.flatten() {
+        let path = entrada.path();
+        if path.is_dir() {
+            coletar_pasta(raiz, &path, chaves);
+        } else if let Ok(relativo) = path.strip_prefix(raiz) {
+            let partes: Vec<String> = relativo.components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            chaves.push(partes.join("/"));
+        }
+    }
+}
+
+// --- CLOUDFLARE PAGES ---
+// Os envios só montam a pasta do site; o deploy acontece uma vez por prova,
+// no fim do pipeline (pages::implantar).
+
+struct Pages {
+    projeto: String,
+    site: Pasta,
+}
+
+#[async_trait]
+impl Storage for Pages {
+    fn nome(&self) -> String {
+        format!("pages:{}", self.projeto)
+    }
+
+    async fn enviar(&self, file_path: &Path, key: &str, content_type: &str, cache_control: Option<&str>) -> Result<(), ErroStorage> {
+        self.site.enviar(file_path, key, content_type, cache_control).await
+    }
+
+    async fn listar(&self, prefix: &str) -> Result<Vec<String>, ErroStorage> {
+        self.site.listar(prefix).await
+    }
+
+    async fn baixar(&self, key: &str) -> Result<Option<Vec<u8>>, ErroStorage> {
+        self.site.baixar(key).await
+    }
+}
+
+// --- SFTP E FTP (HOSPEDAGEM COMPARTILHADA) ---
+// As bibliotecas são síncronas: cada operação abre uma conexão dentro de
+// spawn_blocking, para não travar o runtime durante os envios em paralelo.
+
+#[derive(Clone)]
+struct Remoto {
+    usuario: String,
+    host: String,
+    porta: u16,
+    base: String,
+    senha: Option<String>,
+}
+
+impl Remoto {
+    /// "usuario@host[:porta]/caminho/base"
+    fn interpretar(resto: &str, porta_padrao: u16, var_senha: &str) -> Option<Remoto> {
+        let (autoridade, base) = resto.split_once('/').unwrap_or((resto, ""));
+        let (usuario, host_porta) = autoridade.split_once('@')?;
+        let (host, porta) = match host_porta.rsplit_once(':') {
+            Some((h, p)) => (h, p.parse().ok()?),
+            None => (host_porta, porta_padrao),
+        };
+        if usuario.is_empty() || host.is_empty() { return None; }
+        Some(Remoto {
+            usuario: usuario.to_string(),
+            host: host.to_string(),
+            porta,
+            base: format!("/{}", base.trim_matches('/')),
+            senha: env::var(var_senha).ok(),
+        })
+    }
+
+    fn caminho(&self, key: &str) -> String {
+        format!("{}/{}", self.base.trim_end_matches('/'), key)
+    }
+
+    /// Pastas intermediárias de um caminho remoto, da mais externa para a mais interna.
+    fn pastas(caminho: &str) -> Vec<String> {
+        let partes: Vec<&str> = caminho.split('/').filter(|p| !p.is_empty()).collect();
+        (1..partes.len()).map(|n| format!("/{}", partes[..n].join("/"))).collect()
+    }
+
+    fn chave(&self, caminho: &str) -> Option<String> {
+        caminho.strip_prefix(self.base.trim_end_matches('/'))
+            .map(|k| k.trim_start_matches('/').to_string())
+    }
+}
+
+async fn bloqueante<T: Send + 'static>(f: impl FnOnce() -> Result<T, ErroStorage> + Send + 'static) -> Result<T, ErroStorage> {
+    tokio::task::spawn_blocking(f).await?
+}
+
+struct Sftp {
+    remoto: Remoto,
+    chave: Option<PathBuf>,
+}
+
+fn conectar_sftp(remoto: &Remoto, chave: Option<&Path>) -> Result<ssh2::Sftp, ErroStorage> {
+    let tcp = TcpStream::connect((remoto.host.as_str(), remoto.porta))?;
+    let mut sessao = ssh2::Session::new()?;
+    sessao.set_tcp_stream(tcp);
+    sessao.handshake()?;
+    match (chave, &remoto.senha) {
+        (Some(chave), senha) => sessao.userauth_pubkey_file(&remoto.usuario, None, chave, senha.as_deref())?,
+        (None, Some(senha)) => sessao.userauth_password(&remoto.usuario, senha)?,
+        (None, None) => sessao.userauth_agent(&remoto.usuario)?,
+    }
+    Ok(sessao.sftp()?)
+}
+
+fn listar_sftp(sftp: &ssh2::Sftp, dir: &Path, remoto: &Remoto, chaves: &mut Vec<String>) {
+    let Ok(entradas) = sftp.readdir(dir) else { return };
+    for (path, stat) in entradas {
+        if stat.is_dir() {
+            listar_sftp(sftp, &path, remoto, chaves);
+        } else if let Some(chave) = remoto.chave(&path.to_string_lossy()) {
+            chaves.push(chave);
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for Sftp {
+    fn nome(&self) -> String {
+        format!("sftp:{}{}", self.remoto.host, self.remoto.base)
+    }
+
+    async fn enviar(&self, file_path: &Path, key: &str, _content_type: &str, _cache_control: Option<&str>) -> Result<(), ErroStorage> {
+        let (remoto, chave) = (self.remoto.clone(), self.chave.clone());
+        let (local, alvo) = (file_path.to_path_buf(), self.remoto.caminho(key));
+        bloqueante(move || {
+            let sftp = conectar_sftp(&remoto, chave.as_deref())?;
+            for pasta in Remoto::pastas(&alvo) {
+                // Já existir não é erro
+                let _ = sftp.mkdir(Path::new(&pasta), 0o755);
+            }
+            sftp.create(Path::new(&alvo))?.write_all(&fs::read(local)?)?;
+            Ok(())
+        }).await
+    }
+
+    async fn listar(&self, prefix: &str) -> Result<Vec<String>, ErroStorage> {
+        let (remoto, chave, prefix) = (self.remoto.clone(), self.chave.clone(), prefix.to_string());
+        bloqueante(move || {
+            let sftp = conectar_sftp(&remoto, chave.as_deref())?;
+            let mut chaves = Vec::new();
+            listar_sftp(&sftp, Path::new(&remoto.base), &remoto, &mut chaves);
+            chaves.retain(|k| k.starts_with(&prefix));
+            chaves.sort();
+            Ok(chaves)
+        }).await
+    }
+
+    async fn baixar(&self, key: &str) -> Result<Option<Vec<u8>>, ErroStorage> {
+        let (remoto, chave, alvo) = (self.remoto.clone(), self.chave.clone(), self.remoto.caminho(key));
+        bloqueante(move || {
+            let sftp = conectar_sftp(&remoto, chave.as_deref())?;
+            // LIBSSH2_FX_NO_SUCH_FILE
+            match sftp.open(Path::new(&alvo)) {
+                Ok(mut arquivo) => {
+                    let mut conteudo = Vec::new();
+                    arquivo.read_to_end(&mut conteudo)?;
+                    Ok(Some(conteudo))
+                }
+                Err(e) if e.code() == ssh2::ErrorCode::SFTP(2) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        }).await
+    }
+}
+
+struct Ftp(Remoto);
+
+fn conectar_ftp(remoto: &Remoto) -> Result<suppaftp::FtpStream, ErroStorage> {
+    let mut ftp = suppaftp::FtpStream::connect((remoto.host.as_str(), remoto.porta))?;
+    ftp.login(remoto.usuario.as_str(), remoto.senha.as_deref().unwrap_or(""))?;
+    ftp.transfer_type(suppaftp::types::FileType::Binary)?;
+    Ok(ftp)
+}
+
+fn listar_ftp(ftp: &mut suppaftp::FtpStream, dir: &str, remoto: &Remoto, chaves: &mut Vec<String>) {
+    let Ok(linhas) = ftp.list(Some(dir)) else { return };
+    for linha in linhas {
+        let Ok(item) = linha.parse::<suppaftp::list::File>() else { continue };
+        if item.name() == "." || item.name() == ".." { continue; }
+        let caminho = format!("{}/{}", dir.trim_end_matches('/'), item.name());
+        if item.is_directory() {
+            listar_ftp(ftp, &caminho, remoto, chaves);
+        } else if let Some(chave) = remoto.chave(&caminho) {
+            chaves.push(chave);
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for Ftp {
+    fn nome(&self) -> String {
+        format!("ftp:{}{}", self.0.host, self.0.base)
+    }
+
+    async fn enviar(&self, file_path: &Path, key: &str, _content_type: &str, _cache_control: Option<&str>) -> Result<(), ErroStorage> {
+        let (remoto, local, alvo) = (self.0.clone(), file_path.to_path_buf(), self.0.caminho(key));
+        bloqueante(move || {
+            let mut ftp = conectar_ftp(&remoto)?;
+            for pasta in Remoto::pastas(&alvo) {
+                let _ = ftp.mkdir(&pasta);
+            }
+            ftp.put_file(&alvo, &mut fs::File::open(local)?)?;
+            let _ = ftp.quit();
+            Ok(())
+        }).await
+    }
+
+    async fn listar(&self, prefix: &str) -> Result<Vec<String>, ErroStorage> {
+        let (remoto, prefix) = (self.0.clone(), prefix.to_string());
+        bloqueante(move || {
+            let mut ftp = conectar_ftp(&remoto)?;
+            let mut chaves = Vec::new();
+            listar_ftp(&mut ftp, &remoto.base, &remoto, &mut chaves);
+            let _ = ftp.quit();
+            chaves.retain(|k| k.starts_with(&prefix));
+            chaves.sort();
+            Ok(chaves)
+        }).await
+    }
+
+    async fn baixar(&self, key: &str) -> Result<Option<Vec<u8>>, ErroStorage> {
+        let (remoto, alvo) = (self.0.clone(), self.0.caminho(key));
+        bloqueante(move || {
+            let mut ftp = conectar_ftp(&remoto)?;
+            let resultado = match ftp.retr_as_buffer(&alvo) {
+                Ok(buffer) => Ok(Some(buffer.into_inner())),
+                // 550: arquivo inexistente
+                Err(suppaftp::FtpError::UnexpectedResponse(r)) if r.status == suppaftp::Status::FileUnavailable => Ok(None),
+                Err(e) => Err(e.into()),
+            };
+            let _ = ftp.quit();
+            resultado
+        }).await
+    }
+}
+
+// --- GOOGLE CLOUD STORAGE ---
+// Envio e download pela API XML (aceita Content-Type e Cache-Control como
+// cabeçalhos); a listagem usa a API JSON, que pagina com nextPageToken.
+
+struct Gcs {
+    bucket: String,
+}
+
+const GCS_BASE: &str = "https://storage.googleapis.com";
+
+fn token_gcs() -> Result<String, ErroStorage> {
+    env::var("GCS_ACCESS_TOKEN").map_err(|_| "GCS_ACCESS_TOKEN não definida".into())
+}
+
+fn cliente_http() -> &'static reqwest::Client {
+    static CLIENTE: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENTE.get_or_init(reqwest::Client::new)
+}
+
+#[async_trait]
+impl Storage for Gcs {
+    fn nome(&self) -> String {
+        format!("gcs:{}", self.bucket)
+    }
+
+    async fn enviar(&self, file_path: &Path, key: &str, content_type: &str, cache_control: Option<&str>) -> Result<(), ErroStorage> {
+        let mut requisicao = cliente_http()
+            .put(format!("{}/{}/{}", GCS_BASE, self.bucket, key))
+            .bearer_auth(token_gcs()?)
+            .header("Content-Type", content_type)
+            .body(fs::read(file_path)?);
+        if let Some(cc) = cache_control {
+            requisicao = requisicao.header("Cache-Control", cc);
+        }
+        requisicao.send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    async fn listar(&self, prefix: &str) -> Result<Vec<String>, ErroStorage> {
+        let token = token_gcs()?;
+        let mut chaves = Vec::new();
+        let mut pagina: Option<String> = None;
+        loop {
+            crate::throttle::aguardar().await;
+            let mut consulta = vec![("prefix", prefix.to_string()), ("fields", "items(name),nextPageToken".to_string())];
+            if let Some(p) = &pagina { consulta.push(("pageToken", p.clone())); }
+            let resp: serde_json::Value = cliente_http()
+                .get(format!("{}/storage/v1/b/{}/o", GCS_BASE, self.bucket))
+                .bearer_auth(&token)
+                .query(&consulta)
+                .send().await?.error_for_status()?
+                .json().await?;
+            if let Some(itens) = resp["items"].as_array() {
+                chaves.extend(itens.iter().filter_map(|i| i["name"].as_str().map(|n| n.to_string())));
+            }
+            match resp["nextPageToken"].as_str() {
+                Some(p) => pagina = Some(p.to_string()),
+                None => break,
+            }
+        }
+        Ok(chaves)
+    }
+
+    async fn baixar(&self, key: &str) -> Result<Option<Vec<u8>>, ErroStorage> {
+        let resp = cliente_http()
+            .get(format!("{}/{}/{}", GCS_BASE, self.bucket, key))
+            .bearer_auth(token_gcs()?)
+            .send().await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND { return Ok(None); }
+        Ok(Some(resp.error_for_status()?.bytes().await?.to_vec()))
+    }
+}
+
+// --- GITHUB PAGES (REPOSITÓRIO GIT) ---
+// Cada envio vira um commit pela API de conteúdo do GitHub, na branch e pasta
+// configuradas (ex.: github:dgslot/resultados@gh-pages/ ou @main/docs).
+// A listagem usa a árvore da branch, que vem inteira numa única chamada.
+
+struct GitHub {
+    repo: String,
+    branch: String,
+    pasta: String,
+}
+
+const GITHUB_API: &str = "https://api.github.com";
+const JSON_GITHUB: &str = "application/vnd.github+json";
+
+impl GitHub {
+    /// "<dono>/<repo>@<branch>[/<pasta>]"
+    fn interpretar(resto: &str) -> Option<GitHub> {
+        let (repo, destino) = resto.split_once('@')?;
+        let (branch, pasta) = destino.split_once('/').unwrap_or((destino, ""));
+        if !repo.contains('/') || branch.is_empty() { return None; }
+        Some(GitHub { repo: repo.to_string(), branch: branch.to_string(), pasta: pasta.trim_matches('/').to_string() })
+    }
+
+    fn caminho(&self, key: &str) -> String {
+        if self.pasta.is_empty() { key.to_string() } else { format!("{}/{}", self.pasta, key) }
+    }
+
+    fn requisicao(&self, metodo: reqwest::Method, url: String, accept: &str) -> Result<reqwest::RequestBuilder, ErroStorage> {
+        let token = env::var("GITHUB_TOKEN").map_err(|_| "GITHUB_TOKEN não definida")?;
+        Ok(cliente_http().request(metodo, url)
+            .bearer_auth(token)
+            .header("User-Agent", "raceday")
+            .header("Accept", accept))
+    }
+
+    /// Metadados do arquivo na branch (o `sha` é exigido para sobrescrever).
+    async fn metadados(&self, key: &str) -> Result<Option<serde_json::Value>, ErroStorage> {
+        let url = format!("{}/repos/{}/contents/{}", GITHUB_API, self.repo, self.caminho(key));
+        let resp = self.requisicao(reqwest::Method::GET, url, JSON_GITHUB)?.query(&[("ref", &self.branch)]).send().await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND { return Ok(None); }
+        Ok(Some(resp.error_for_status()?.json().await?))
+    }
+}
+
+#[async_trait]
+impl Storage for GitHub {
+    fn nome(&self) -> String {
+        format!("github:{}@{}", self.repo, self.branch)
+    }
+
+    async fn enviar(&self, file_path: &Path, key: &str, _content_type: &str, _cache_control: Option<&str>) -> Result<(), ErroStorage> {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+        let conteudo = fs::read(file_path)?;
+        let mut corpo = serde_json::json!({
+            "message": format!("RaceDay: {}", key),
+            "content": STANDARD.encode(&conteudo),
+            "branch": self.branch,
+        });
+        if let Some(atual) = self.metadados(key).await? {
+            // Mesmo blob: evita um commit vazio a cada reprocessamento
+            if atual["size"].as_u64() == Some(conteudo.len() as u64)
+                && self.baixar(key).await?.as_deref() == Some(conteudo.as_slice()) {
+                return Ok(());
+            }
+            corpo["sha"] = atual["sha"].clone();
+        }
+        let url = format!("{}/repos/{}/contents/{}", GITHUB_API, self.repo, self.caminho(key));
+        self.requisicao(reqwest::Method::PUT, url, JSON_GITHUB)?.json(&corpo).send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    async fn listar(&self, prefix: &str) -> Result<Vec<String>, ErroStorage> {
+        let url = format!("{}/repos/{}/git/trees/{}", GITHUB_API, self.repo, self.branch);
+        let resp = self.requisicao(reqwest::Method::GET, url, JSON_GITHUB)?.query(&[("recursive", "1")]).send().await?;
+        // Branch ainda inexistente: nada publicado
+        if resp.status() == reqwest::StatusCode::NOT_FOUND { return Ok(Vec::new()); }
+        let arvore: serde_json::Value = resp.error_for_status()?.json().await?;
+        let base = if self.pasta.is_empty() { String::new() } else { format!("{}/", self.pasta) };
+        let mut chaves: Vec<String> = arvore["tree"].as_array().into_iter().flatten()
+            .filter(|item| item["type"] == "blob")
+            .filter_map(|item| item["path"].as_str()?.strip_prefix(base.as_str()).map(|k| k.to_string()))
+            .filter(|k| k.starts_with(prefix))
+            .collect();
+        chaves.sort();
+        Ok(chaves)
+    }
+
+    async fn baixar(&self, key: &str) -> Result<Option<Vec<u8>>, ErroStorage> {
+        let url = format!("{}/repos/{}/contents/{}", GITHUB_API, self.repo, self.caminho(key));
+        // O media type "raw" devolve o arquivo em si, sem o limite de 1 MB do base64
+        let resp = self.requisicao(reqwest::Method::GET, url, "application/vnd.github.raw+json")?
+            .query(&[("ref", &self.branch)])
+            .send().await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND { return Ok(None); }
+        Ok(Some(resp.error_for_status()?.bytes().await?.to_vec()))
+    }
+}
@@ -0,0 +1,68 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::pipeline::{self, Extras, ResultadoProva};
+
+// --- LOTE: PASTA INTEIRA DE .INI ---
+// Uso: raceday process --dir ./exports
+// Depois de um fim de semana de provas: processa e publica cada .INI da pasta
+// (ordem alfabética, que nos exports do cronometrador é a ordem cronológica).
+// Um arquivo com erro não interrompe os demais; o resumo sai no final.
+
+struct Resultado {
+    arquivo: String,
+    segundos: f64,
+    erro: Option<String>,
+}
+
+/// Arquivos .ini/.INI da pasta (sem entrar em subpastas), em ordem.
+pub fn descobrir(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut arquivos: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("❌ Não foi possível ler a pasta {}: {}", dir.display(), e))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e.eq_ignore_ascii_case("ini")))
+        .collect();
+    arquivos.sort();
+    Ok(arquivos)
+}
+
+/// Processa a pasta e devolve o que foi publicado, na ordem dos arquivos.
+pub async fn executar(dir: &Path, club: &str, track: &str) -> Result<Vec<ResultadoProva>, Box<dyn Error>> {
+    let arquivos = descobrir(dir)?;
+    if arquivos.is_empty() {
+        return Err(format!("❌ Nenhum arquivo .INI em {}", dir.display()).into());
+    }
+    saida!("📦 {} arquivo(s) .INI em {}", arquivos.len(), dir.display());
+
+    let mut resultados = Vec::new();
+    let mut publicadas = Vec::new();
+    for (i, arquivo) in arquivos.iter().enumerate() {
+        let nome = arquivo.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        saida!("\n🏁 [{}/{}] {}", i + 1, arquivos.len(), nome);
+        let inicio = Instant::now();
+        let erro = match pipeline::processar(arquivo, club, track, &Extras::default()).await {
+            Ok(publicada) => { publicadas.push(publicada); None }
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(e) = &erro { erro!("{}", e); }
+        resultados.push(Resultado { arquivo: nome, segundos: inicio.elapsed().as_secs_f64(), erro });
+    }
+
+    let largura = resultados.iter().map(|r| r.arquivo.chars().count()).max().unwrap_or(0);
+    saida!("\n📋 Resumo do lote:");
+    for r in &resultados {
+        match &r.erro {
+            None => saida!("   ✅ {:<largura$}  {:>6.1}s", r.arquivo, r.segundos),
+            Some(e) => saida!("   ❌ {:<largura$}  {:>6.1}s  {}", r.arquivo, r.segundos, e.lines().next().unwrap_or("")),
+        }
+    }
+    let falhas = resultados.iter().filter(|r| r.erro.is_some()).count();
+    saida!("📊 {} ok, {} com falha", resultados.len() - falhas, falhas);
+
+    if falhas > 0 {
+        return Err(format!("⚠️ {} de {} arquivo(s) falharam", falhas, resultados.len()).into());
+    }
+    Ok(publicadas)
+}
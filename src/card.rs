@@ -0,0 +1,97 @@
+use image::GenericImageView;
+use plotters::prelude::*;
+use plotters::style::FontStyle;
+use serde_json::Value;
+use std::error::Error;
+use std::path::Path;
+
+use crate::i18n::{t, tf};
+use crate::ranking::PilotoDisplay;
+
+// --- CARD DO RESULTADO PARA REDES SOCIAIS ---
+// PNG quadrado (1080x1080, o formato do Instagram) com o logo do clube, pista,
+// pódio e melhor volta, publicado em <base>_card.png ao lado do relatório.
+// O logo é o do tema (theme.rs: CLUB_LOGO ou o logo.png da pasta de assets);
+// sem logo o card sai só com os textos.
+
+const LADO: u32 = 1080;
+const LOGO: u32 = 180;
+const FUNDO: RGBColor = RGBColor(15, 23, 42);
+const CINZA: RGBColor = RGBColor(148, 163, 184);
+const MEDALHAS: [RGBColor; 3] = [RGBColor(234, 179, 8), RGBColor(203, 213, 225), RGBColor(180, 83, 9)];
+
+type Area<'a> = DrawingArea<BitMapBackend<'a>, plotters::coord::Shift>;
+
+/// Desenha o logo redimensionado no canto superior esquerdo, pixel a pixel
+/// (respeitando a transparência sobre o fundo).
+fn desenhar_logo(area: &Area, caminho: &Path) -> Result<(), Box<dyn Error>> {
+    let logo = image::open(caminho)?.thumbnail(LOGO, LOGO);
+    for (x, y, pixel) in logo.pixels() {
+        let [r, g, b, a] = pixel.0;
+        if a < 16 { continue; }
+        let alfa = a as f64 / 255.0;
+        let mistura = |c: u8, f: u8| (c as f64 * alfa + f as f64 * (1.0 - alfa)) as u8;
+        area.draw_pixel((60 + x as i32, 60 + y as i32), &RGBColor(mistura(r, FUNDO.0), mistura(g, FUNDO.1), mistura(b, FUNDO.2)))?;
+    }
+    Ok(())
+}
+
+pub fn gerar(data: &Value, ranking: &[PilotoDisplay], club: &str, track: &str, destino: &Path) -> Result<(), Box<dyn Error>> {
+    let area = BitMapBackend::new(destino, (LADO, LADO)).into_drawing_area();
+    area.fill(&FUNDO)?;
+
+    let texto = |tamanho: f64, cor: &RGBColor, negrito: bool| {
+        let fonte = ("sans-serif", tamanho).into_font();
+        let fonte = if negrito { fonte.style(FontStyle::Bold) } else { fonte };
+        fonte.color(cor)
+    };
+
+    // Cabeçalho: logo (se houver) e clube / pista / data
+    let x_cabecalho = match crate::theme::arquivo_logo() {
+        Some(logo) => match desenhar_logo(&area, &logo) {
+            Ok(()) => 60 + LOGO as i32 + 40,
+            Err(e) => {
+                erro!("⚠️ Logo ignorado no card ({}): {}", logo.display(), e);
+                60
+            }
+        },
+        None => 60,
+    };
+    area.draw(&Text::new(club.to_string(), (x_cabecalho, 75), texto(46.0, &WHITE, true)))?;
+    area.draw(&Text::new(track.to_string(), (x_cabecalho, 135), texto(34.0, &CINZA, false)))?;
+    if let Some(date) = data["event"]["date"].as_str().filter(|d| !d.is_empty()) {
+        area.draw(&Text::new(date.to_string(), (x_cabecalho, 185), texto(28.0, &CINZA, false)))?;
+    }
+    area.draw(&Text::new(data["event"]["title"].as_str().map(|s| s.to_string()).unwrap_or_else(|| t("texts.race")), (60, 300), texto(56.0, &WHITE, true)))?;
+
+    // Pódio
+    for (i, (p, cor)) in ranking.iter().zip(MEDALHAS).enumerate() {
+        let y = 420 + i as i32 * 150;
+        area.draw(&Rectangle::new([(60, y), (80, y + 110)], cor.filled()))?;
+        area.draw(&Text::new(format!("{}º", i + 1), (110, y + 15), texto(60.0, &cor, true)))?;
+        area.draw(&Text::new(p.nome.clone(), (230, y + 10), texto(50.0, &WHITE, true)))?;
+        area.draw(&Text::new(tf("texts.n_laps", &[("voltas", p.total_laps.to_string())]), (230, y + 70), texto(30.0, &CINZA, false)))?;
+    }
+
+    // Melhor volta
+    if let Some(top) = ranking.iter().find(|p| p.is_overall_best) {
+        area.draw(&Rectangle::new([(60, 890), (LADO as i32 - 60, 892)], CINZA.filled()))?;
+        area.draw(&Text::new(t("texts.best_lap").to_uppercase(), (60, 920), texto(26.0, &CINZA, true)))?;
+        area.draw(&Text::new(format!("{}s  •  {}  •  {}", top.best_time, top.nome, top.best_slot_name), (60, 960), texto(40.0, &WHITE, true)))?;
+    }
+    area.present()?;
+    Ok(())
+}
+
+/// Gera e publica <base>_card.png. Retorna a chave.
+pub async fn publicar(data: &Value, ranking: &[PilotoDisplay], club: &str, track: &str, r2_key_base: &str) -> Result<String, Box<dyn Error>> {
+    let local = crate::pasta_saida().join("last_card.png");
+    gerar(data, ranking, club, track, &local)?;
+    let key = chave(r2_key_base);
+    crate::storage::upload_to_r2(&local, &key).await?;
+    Ok(key)
+}
+
+pub fn chave(r2_key_base: &str) -> String {
+    format!("{}_card.png", r2_key_base)
+}
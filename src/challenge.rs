@@ -0,0 +1,135 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::env;
+use std::error::Error;
+use std::fs;
+use tera::{Context, Tera};
+
+use crate::federation::{baixar_json, chave_api, fonte};
+use crate::pilots::slug_piloto;
+
+// --- DESAFIO ENTRE CLUBES ---
+// Uso:
+//   raceday challenge --home "DG Slot=https://pub-aaa.r2.dev" --home-race 20250614200000 \
+//                     --away "Clube B=https://pub-bbb.r2.dev" --away-race 20250621193000 \
+//                     [--title "Desafio DG x B"] [--away-target s3:bucket-b@https://...]
+// Cada lado pode informar várias provas separadas por vírgula; cada piloto entra
+// com o melhor índice de performance. Os pilotos dos dois clubes são ordenados
+// juntos e pontuam como em uma prova única (10, 8, 6, 5, 4, 3, 2, 1).
+// O relatório vai para o R2 deste clube e para o destino do outro clube
+// (--away-target ou CHALLENGE_AWAY_TARGET, mesmo formato de REPLICA_TARGETS).
+
+const PONTOS: [u32; 8] = [10, 8, 6, 5, 4, 3, 2, 1];
+
+#[derive(Serialize, Clone)]
+struct PilotoDesafio {
+    posicao: usize,
+    nome: String,
+    clube: String,
+    indice: f64,
+    melhor_volta: f64,
+    pontos: u32,
+}
+
+#[derive(Serialize)]
+struct Lado {
+    nome: String,
+    url: String,
+    provas: Vec<String>,
+    pontos: u32,
+    destaques: Vec<PilotoDesafio>,
+}
+
+/// Baixa as provas do lado e devolve (título das provas, melhor resultado de cada piloto).
+async fn carregar_lado(client: &reqwest::Client, clube: &str, base: &str, provas: &str) -> Result<(Vec<String>, Vec<PilotoDesafio>), Box<dyn Error>> {
+    let indice = baixar_json(client, &format!("{}/index.json", base)).await
+        .map_err(|e| format!("{}: não foi possível ler o index.json ({})", clube, e))?;
+    let mut titulos = Vec::new();
+    let mut pilotos: Vec<PilotoDesafio> = Vec::new();
+    for ts in provas.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        let item = indice.as_array().into_iter().flatten()
+            .find(|i| i["timestamp"].as_str() == Some(ts))
+            .ok_or_else(|| format!("{}: prova {} não está no index.json", clube, ts))?;
+        let prova: Value = baixar_json(client, &format!("{}/{}", base, chave_api(item))).await
+            .map_err(|e| format!("{}: prova {} indisponível ({})", clube, ts, e))?;
+        titulos.push(prova["event"]["title"].as_str().unwrap_or(ts).to_string());
+        for linha in prova["ranking"].as_array().into_iter().flatten() {
+            let Some(nome) = linha["name"].as_str() else { continue };
+            let indice = linha["performance_index"].as_f64().unwrap_or(0.0);
+            let melhor_volta = linha["best_lap"].as_f64().unwrap_or(0.0);
+            match pilotos.iter_mut().find(|p| p.nome == nome) {
+                Some(p) if p.indice >= indice => {}
+                Some(p) => { p.indice = indice; p.melhor_volta = melhor_volta; }
+                None => pilotos.push(PilotoDesafio { posicao: 0, nome: nome.to_string(), clube: clube.to_string(), indice, melhor_volta, pontos: 0 }),
+            }
+        }
+    }
+    Ok((titulos, pilotos))
+}
+
+/// `casa`/`fora`: "Clube=https://url-publica"; `provas_*`: timestamps separados por vírgula.
+pub async fn executar(casa: &str, provas_casa: &str, fora: &str, provas_fora: &str, titulo: Option<&str>, destino_fora: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let invalido = |lado: &str| format!("❌ {} inválido: use \"Clube=https://url-publica\"", lado);
+    let (casa, base_casa) = fonte(casa).ok_or_else(|| invalido("--home"))?;
+    let (fora, base_fora) = fonte(fora).ok_or_else(|| invalido("--away"))?;
+    let titulo = titulo.map(|t| t.to_string()).unwrap_or_else(|| format!("{} x {}", casa, fora));
+    let destino_fora = destino_fora.map(|d| d.to_string()).or_else(|| env::var("CHALLENGE_AWAY_TARGET").ok());
+
+    let client = reqwest::Client::new();
+    let (titulos_casa, pilotos_casa) = carregar_lado(&client, &casa, &base_casa, provas_casa).await?;
+    let (titulos_fora, pilotos_fora) = carregar_lado(&client, &fora, &base_fora, provas_fora).await?;
+
+    let mut geral: Vec<PilotoDesafio> = pilotos_casa.into_iter().chain(pilotos_fora).collect();
+    geral.sort_by(|a, b| b.indice.partial_cmp(&a.indice).unwrap_or(std::cmp::Ordering::Equal));
+    for (i, p) in geral.iter_mut().enumerate() {
+        p.posicao = i + 1;
+        p.pontos = PONTOS.get(i).copied().unwrap_or(0);
+    }
+
+    let lado = |nome: &str, url: &str, provas: Vec<String>| Lado {
+        nome: nome.to_string(),
+        url: url.to_string(),
+        provas,
+        pontos: geral.iter().filter(|p| p.clube == nome).map(|p| p.pontos).sum(),
+        destaques: geral.iter().filter(|p| p.clube == nome).take(3).cloned().collect(),
+    };
+    let lados = [lado(&casa, &base_casa, titulos_casa), lado(&fora, &base_fora, titulos_fora)];
+    let vencedor = match lados[0].pontos.cmp(&lados[1].pontos) {
+        std::cmp::Ordering::Greater => Some(casa.clone()),
+        std::cmp::Ordering::Less => Some(fora.clone()),
+        std::cmp::Ordering::Equal => None,
+    };
+
+    crate::assets::publicar().await?;
+    let mut tera = Tera::default();
+    crate::assets::registrar(&mut tera);
+    crate::templates::carregar(&mut tera, "challenge.html", "challenge")?;
+    let mut context = Context::new();
+    context.insert("titulo", &titulo);
+    context.insert("lados", &lados);
+    context.insert("vencedor", &vencedor);
+    context.insert("classificacao", &geral);
+
+    let local_dir = crate::pasta_saida().join("challenge");
+    fs::create_dir_all(&local_dir)?;
+    let key = format!("challenge/{}.html", slug_piloto(&titulo));
+    let local = local_dir.join(format!("{}.html", slug_piloto(&titulo)));
+    fs::write(&local, tera.render("challenge", &context)?)?;
+
+    crate::storage::upload_to_r2(&local, &key).await?;
+    saida!("☁️ {}: {}/{}", casa, base_casa, key);
+    match destino_fora {
+        Some(destino) => {
+            crate::replication::enviar(&destino, &local, &key, "text/html").await
+                .map_err(|e| format!("falha ao enviar para {}: {}", fora, e))?;
+            saida!("☁️ {}: {}/{}", fora, base_fora, key);
+        }
+        None => erro!("⚠️ Sem --away-target/CHALLENGE_AWAY_TARGET: envie {} para {} manualmente", local.display(), fora),
+    }
+
+    match &vencedor {
+        Some(v) => saida!("🏆 {}: {} {} x {} {} — vitória de {}", titulo, casa, lados[0].pontos, lados[1].pontos, fora, v),
+        None => saida!("🏆 {}: empate em {} pontos", titulo, lados[0].pontos),
+    }
+    Ok(())
+}
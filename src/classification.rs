@@ -0,0 +1,218 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::env;
+use std::sync::OnceLock;
+
+use crate::history::ResultadoHistorico;
+use crate::ranking::PilotoDisplay;
+
+// --- CRITÉRIO DE CLASSIFICAÇÃO ---
+// Cada formato de prova classifica de um jeito. CLASSIFICATION (ou [classification]
+// mode no raceday.toml) escolhe o critério:
+//   laps      padrão: mais voltas; empate decidido pelo gap oficial (fração de volta)
+//   best_lap  tomada de tempo: melhor volta, do menor para o maior; sem volta válida vai para o fim
+//   points    pontos por bateria: em cada bateria os pilotos recebem HEAT_POINTS
+//             ("10,8,6,5,4,3", padrão a tabela do campeonato) pela posição em voltas
+//             naquela bateria; empate decidido pelas voltas totais; PENALTY_POINTS
+//             desconta (penalties.rs)
+// Critérios próprios implementam Classifier e entram em criterio().
+
+pub trait Classifier: Send + Sync {
+    fn nome(&self) -> &str;
+    /// Ordena o ranking do 1º ao último colocado.
+    fn classificar(&self, ranking: &mut [PilotoDisplay], data: &Value);
+    /// Reordena o resultado gravado no histórico depois de uma correção de voltas.
+    fn reclassificar(&self, resultados: &mut [ResultadoHistorico]) {
+        resultados.sort_by_key(|r| std::cmp::Reverse(r.total_laps));
+    }
+}
+
+fn comparar_f64(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+/// Mais voltas primeiro; com as mesmas voltas, o menor gap oficial.
+fn por_voltas(a: &PilotoDisplay, b: &PilotoDisplay) -> Ordering {
+    b.total_laps.cmp(&a.total_laps).then_with(|| comparar_f64(a.gap_laps, b.gap_laps))
+}
+
+pub struct VoltasGap;
+
+impl Classifier for VoltasGap {
+    fn nome(&self) -> &str { "laps" }
+
+    fn classificar(&self, ranking: &mut [PilotoDisplay], _: &Value) {
+        ranking.sort_by(por_voltas);
+    }
+}
+
+pub struct MelhorVolta;
+
+impl Classifier for MelhorVolta {
+    fn nome(&self) -> &str { "best_lap" }
+
+    fn classificar(&self, ranking: &mut [PilotoDisplay], _: &Value) {
+        // best_lap_secs é 0 sem volta válida: fica depois de todos os tempos
+        let chave = |p: &PilotoDisplay| if p.best_lap_secs > 0.0 { p.best_lap_secs } else { f64::MAX };
+        ranking.sort_by(|a, b| comparar_f64(chave(a), chave(b)).then_with(|| por_voltas(a, b)));
+    }
+
+    fn reclassificar(&self, resultados: &mut [ResultadoHistorico]) {
+        let chave = |r: &ResultadoHistorico| if r.best_lap > 0.0 { r.best_lap } else { f64::MAX };
+        resultados.sort_by(|a, b| comparar_f64(chave(a), chave(b)).then_with(|| b.total_laps.cmp(&a.total_laps)));
+    }
+}
+
+pub struct PontosBateria {
+    pub pontos: Vec<f64>,
+}
+
+impl PontosBateria {
+    /// Pontos de cada piloto (pelo nome): posição em voltas em cada bateria.
+    fn somar(&self, data: &Value) -> HashMap<String, f64> {
+        let mut totais: HashMap<String, f64> = HashMap::new();
+        for race in data["races"].as_array().into_iter().flatten() {
+            let mut voltas: HashMap<String, i64> = HashMap::new();
+            for slots in race["sessions"].as_array().into_iter().flatten().filter_map(|s| s["slots"].as_object()) {
+                for s_data in slots.values() {
+                    let id = s_data["p_id"].as_str().map(|s| s.to_string()).unwrap_or_else(|| s_data["p_id"].to_string());
+                    *voltas.entry(id).or_default() += s_data["laps"].as_i64().unwrap_or(0);
+                }
+            }
+            for (id, l) in &voltas {
+                if *l <= 0 { continue; }
+                let posicao = voltas.values().filter(|outro| *outro > l).count();
+                let nome = data["pilots"][id]["name"].as_str().unwrap_or("---").to_string();
+                *totais.entry(nome).or_default() += self.pontos.get(posicao).copied().unwrap_or(0.0);
+            }
+        }
+        totais
+    }
+}
+
+impl Classifier for PontosBateria {
+    fn nome(&self) -> &str { "points" }
+
+    fn classificar(&self, ranking: &mut [PilotoDisplay], data: &Value) {
+        let totais = self.somar(data);
+        for p in ranking.iter_mut() {
+            p.pontos_baterias = totais.get(&p.nome).copied().unwrap_or(0.0) - p.penalidade.pontos;
+        }
+        ranking.sort_by(|a, b| comparar_f64(b.pontos_baterias, a.pontos_baterias).then_with(|| por_voltas(a, b)));
+    }
+
+    /// Os pontos das baterias não mudam com a correção: a ordem fica como está.
+    fn reclassificar(&self, _: &mut [ResultadoHistorico]) {}
+}
+
+/// HEAT_POINTS ("10,8,6,..."); sem ela, a tabela do campeonato (config::pontuacao).
+fn pontos_bateria() -> Vec<f64> {
+    env::var("HEAT_POINTS").ok()
+        .map(|v| v.split(',').filter_map(|p| p.trim().parse::<f64>().ok()).collect::<Vec<_>>())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| crate::config::pontuacao().posicoes)
+}
+
+/// Critério configurado em CLASSIFICATION (padrão: laps).
+pub fn criterio() -> &'static dyn Classifier {
+    static CRITERIO: OnceLock<Box<dyn Classifier>> = OnceLock::new();
+    CRITERIO.get_or_init(|| {
+        let modo = env::var("CLASSIFICATION").unwrap_or_default();
+        match modo.trim() {
+            "" | "laps" => Box::new(VoltasGap),
+            "best_lap" => Box::new(MelhorVolta),
+            "points" => Box::new(PontosBateria { pontos: pontos_bateria() }),
+            outro => {
+                erro!("⚠️ CLASSIFICATION desconhecida: '{}' (laps, best_lap ou points); usando laps", outro);
+                Box::new(VoltasGap)
+            }
+        }
+    }).as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn piloto(nome: &str, total_laps: i64, gap_laps: f64, best_lap_secs: f64) -> PilotoDisplay {
+        PilotoDisplay { nome: nome.to_string(), total_laps, gap_laps, best_lap_secs, ..Default::default() }
+    }
+
+    fn nomes(ranking: &[PilotoDisplay]) -> Vec<&str> {
+        ranking.iter().map(|p| p.nome.as_str()).collect()
+    }
+
+    fn resultado(nome: &str, total_laps: i64, best_lap: f64) -> ResultadoHistorico {
+        ResultadoHistorico {
+            nome: nome.to_string(),
+            posicao: 0,
+            total_laps,
+            best_lap,
+            indice: 0.0,
+            penalidades: 0,
+            provisorio: false,
+            convidado: false,
+            status: String::new(),
+            voltas: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn voltas_com_desempate_pelo_gap() {
+        let mut ranking = vec![piloto("Ana", 40, 0.5, 5.0), piloto("Bia", 41, 0.0, 5.5), piloto("Caio", 40, 0.2, 4.9)];
+        VoltasGap.classificar(&mut ranking, &Value::Null);
+        assert_eq!(nomes(&ranking), vec!["Bia", "Caio", "Ana"]);
+    }
+
+    #[test]
+    fn melhor_volta_sem_tempo_vai_para_o_fim() {
+        let mut ranking = vec![piloto("Ana", 40, 0.0, 0.0), piloto("Bia", 30, 0.0, 5.2), piloto("Caio", 35, 0.0, 5.1)];
+        MelhorVolta.classificar(&mut ranking, &Value::Null);
+        assert_eq!(nomes(&ranking), vec!["Caio", "Bia", "Ana"]);
+
+        let mut resultados = vec![resultado("Ana", 40, 0.0), resultado("Bia", 30, 5.2), resultado("Caio", 35, 5.1)];
+        MelhorVolta.reclassificar(&mut resultados);
+        assert_eq!(resultados.iter().map(|r| r.nome.as_str()).collect::<Vec<_>>(), vec!["Caio", "Bia", "Ana"]);
+    }
+
+    #[test]
+    fn pontos_por_bateria() {
+        // Bateria 1: Ana 20, Bia 18 (duas sessões), Caio 19; bateria 2: Caio 21, Bia 20, Ana 0
+        let data = json!({
+            "pilots": { "1": { "name": "Ana" }, "2": { "name": "Bia" }, "3": { "name": "Caio" } },
+            "races": [
+                { "sessions": [
+                    { "slots": { "1": { "p_id": "1", "laps": 20 }, "2": { "p_id": "2", "laps": 9 } } },
+                    { "slots": { "1": { "p_id": "3", "laps": 19 }, "2": { "p_id": "2", "laps": 9 } } },
+                ] },
+                { "sessions": [
+                    { "slots": { "1": { "p_id": "3", "laps": 21 }, "2": { "p_id": "2", "laps": 20 }, "3": { "p_id": "1", "laps": 0 } } },
+                ] },
+            ],
+        });
+        let criterio = PontosBateria { pontos: vec![10.0, 8.0, 6.0] };
+        let mut ranking = vec![piloto("Ana", 20, 0.0, 0.0), piloto("Bia", 38, 0.0, 0.0), piloto("Caio", 40, 0.0, 0.0)];
+        ranking[2].penalidade.pontos = 1.0;
+        criterio.classificar(&mut ranking, &data);
+
+        // Caio 8 + 10 - 1 = 17, Bia 6 + 8 = 14, Ana 10 (sem pontos com 0 voltas)
+        assert_eq!(nomes(&ranking), vec!["Caio", "Bia", "Ana"]);
+        assert_eq!(ranking.iter().map(|p| p.pontos_baterias).collect::<Vec<_>>(), vec![17.0, 14.0, 10.0]);
+    }
+
+    #[test]
+    fn pontos_empatados_decididos_pelas_voltas() {
+        let data = json!({
+            "pilots": { "1": { "name": "Ana" }, "2": { "name": "Bia" } },
+            "races": [
+                { "sessions": [{ "slots": { "1": { "p_id": "1", "laps": 10 }, "2": { "p_id": "2", "laps": 9 } } }] },
+                { "sessions": [{ "slots": { "1": { "p_id": "2", "laps": 12 }, "2": { "p_id": "1", "laps": 8 } } }] },
+            ],
+        });
+        let mut ranking = vec![piloto("Ana", 18, 0.0, 0.0), piloto("Bia", 21, 0.0, 0.0)];
+        PontosBateria { pontos: vec![5.0, 3.0] }.classificar(&mut ranking, &data);
+        assert_eq!(nomes(&ranking), vec!["Bia", "Ana"]);
+    }
+}
@@ -0,0 +1,192 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use tera::{Context, Tera};
+
+use crate::pilots::slug_piloto;
+use crate::storage::{chave_prova, slug_chave};
+
+// --- COMPARAÇÃO ENTRE DOIS PILOTOS (HEAD-TO-HEAD) ---
+// Uso: raceday compare --pilots "Ana,Bruno" (--race <timestamp> | --season 2025)
+// Lê os JSONs das provas no bucket (uma prova do clube/pista, ou todas do clube
+// no ano) e compara, fenda a fenda, voltas, melhor tempo e média de volta, além
+// de quem venceu cada bateria em que os dois correram juntos (mais voltas; no
+// empate, a melhor volta). Publica compare/<a>_x_<b>_<prova ou ano>.html.
+
+#[derive(Serialize, Default)]
+struct LadoFenda {
+    voltas: i64,
+    melhor: f64,
+    media: f64,
+    #[serde(skip)]
+    tempos: Vec<f64>,
+}
+
+impl LadoFenda {
+    fn somar(&mut self, s: &Value) {
+        self.voltas += s["laps"].as_i64().unwrap_or(0);
+        let best = s["best"].as_f64().unwrap_or(0.0);
+        if best > 0.0 && (self.melhor == 0.0 || best < self.melhor) { self.melhor = best; }
+        self.tempos.extend(s["lap_times"].as_array().into_iter().flatten().filter_map(|t| t.as_f64()).filter(|t| *t > 0.0));
+    }
+
+    fn fechar(&mut self) {
+        if !self.tempos.is_empty() {
+            self.media = self.tempos.iter().sum::<f64>() / self.tempos.len() as f64;
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LinhaFenda {
+    fenda: String,
+    a: LadoFenda,
+    b: LadoFenda,
+}
+
+#[derive(Serialize)]
+struct Duelo {
+    prova: String,
+    bateria: String,
+    sessao: u64,
+    voltas_a: i64,
+    voltas_b: i64,
+    vencedor: String,
+}
+
+/// Mais voltas vence; no empate, a melhor volta (menor tempo válido).
+fn vencedor_duelo<'a>(a: (&'a str, &Value), b: (&'a str, &Value)) -> &'a str {
+    let voltas = |s: &Value| s["laps"].as_i64().unwrap_or(0);
+    let melhor = |s: &Value| s["best"].as_f64().filter(|t| *t > 0.0).unwrap_or(f64::MAX);
+    match voltas(a.1).cmp(&voltas(b.1)) {
+        std::cmp::Ordering::Greater => a.0,
+        std::cmp::Ordering::Less => b.0,
+        std::cmp::Ordering::Equal if melhor(a.1) < melhor(b.1) => a.0,
+        std::cmp::Ordering::Equal if melhor(b.1) < melhor(a.1) => b.0,
+        std::cmp::Ordering::Equal => "Empate",
+    }
+}
+
+fn comparar(provas: &[Value], a: &str, b: &str) -> (Vec<LinhaFenda>, Vec<Duelo>) {
+    let fendas = crate::config::fendas_pista(provas.iter().map(crate::ranking::total_fendas).max().unwrap_or(0));
+    let mut por_fenda: BTreeMap<u32, (LadoFenda, LadoFenda)> = BTreeMap::new();
+    let mut duelos = Vec::new();
+
+    for data in provas {
+        let prova = data["event"]["title"].as_str().unwrap_or("Corrida");
+        for bateria in data["races"].as_array().into_iter().flatten() {
+            for sessao in bateria["sessions"].as_array().into_iter().flatten() {
+                let mut lado_a = None;
+                let mut lado_b = None;
+                for (fenda, s) in sessao["slots"].as_object().into_iter().flatten() {
+                    let nome = s["name"].as_str().unwrap_or("");
+                    let numero: u32 = fenda.parse().unwrap_or(0);
+                    if nome.eq_ignore_ascii_case(a) {
+                        por_fenda.entry(numero).or_default().0.somar(s);
+                        lado_a = Some(s);
+                    } else if nome.eq_ignore_ascii_case(b) {
+                        por_fenda.entry(numero).or_default().1.somar(s);
+                        lado_b = Some(s);
+                    }
+                }
+                if let (Some(sa), Some(sb)) = (lado_a, lado_b) {
+                    duelos.push(Duelo {
+                        prova: prova.to_string(),
+                        bateria: bateria["name"].as_str().unwrap_or("").to_string(),
+                        sessao: sessao["session"].as_u64().unwrap_or(0),
+                        voltas_a: sa["laps"].as_i64().unwrap_or(0),
+                        voltas_b: sb["laps"].as_i64().unwrap_or(0),
+                        vencedor: vencedor_duelo((a, sa), (b, sb)).to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    let linhas = por_fenda.into_iter().map(|(numero, (mut la, mut lb))| {
+        la.fechar();
+        lb.fechar();
+        let fenda = (numero as usize).checked_sub(1).and_then(|i| fendas.get(i))
+            .map(|f| f.nome.clone())
+            .unwrap_or_else(|| format!("Fenda {}", numero));
+        LinhaFenda { fenda, a: la, b: lb }
+    }).collect();
+    (linhas, duelos)
+}
+
+async fn carregar_provas(club: &str, track: Option<&str>, prova: Option<&str>, temporada: Option<&str>) -> Result<Vec<Value>, Box<dyn Error>> {
+    let chaves: Vec<String> = match (prova, temporada) {
+        (Some(ts), _) => {
+            let track = track.ok_or("❌ --race precisa da pista (--track ou TRACK)")?;
+            vec![chave_prova(club, track, ts)]
+        }
+        (None, Some(ano)) => {
+            let mut chaves: Vec<String> = crate::storage::listar_r2(&format!("races/{}_", slug_chave(club))).await?.into_iter()
+                .filter(|k| crate::season::timestamp_da_chave(k).is_some_and(|ts| ts.starts_with(ano)))
+                .collect();
+            chaves.sort_by(|x, y| crate::season::timestamp_da_chave(x).cmp(&crate::season::timestamp_da_chave(y)));
+            chaves
+        }
+        (None, None) => return Err("❌ Informe --race <timestamp> ou --season <ano>".into()),
+    };
+
+    let mut provas = Vec::new();
+    for key in &chaves {
+        let Some(bytes) = crate::storage::baixar_r2(key).await? else {
+            erro!("⚠️ Prova não encontrada no bucket: {}", key);
+            continue;
+        };
+        let mut data = crate::schema::ler_publicada(key, &bytes).map_err(|e| format!("❌ JSON inválido em {}: {}", key, e))?;
+        crate::privacy::anonimizar_prova(&mut data);
+        provas.push(data);
+    }
+    if provas.is_empty() { return Err("❌ Nenhuma prova para comparar".into()); }
+    Ok(provas)
+}
+
+pub async fn executar(pilotos: &str, club: &str, track: Option<&str>, prova: Option<&str>, temporada: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let nomes: Vec<&str> = pilotos.split(',').map(|n| n.trim()).filter(|n| !n.is_empty()).collect();
+    let [a, b] = nomes[..] else {
+        return Err("❌ Informe exatamente dois pilotos: --pilots \"Ana,Bruno\"".into());
+    };
+
+    let provas = carregar_provas(club, track, prova, temporada).await?;
+    let (fendas, duelos) = comparar(&provas, a, b);
+    if fendas.is_empty() {
+        return Err(format!("❌ Nem {} nem {} aparecem nas provas selecionadas", a, b).into());
+    }
+    let vitorias_a = duelos.iter().filter(|d| d.vencedor == a).count();
+    let vitorias_b = duelos.iter().filter(|d| d.vencedor == b).count();
+    let escopo = prova.map(|p| p.to_string()).or(temporada.map(|t| t.to_string())).unwrap_or_default();
+
+    saida!("🎯 {} x {} — {} prova(s), {} bateria(s) em comum: {} a {}", a, b, provas.len(), duelos.len(), vitorias_a, vitorias_b);
+    for f in &fendas {
+        saida!("   {:<10} {:>5} voltas / {:.3}s  x  {:>5} voltas / {:.3}s", f.fenda, f.a.voltas, f.a.melhor, f.b.voltas, f.b.melhor);
+    }
+
+    let mut tera = Tera::default();
+    crate::assets::registrar(&mut tera);
+    crate::templates::carregar(&mut tera, "compare.html", "compare")?;
+    let mut context = Context::new();
+    context.insert("club", club);
+    context.insert("a", a);
+    context.insert("b", b);
+    context.insert("escopo", &escopo);
+    context.insert("provas", &provas.iter().map(|p| p["event"]["title"].as_str().unwrap_or("Corrida")).collect::<Vec<_>>());
+    context.insert("fendas", &fendas);
+    context.insert("duelos", &duelos);
+    context.insert("vitorias_a", &vitorias_a);
+    context.insert("vitorias_b", &vitorias_b);
+
+    let nome = format!("{}_x_{}_{}", slug_piloto(a), slug_piloto(b), escopo);
+    let local_dir = crate::pasta_saida().join("compare");
+    fs::create_dir_all(&local_dir)?;
+    let local = local_dir.join(format!("{}.html", nome));
+    fs::write(&local, tera.render("compare", &context)?)?;
+    let key = format!("compare/{}.html", nome);
+    crate::storage::upload_to_r2(&local, &key).await?;
+    saida!("☁️ Comparação publicada: {}/{}", crate::refresh::base_url(), key);
+    Ok(())
+}
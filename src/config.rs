@@ -0,0 +1,338 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// --- ARQUIVO DE CONFIGURAÇÃO (raceday.toml) ---
+// Tudo no RaceDay é configurado por variáveis de ambiente; o raceday.toml só
+// preenche as que não estiverem definidas. Ordem de prioridade:
+//   opções da linha de comando > variáveis de ambiente > raceday.toml
+// O arquivo é procurado em RACEDAY_CONFIG (ou --config), na pasta atual e ao
+// lado do executável, para o clube rodar o binário com um duplo clique.
+//
+//   [club]
+//   name = "DG SLOT RACING"
+//   track = "ESPANHA"
+//   locale = "pt-BR"           # idioma dos relatórios: pt-BR, en ou es (ver i18n.rs)
+//
+//   [storage]
+//   endpoint = "https://<account>.r2.cloudflarestorage.com"
+//   bucket = "raceday-data"
+//
+//   [slots]
+//   names = ["Vermelha", "Branca", "Verde", "Laranja", "Azul", "Amarela"]
+//   colors = ["#ef4444", "#64748b", "#22c55e", "#f97316", "#3b82f6", "#eab308"]
+//
+//   [charts]
+//   colors = ["#FF6384", "#36A2EB", "#FFCE56"]   # uma por piloto, repetidas se faltarem
+//
+//   [theme]                    # identidade visual dos relatórios (ver theme.rs)
+//   logo = "C:/RaceDay/logo.png"
+//   primary = "#dc2626"
+//   secondary = "#1e293b"
+//   footer = "DG Slot Racing • Rua das Flores, 123"
+//
+//   [views]                    # relatórios extras, publicados em <base>_<nome>.html (ver views.rs)
+//   tv = "report_tv.html"
+//
+//   [aliases]                  # grafias do cronometrador -> nome do piloto (ver aliases.rs)
+//   "J. Silva" = "João Silva"
+//
+//   [championship]
+//   points = [25, 18, 15, 12, 10, 8, 6, 4, 2, 1]
+//   fastest_lap_bonus = 1
+//   drop_worst = 2             # conta só os melhores resultados (ex.: 8 de 10 etapas)
+//
+//   [classification]           # critério da classificação (ver classification.rs)
+//   mode = "points"            # laps (padrão), best_lap ou points
+//   heat_points = [10, 8, 6, 5, 4, 3]
+//
+//   [penalties]                # custo de cada penalidade (ver penalties.rs)
+//   laps = 1
+//   seconds = 5
+//   points = 2
+//
+//   [rating]                   # rating Elo dos pilotos (opcional)
+//   enabled = true
+//   k_factor = 32
+//   initial = 1500
+//
+//   [env]                      # qualquer outra variável (SMTP_HOST, AWS_ACCESS_KEY_ID, ...)
+//   INSIGHTS_STYLE = "formal"
+
+const ARQUIVO_PADRAO: &str = "raceday.toml";
+
+/// Pontos por posição quando CHAMPIONSHIP_POINTS não está definida (1º, 2º, ...).
+pub const PONTOS_PADRAO: [f64; 10] = [25.0, 18.0, 15.0, 12.0, 10.0, 8.0, 6.0, 4.0, 2.0, 1.0];
+
+/// Nomes padrão das fendas, na ordem da pista (fenda 1 = Vermelha).
+/// Cores padrão das fendas, na ordem dos nomes de locales/*.toml ("slots").
+/// Nomes próprios ou traduzidos não têm classe no CSS do relatório, então a cor vai explícita.
+pub const CORES_PADRAO: [&str; 8] = ["#ef4444", "#94a3b8", "#22c55e", "#f97316", "#3b82f6", "#eab308", "#a855f7", "#64748b"];
+
+/// Cores das linhas dos gráficos, uma por piloto na ordem do ranking.
+pub const CORES_GRAFICO_PADRAO: [&str; 16] = [
+    "#FF6384", "#36A2EB", "#FFCE56", "#4BC0C0", "#9966FF", "#FF9F40", "#8BC34A", "#000000",
+    "#E91E63", "#9C27B0", "#00BCD4", "#009688", "#CDDC39", "#FFEB3B", "#795548", "#607D8B"
+];
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Config {
+    pub club: Clube,
+    pub storage: Armazenamento,
+    pub slots: Fendas,
+    pub charts: Graficos,
+    pub theme: Tema,
+    pub views: BTreeMap<String, String>,
+    pub aliases: BTreeMap<String, String>,
+    pub paths: Caminhos,
+    pub championship: Campeonato,
+    pub classification: Classificacao,
+    pub penalties: Penalidades,
+    pub rating: Rating,
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Clube {
+    pub name: Option<String>,
+    pub track: Option<String>,
+    pub public_url: Option<String>,
+    pub locale: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Armazenamento {
+    pub backend: Option<String>,
+    pub endpoint: Option<String>,
+    pub bucket: Option<String>,
+    pub private_bucket: Option<String>,
+    pub render_sync_url: Option<String>,
+    pub remote_templates: Option<bool>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Fendas {
+    pub names: Vec<String>,
+    pub colors: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Graficos {
+    pub colors: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Tema {
+    pub logo: Option<String>,
+    pub primary: Option<String>,
+    pub secondary: Option<String>,
+    pub footer: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Caminhos {
+    pub templates: Option<PathBuf>,
+    pub out_dir: Option<PathBuf>,
+    pub history: Option<PathBuf>,
+    pub assets: Option<PathBuf>,
+    pub metrics_script: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Classificacao {
+    pub mode: Option<String>,
+    pub heat_points: Vec<f64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Campeonato {
+    pub points: Vec<f64>,
+    pub fastest_lap_bonus: Option<f64>,
+    pub drop_worst: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Penalidades {
+    pub laps: Option<i64>,
+    pub seconds: Option<f64>,
+    pub points: Option<f64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Rating {
+    pub enabled: Option<bool>,
+    pub k_factor: Option<f64>,
+    pub initial: Option<f64>,
+}
+
+/// Fenda da pista como aparece no relatório. `cor` vazia usa o CSS do template.
+#[derive(Serialize, Clone)]
+pub struct Fenda {
+    pub nome: String,
+    pub cor: String,
+}
+
+fn caminho_config() -> Option<PathBuf> {
+    match env::var_os("RACEDAY_CONFIG") {
+        Some(p) => Some(PathBuf::from(p)),
+        None => Some(crate::recurso(Path::new(ARQUIVO_PADRAO))).filter(|p| p.exists()),
+    }
+}
+
+pub fn carregar() -> Result<Option<(PathBuf, Config)>, Box<dyn Error>> {
+    let Some(caminho) = caminho_config() else { return Ok(None) };
+    let texto = fs::read_to_string(&caminho).map_err(|e| format!("❌ Não foi possível ler {}: {}", caminho.display(), e))?;
+    let config: Config = toml::from_str(&texto).map_err(|e| format!("❌ {} inválido: {}", caminho.display(), e))?;
+    Ok(Some((caminho, config)))
+}
+
+/// Variáveis de ambiente equivalentes ao arquivo.
+fn variaveis(config: &Config) -> Vec<(String, String)> {
+    let lista = |v: &[String]| (!v.is_empty()).then(|| v.join(","));
+    let caminho = |p: &Option<PathBuf>| p.as_ref().map(|p| p.to_string_lossy().to_string());
+    let mut vars: Vec<(String, String)> = [
+        ("CLUB", config.club.name.clone()),
+        ("TRACK", config.club.track.clone()),
+        ("PUBLIC_BASE_URL", config.club.public_url.clone()),
+        ("RACEDAY_LOCALE", config.club.locale.clone()),
+        ("STORAGE_BACKEND", config.storage.backend.clone()),
+        ("R2_ENDPOINT", config.storage.endpoint.clone()),
+        ("R2_BUCKET", config.storage.bucket.clone()),
+        ("R2_PRIVATE_BUCKET", config.storage.private_bucket.clone()),
+        ("RENDER_SYNC_URL", config.storage.render_sync_url.clone()),
+        ("TEMPLATES_REMOTE", config.storage.remote_templates.map(|a| a.to_string())),
+        ("SLOT_NAMES", lista(&config.slots.names)),
+        ("SLOT_COLORS", lista(&config.slots.colors)),
+        ("CHART_COLORS", lista(&config.charts.colors)),
+        ("CLUB_LOGO", config.theme.logo.clone()),
+        ("THEME_PRIMARY", config.theme.primary.clone()),
+        ("THEME_SECONDARY", config.theme.secondary.clone()),
+        ("THEME_FOOTER", config.theme.footer.clone()),
+        ("REPORT_VIEWS", (!config.views.is_empty())
+            .then(|| config.views.iter().map(|(nome, template)| format!("{}={}", nome, template)).collect::<Vec<_>>().join(","))),
+        ("PILOT_ALIASES_MAP", (!config.aliases.is_empty())
+            .then(|| config.aliases.iter().map(|(apelido, nome)| format!("{}={}", apelido, nome)).collect::<Vec<_>>().join(","))),
+        ("TEMPLATES_DIR", caminho(&config.paths.templates)),
+        ("RACEDAY_OUT_DIR", caminho(&config.paths.out_dir)),
+        ("HISTORY_PATH", caminho(&config.paths.history)),
+        ("ASSETS_DIR", caminho(&config.paths.assets)),
+        ("METRICS_SCRIPT", caminho(&config.paths.metrics_script)),
+        ("CHAMPIONSHIP_POINTS", (!config.championship.points.is_empty())
+            .then(|| config.championship.points.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(","))),
+        ("FASTEST_LAP_BONUS", config.championship.fastest_lap_bonus.map(|b| b.to_string())),
+        ("CHAMPIONSHIP_DROP_WORST", config.championship.drop_worst.map(|d| d.to_string())),
+        ("CLASSIFICATION", config.classification.mode.clone()),
+        ("HEAT_POINTS", (!config.classification.heat_points.is_empty())
+            .then(|| config.classification.heat_points.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(","))),
+        ("PENALTY_LAPS", config.penalties.laps.map(|l| l.to_string())),
+        ("PENALTY_SECONDS", config.penalties.seconds.map(|s| s.to_string())),
+        ("PENALTY_POINTS", config.penalties.points.map(|p| p.to_string())),
+        ("RATING_ENABLED", config.rating.enabled.map(|a| a.to_string())),
+        ("RATING_K", config.rating.k_factor.map(|k| k.to_string())),
+        ("RATING_INITIAL", config.rating.initial.map(|i| i.to_string())),
+    ].into_iter()
+        .filter_map(|(nome, valor)| valor.map(|v| (nome.to_string(), v)))
+        .collect();
+    vars.extend(config.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+    vars
+}
+
+/// Lê o raceday.toml e define as variáveis que ainda não existem no ambiente.
+///
+/// # Safety
+/// Altera o ambiente do processo: chame antes de criar threads (inclusive o runtime do tokio).
+pub unsafe fn aplicar_ambiente() -> Result<(), Box<dyn Error>> {
+    let Some((caminho, config)) = carregar()? else { return Ok(()) };
+    let mut aplicadas = 0;
+    for (nome, valor) in variaveis(&config) {
+        if env::var_os(&nome).is_some() { continue; }
+        // SAFETY: garantido por quem chama (ainda sem outras threads)
+        unsafe { env::set_var(&nome, &valor) };
+        aplicadas += 1;
+    }
+    saida!("ℹ️ Configuração: {} ({} valor(es) aplicado(s))", caminho.display(), aplicadas);
+    Ok(())
+}
+
+/// As `total` (ou mais) fendas da pista: SLOT_NAMES / SLOT_COLORS (listas separadas
+/// por vírgula), completadas com os nomes padrão do idioma (i18n, "slots"); as que
+/// passarem das duas listas viram "Fenda 9", "Fenda 10"... com cor gerada.
+pub fn fendas_pista(total: usize) -> Vec<Fenda> {
+    let lista = |var: &str| env::var(var).ok()
+        .map(|v| v.split(',').map(|s| s.trim().to_string()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let (nomes, cores) = (lista("SLOT_NAMES"), lista("SLOT_COLORS"));
+    let padrao = crate::i18n::lista("slots");
+    let nomes_css = crate::i18n::locale() == "pt-BR";
+    let total = total.max(padrao.len()).max(nomes.len());
+    (0..total).map(|i| {
+        let proprio = nomes.get(i).filter(|n| !n.is_empty()).cloned();
+        // Os nomes padrão em português já têm cor no CSS do template
+        let cor_padrao = match CORES_PADRAO.get(i) {
+            Some(c) => (!nomes_css || proprio.is_some() || i >= padrao.len()).then(|| c.to_string()),
+            None => Some(cor_gerada(i)),
+        };
+        Fenda {
+            nome: proprio.or_else(|| padrao.get(i).cloned())
+                .unwrap_or_else(|| format!("{} {}", crate::i18n::t("texts.slot"), i + 1)),
+            cor: cores.get(i).filter(|c| !c.is_empty()).cloned().or(cor_padrao).unwrap_or_default(),
+        }
+    }).collect()
+}
+
+/// Cor para fendas além das padrão: matizes espaçados pelo ângulo áureo,
+/// para fendas vizinhas não saírem parecidas.
+fn cor_gerada(i: usize) -> String {
+    format!("hsl({}, 70%, 50%)", (i * 137 + 20) % 360)
+}
+
+/// Cores dos gráficos: CHART_COLORS (lista separada por vírgula) ou as padrão.
+pub fn cores_grafico() -> Vec<String> {
+    let cores: Vec<String> = env::var("CHART_COLORS").ok()
+        .map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    if cores.is_empty() { CORES_GRAFICO_PADRAO.iter().map(|c| c.to_string()).collect() } else { cores }
+}
+
+/// Tabela de pontos do campeonato por prova.
+pub struct Pontuacao {
+    /// Pontos do 1º, 2º, ... colocado; quem passa do fim da tabela não pontua
+    pub posicoes: Vec<f64>,
+    /// Bônus para o dono da melhor volta da prova
+    pub melhor_volta: f64,
+    /// Piores resultados descartados na soma líquida da temporada
+    pub descartes: usize,
+}
+
+impl Pontuacao {
+    pub fn pontos(&self, posicao: usize, melhor_volta: bool) -> f64 {
+        let base = posicao.checked_sub(1).and_then(|i| self.posicoes.get(i)).copied().unwrap_or(0.0);
+        base + if melhor_volta { self.melhor_volta } else { 0.0 }
+    }
+}
+
+/// CHAMPIONSHIP_POINTS ("25,18,15,..."), FASTEST_LAP_BONUS e CHAMPIONSHIP_DROP_WORST;
+/// sem elas, PONTOS_PADRAO, sem bônus e sem descartes.
+pub fn pontuacao() -> Pontuacao {
+    let posicoes = env::var("CHAMPIONSHIP_POINTS").ok()
+        .map(|v| v.split(',').filter_map(|p| p.trim().parse::<f64>().ok()).collect::<Vec<_>>())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| PONTOS_PADRAO.to_vec());
+    let melhor_volta = env::var("FASTEST_LAP_BONUS").ok().and_then(|v| v.trim().parse().ok()).unwrap_or(0.0);
+    let descartes = env::var("CHAMPIONSHIP_DROP_WORST").ok().and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+    Pontuacao { posicoes, melhor_volta, descartes }
+}
@@ -0,0 +1,174 @@
+use std::env;
+use std::fmt;
+use std::future::Future;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Instrument, Level, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::writer::MakeWriterExt;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::EnvFilter;
+
+#[doc(hidden)]
+pub use tracing;
+
+// --- SAÍDA DO CONSOLE ---
+// Em modo texto puro (--plain) os emojis viram prefixos ASCII, para não embaralhar
+// consoles do Windows nem o journal do systemd. Liga sozinho quando a saída não é
+// um terminal UTF-8.
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+const PREFIXOS: [(&str, &str); 38] = [
+    ("✅", "[OK]"), ("❌", "[ERRO]"), ("⚠️", "[AVISO]"), ("ℹ️", "[INFO]"),
+    ("🚀", "[PREP]"), ("📄", "[HTML]"), ("☁️", "[R2]"), ("🔔", "[SYNC]"),
+    ("✨", "[FIM]"), ("📚", "[HIST]"), ("👤", "[PILOTOS]"), ("👮", "[FISCAIS]"),
+    ("📣", "[WEBHOOK]"), ("📝", "[DRY-RUN]"), ("📧", "[EMAIL]"), ("🔁", "[REBUILD]"),
+    ("⏭️", "[SKIP]"), ("🏠", "[PORTAL]"), ("📸", "[FOTOS]"), ("🔥", "*"),
+    ("🏆", "*"), ("🛡️", "*"), ("🎯", "*"), ("🏁", "*"),
+    ("⏱️", "*"), ("📬", ""), ("—", "-"), ("📋", "[RESUMO]"), ("🌐", "[SERVE]"),
+    ("📦", "[EXPORT]"), ("🗑️", "[PURGE]"), ("💰", "[TAXAS]"), ("📊", "[TOTAL]"),
+    ("👀", "[VIGIA]"), ("📴", "[OFFLINE]"), ("📈", "[RECORDE]"), ("🖨️", "[IMPRESSAO]"),
+    ("📺", "[VISAO]"),
+];
+
+fn terminal_utf8() -> bool {
+    if !std::io::stdout().is_terminal() { return false; }
+    if cfg!(target_os = "windows") {
+        // O console clássico do Windows não é UTF-8; o Windows Terminal é
+        return env::var("WT_SESSION").is_ok();
+    }
+    ["LC_ALL", "LC_CTYPE", "LANG"].iter()
+        .find_map(|v| env::var(v).ok().filter(|s| !s.is_empty()))
+        .map(|s| { let s = s.to_lowercase(); s.contains("utf-8") || s.contains("utf8") })
+        .unwrap_or(false)
+}
+
+pub fn configurar(args: &[String]) {
+    let plain = args.iter().any(|a| a == "--plain") || env::var("RACEDAY_PLAIN").is_ok() || !terminal_utf8();
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+pub fn texto(mensagem: &str) -> String {
+    if !PLAIN.load(Ordering::Relaxed) { return mensagem.to_string(); }
+    let mut saida = mensagem.to_string();
+    for (emoji, ascii) in PREFIXOS {
+        saida = saida.replace(emoji, ascii);
+    }
+    // Remove o que sobrar de símbolos (emojis, seletores de variação), mantendo letras acentuadas
+    saida.chars().filter(|c| c.is_ascii() || c.is_alphabetic()).collect::<String>().replace("  ", " ")
+}
+
+// --- LOG (TRACING) ---
+// saida! e erro! viram eventos do tracing: informação na saída padrão, avisos e
+// erros na saída de erro. Cada etapa do processamento é um span com o tempo gasto.
+//   --quiet      só avisos e erros
+//   (padrão)     as mensagens de sempre, sem data nem nível
+//   --verbose    também os detalhes (debug), com horário, nível e a etapa de cada linha
+// RUST_LOG, se definida, prevalece (ex.: RUST_LOG=raceday=debug,aws_config=info).
+// Com --log-format json (RACEDAY_LOG_FORMAT=json) cada evento é uma linha JSON na
+// saída de erro, com a etapa, a duração, a chave enviada e o nível, para scripts e
+// o painel do quiosque; os detalhes (debug) entram mesmo sem --verbose.
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Nivel {
+    Quieto,
+    Normal,
+    Detalhado,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Formato {
+    Texto,
+    Json,
+}
+
+impl Formato {
+    /// --log-format, ou RACEDAY_LOG_FORMAT; "text" por padrão.
+    pub fn de(opcao: Option<&str>) -> Result<Formato, String> {
+        match opcao.map(str::to_string).or_else(|| env::var("RACEDAY_LOG_FORMAT").ok()).as_deref() {
+            None | Some("") | Some("text") => Ok(Formato::Texto),
+            Some("json") => Ok(Formato::Json),
+            Some(outro) => Err(format!("❌ Formato de log inválido: '{}' (text ou json)", outro)),
+        }
+    }
+}
+
+/// Só o texto da mensagem, como era antes do tracing.
+struct SoMensagem;
+
+struct Mensagem(String);
+
+impl Visit for Mensagem {
+    fn record_debug(&mut self, campo: &Field, valor: &dyn fmt::Debug) {
+        if campo.name() == "message" {
+            self.0 = format!("{:?}", valor);
+        }
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for SoMensagem
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, _ctx: &FmtContext<'_, S, N>, mut escritor: Writer<'_>, evento: &Event<'_>) -> fmt::Result {
+        let mut mensagem = Mensagem(String::new());
+        evento.record(&mut mensagem);
+        writeln!(escritor, "{}", mensagem.0)
+    }
+}
+
+pub fn iniciar_log(nivel: Nivel, formato: Formato) {
+    let padrao = match (nivel, formato) {
+        (Nivel::Quieto, _) => "warn",
+        (Nivel::Normal, Formato::Texto) => "warn,raceday=info",
+        (Nivel::Normal, Formato::Json) => "warn,raceday=debug",
+        (Nivel::Detalhado, _) => "info,raceday=debug",
+    };
+    let filtro = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(padrao));
+    let base = tracing_subscriber::fmt().with_env_filter(filtro).with_ansi(false);
+    // Se um subscriber já estiver instalado (biblioteca embutida), fica o dele
+    let _ = match (formato, nivel) {
+        (Formato::Json, _) => base.with_writer(std::io::stderr)
+            .json().flatten_event(true).with_current_span(true).with_span_list(false)
+            .try_init(),
+        (Formato::Texto, Nivel::Detalhado) => base.with_writer(std::io::stderr.with_max_level(Level::WARN).or_else(std::io::stdout))
+            .with_target(false)
+            .try_init(),
+        (Formato::Texto, _) => base.with_writer(std::io::stderr.with_max_level(Level::WARN).or_else(std::io::stdout))
+            .event_format(SoMensagem)
+            .try_init(),
+    };
+}
+
+/// Roda uma etapa do processamento dentro do seu span e registra quanto tempo levou.
+pub async fn etapa<F: Future>(numero: u8, nome: &'static str, futuro: F) -> F::Output {
+    let inicio = Instant::now();
+    let span = tracing::info_span!("etapa", n = numero, nome);
+    let resultado = futuro.instrument(span.clone()).await;
+    let duracao = inicio.elapsed();
+    span.in_scope(|| tracing::debug!(etapa = nome, duracao_ms = duracao.as_millis() as u64,
+        "{}", texto(&format!("⏱️ [{}/5] {} em {:.2}s", numero, nome, duracao.as_secs_f64()))));
+    resultado
+}
+
+#[macro_export]
+macro_rules! saida {
+    ($($arg:tt)*) => { $crate::console::tracing::info!("{}", $crate::console::texto(&format!($($arg)*))) };
+}
+
+#[macro_export]
+macro_rules! erro {
+    ($($arg:tt)*) => { $crate::console::tracing::warn!("{}", $crate::console::texto(&format!($($arg)*))) };
+}
+
+/// Detalhe que só aparece com --verbose.
+#[macro_export]
+macro_rules! detalhe {
+    ($($arg:tt)*) => { $crate::console::tracing::debug!("{}", $crate::console::texto(&format!($($arg)*))) };
+}
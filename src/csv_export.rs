@@ -0,0 +1,61 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+
+use crate::ranking::PilotoDisplay;
+
+// --- CLASSIFICAÇÃO EM CSV ---
+// Publicada em <base>.csv ao lado do HTML e do JSON, para federações que pedem
+// resultados em planilha: posição, piloto, voltas, gap, penalidades, melhor volta
+// e a melhor volta em cada fenda.
+//   CSV_SEPARATOR  separador de campos (padrão ","; ";" para o Excel em português)
+// Com ";" os decimais saem com vírgula, como o Excel brasileiro espera.
+
+fn separador() -> String {
+    env::var("CSV_SEPARATOR").ok().filter(|s| !s.is_empty()).unwrap_or_else(|| ",".to_string())
+}
+
+/// Aspas quando o campo contém separador, aspas ou quebra de linha (RFC 4180).
+fn campo(valor: &str, sep: &str) -> String {
+    if valor.contains(sep) || valor.contains('"') || valor.contains('\n') {
+        format!("\"{}\"", valor.replace('"', "\"\""))
+    } else {
+        valor.to_string()
+    }
+}
+
+pub fn gerar(ranking: &[PilotoDisplay], slots_count: usize) -> String {
+    let sep = separador();
+    let decimal = |v: &str| if sep == ";" { v.replace('.', ",") } else { v.to_string() };
+    let fendas = crate::config::fendas_pista(slots_count);
+
+    let mut cabecalho: Vec<String> = ["position", "name", "laps", "gap", "penalties", "best_lap"].iter().map(|c| c.to_string()).collect();
+    cabecalho.extend(fendas.iter().take(slots_count).map(|f| format!("best_{}", f.nome)));
+    let mut linhas = vec![cabecalho.iter().map(|c| campo(c, &sep)).collect::<Vec<_>>().join(&sep)];
+
+    for (i, p) in ranking.iter().enumerate() {
+        let mut valores = vec![
+            p.posicao_exibida(i),
+            p.nome.clone(),
+            p.total_laps.to_string(),
+            decimal(&p.gap),
+            p.penalties.to_string(),
+            decimal(&p.best_time),
+        ];
+        valores.extend((1..=slots_count).map(|s| {
+            p.times_per_slot.get(&s.to_string()).filter(|t| t.as_str() != "---").map(|t| decimal(t)).unwrap_or_default()
+        }));
+        linhas.push(valores.iter().map(|v| campo(v, &sep)).collect::<Vec<_>>().join(&sep));
+    }
+    // BOM para o Excel reconhecer o UTF-8 (acentos nos nomes)
+    format!("\u{feff}{}\r\n", linhas.join("\r\n"))
+}
+
+/// Grava e publica <base>.csv. Retorna a chave.
+pub async fn publicar(ranking: &[PilotoDisplay], slots_count: usize, r2_key_base: &str) -> Result<String, Box<dyn Error>> {
+    let local = crate::pasta_saida().join("last_upload.csv");
+    fs::write(&local, gerar(ranking, slots_count))?;
+    let key = format!("{}.csv", r2_key_base);
+    crate::storage::upload_to_r2(&local, &key).await?;
+    Ok(key)
+}
@@ -0,0 +1,130 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use tera::{Context, Tera};
+
+use crate::history::{self, classificacao, provisorios, EventoHistorico, Historico};
+use crate::notify;
+
+// --- RESUMO DA TEMPORADA (DIGEST POR E-MAIL) ---
+// Uso: raceday digest --period month|season [--dry-run]
+
+#[derive(Serialize)]
+struct LinhaClassificacao {
+    posicao: usize,
+    nome: String,
+    pontos: f64,
+    /// Positivo = subiu posições no período
+    variacao: i64,
+    /// Associação vencida em alguma prova: pontos sujeitos a revisão
+    provisorio: bool,
+}
+
+#[derive(Serialize)]
+struct RecordeBatido {
+    track: String,
+    titulo: String,
+    nome: String,
+    tempo: f64,
+    anterior: f64,
+}
+
+fn recordes_batidos(historico: &Historico, club: &str, periodo: &[&EventoHistorico]) -> Vec<RecordeBatido> {
+    let mut recordes = Vec::new();
+    for e in periodo {
+        let anterior = historico.eventos.iter()
+            .filter(|h| h.club == club && h.track == e.track && h.timestamp < e.timestamp)
+            .flat_map(|h| h.resultados.iter())
+            .map(|r| r.best_lap)
+            .filter(|t| *t > 0.0)
+            .fold(f64::MAX, f64::min);
+        if let Some(melhor) = e.resultados.iter()
+            .filter(|r| r.best_lap > 0.0)
+            .min_by(|a, b| a.best_lap.partial_cmp(&b.best_lap).unwrap_or(std::cmp::Ordering::Equal))
+            .filter(|m| anterior < f64::MAX && m.best_lap < anterior)
+        {
+            recordes.push(RecordeBatido {
+                track: e.track.clone(),
+                titulo: e.titulo.clone(),
+                nome: melhor.nome.clone(),
+                tempo: melhor.best_lap,
+                anterior,
+            });
+        }
+    }
+    recordes
+}
+
+pub async fn executar(periodo: &str, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let club = env::var("CLUB").map_err(|_| "❌ CLUB não definida")?;
+
+    let agora = history::agora_timestamp();
+    let temporada = &agora[0..4];
+    let (prefixo, rotulo) = match periodo {
+        "month" => (&agora[0..6], format!("{}/{}", &agora[4..6], temporada)),
+        "season" => (temporada, format!("Temporada {}", temporada)),
+        outro => return Err(format!("❌ Período inválido: '{}' (use month ou season)", outro).into()),
+    };
+
+    let historico = crate::privacy::historico_publico(&Historico::carregar());
+    let da_temporada: Vec<&EventoHistorico> = historico.eventos.iter()
+        .filter(|e| e.club == club && e.timestamp.starts_with(temporada))
+        .collect();
+    let do_periodo: Vec<&EventoHistorico> = da_temporada.iter().copied()
+        .filter(|e| e.timestamp.starts_with(prefixo))
+        .collect();
+
+    if do_periodo.is_empty() {
+        saida!("ℹ️ Nenhuma prova de {} no histórico ({}). Nada a enviar.", club, rotulo);
+        return Ok(());
+    }
+
+    // Movimentação: classificação antes do período vs. depois dele
+    let inicio_periodo = &do_periodo[0].timestamp;
+    let antes: Vec<&EventoHistorico> = da_temporada.iter().copied().filter(|e| &e.timestamp < inicio_periodo).collect();
+    let pos_antes: HashMap<String, usize> = classificacao(&antes).into_iter()
+        .enumerate()
+        .map(|(i, (nome, _))| (nome, i + 1))
+        .collect();
+    let pendentes = provisorios(&da_temporada);
+    let tabela: Vec<LinhaClassificacao> = classificacao(&da_temporada).into_iter()
+        .enumerate()
+        .map(|(i, (nome, pontos))| {
+            let variacao = pos_antes.get(&nome).map(|p| *p as i64 - (i as i64 + 1)).unwrap_or(0);
+            let provisorio = pendentes.contains(&nome);
+            LinhaClassificacao { posicao: i + 1, nome, pontos: (pontos * 10.0).round() / 10.0, variacao, provisorio }
+        })
+        .collect();
+
+    let proximas: Vec<String> = env::var("NEXT_EVENTS").unwrap_or_default()
+        .split(';')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let mut tera = Tera::default();
+    crate::templates::carregar(&mut tera, "digest.html", "digest")?;
+    let mut context = Context::new();
+    context.insert("club", &club);
+    context.insert("rotulo", &rotulo);
+    context.insert("provas", &do_periodo);
+    context.insert("classificacao", &tabela);
+    context.insert("recordes", &recordes_batidos(&historico, &club, &do_periodo));
+    context.insert("proximas", &proximas);
+    let html = tera.render("digest", &context)?;
+
+    let assunto = format!("📬 {} - Resumo {}", club, rotulo);
+    if dry_run {
+        let local = crate::pasta_saida().join("digest.html");
+        fs::create_dir_all(crate::pasta_saida())?;
+        fs::write(&local, &html)?;
+        saida!("📝 [dry-run] Resumo salvo em {} ({})", local.display(), assunto);
+        return Ok(());
+    }
+
+    let enviados = notify::enviar_email(&assunto, &html)?;
+    saida!("📧 Resumo '{}' enviado para {} membro(s)", assunto, enviados);
+    Ok(())
+}
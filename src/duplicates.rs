@@ -0,0 +1,120 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs;
+
+use crate::aliases;
+
+// --- PILOTOS DUPLICADOS (AUDITORIA DE NOMES) ---
+// Uso: raceday audit pilots [--year 2025] [--threshold 0.9] [--write]
+// Lê as provas publicadas do clube, já com o mapa de apelidos aplicado, e compara
+// os nomes dois a dois (Jaro-Winkler e distância de Levenshtein, sem acentos e
+// maiúsculas; "J. Silva" casa com "João Silva" pela inicial). Dois nomes que
+// correram a mesma prova são pilotos diferentes e nunca viram par.
+// Cada par provável vira uma sugestão de apelido: o nome do cadastro ou, sem ele,
+// o que aparece em mais provas fica como canônico. --write grava as sugestões no
+// mapa de apelidos local (PILOT_ALIASES); sem ela, só imprime o JSON para revisar.
+
+pub const LIMIAR_PADRAO: f64 = 0.9;
+
+/// "j._silva" x "joao_silva": mesmo sobrenome e o primeiro nome de um é a inicial do outro.
+fn inicial_confere(a: &str, b: &str) -> bool {
+    let partes = |s: &str| s.split('_').filter(|p| !p.is_empty()).map(|p| p.trim_end_matches('.').to_string()).collect::<Vec<_>>();
+    let (pa, pb) = (partes(a), partes(b));
+    if pa.len() < 2 || pb.len() < 2 || pa.last() != pb.last() { return false; }
+    let (na, nb) = (&pa[0], &pb[0]);
+    (na.len() == 1 || nb.len() == 1) && na.chars().next() == nb.chars().next()
+}
+
+/// Semelhança de 0 a 1 entre dois nomes já em forma de comparação (aliases::chave).
+pub fn semelhanca(a: &str, b: &str) -> f64 {
+    if inicial_confere(a, b) { return 0.95; }
+    let jaro = strsim::jaro_winkler(a, b);
+    // Uma ou duas letras trocadas em nomes longos: "silvera" x "silveira"
+    let distancia = strsim::levenshtein(a, b);
+    let levenshtein = if a.len().min(b.len()) >= 6 && distancia <= 2 { 0.92 } else { 0.0 };
+    jaro.max(levenshtein)
+}
+
+struct Sugestao {
+    apelido: String,
+    canonico: String,
+    provas_apelido: usize,
+    provas_canonico: usize,
+    semelhanca: f64,
+}
+
+/// `raceday audit pilots`: `ano` restringe às provas do ano; `gravar` é o --write.
+pub async fn executar(club: &str, ano: Option<&str>, limiar: f64, gravar: bool) -> Result<(), Box<dyn Error>> {
+    if !(0.0..=1.0).contains(&limiar) {
+        return Err("❌ --threshold deve estar entre 0 e 1".into());
+    }
+    let mapa = aliases::carregar().await;
+
+    // Provas de cada nome, já pelo nome canônico
+    let mut provas: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (nome, chaves) in aliases::nomes_publicados(ano, club).await? {
+        provas.entry(mapa.nome(&nome)).or_default().extend(chaves);
+    }
+    let nomes: Vec<(&String, &BTreeSet<String>, String)> = provas.iter().map(|(n, c)| (n, c, aliases::chave(n))).collect();
+
+    let mut sugestoes: Vec<Sugestao> = Vec::new();
+    for (i, (nome_a, provas_a, chave_a)) in nomes.iter().enumerate() {
+        for (nome_b, provas_b, chave_b) in &nomes[i + 1..] {
+            if !provas_a.is_disjoint(provas_b) { continue; }
+            let s = semelhanca(chave_a, chave_b);
+            if s < limiar { continue; }
+            // Canônico: o do cadastro; senão o mais frequente; empate, o nome mais completo
+            let a_fica = match (mapa.cadastrado(nome_a), mapa.cadastrado(nome_b)) {
+                (true, false) => true,
+                (false, true) => false,
+                _ => (provas_a.len(), nome_a.len()) >= (provas_b.len(), nome_b.len()),
+            };
+            let ((canonico, pc), (apelido, pa)) = if a_fica {
+                ((nome_a, provas_a), (nome_b, provas_b))
+            } else {
+                ((nome_b, provas_b), (nome_a, provas_a))
+            };
+            sugestoes.push(Sugestao {
+                apelido: apelido.to_string(),
+                canonico: canonico.to_string(),
+                provas_apelido: pa.len(),
+                provas_canonico: pc.len(),
+                semelhanca: s,
+            });
+        }
+    }
+    sugestoes.sort_by(|a, b| b.semelhanca.total_cmp(&a.semelhanca));
+
+    saida!("🔎 {} nome(s) comparado(s); {} par(es) provável(is) de piloto duplicado (limiar {:.2}):", nomes.len(), sugestoes.len(), limiar);
+    for s in &sugestoes {
+        saida!("   {:.2}  {} ({} prova(s)) -> {} ({} prova(s))", s.semelhanca, s.apelido, s.provas_apelido, s.canonico, s.provas_canonico);
+    }
+    if sugestoes.is_empty() { return Ok(()); }
+
+    // Um apelido só aponta para um nome: fica o par mais parecido
+    let mut propostas: BTreeMap<String, String> = BTreeMap::new();
+    for s in &sugestoes {
+        propostas.entry(s.apelido.clone()).or_insert_with(|| s.canonico.clone());
+    }
+    if !gravar {
+        println!("{}", serde_json::to_string_pretty(&propostas)?);
+        saida!("   Revise e acrescente ao mapa de apelidos ({}), ou rode de novo com --write", aliases::caminho_local().display());
+        return Ok(());
+    }
+
+    let path = aliases::caminho_local();
+    let mut atuais: BTreeMap<String, String> = match fs::read_to_string(&path) {
+        Ok(texto) => serde_json::from_str(&texto).map_err(|e| format!("❌ {} inválido: {}", path.display(), e))?,
+        Err(_) => BTreeMap::new(),
+    };
+    let antes = atuais.len();
+    for (apelido, canonico) in propostas {
+        atuais.entry(apelido).or_insert(canonico);
+    }
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&atuais)?)?;
+    saida!("✅ {} apelido(s) novo(s) gravado(s) em {}", atuais.len() - antes, path.display());
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+// --- INSCRITOS DA PROVA (CLASSES E CONVIDADOS) ---
+// Dados que valem só para uma prova, num JSON apontado por ENTRANTS_FILE
+// (--entrants), pelo nome usado no cronometrador:
+// {
+//   "João Côrtes": "LMP",                -> classe do carro
+//   "Maria Souza": {"class": "Classic"},
+//   "Pedro Lima": {"guest": true},        -> convidado
+//   "Ana Reis": {"status": "DNS"}          -> DNF, DNS ou DSQ (ver ranking::STATUS)
+// }
+// A classe também pode vir do preparador (data["pilots"][id]["class"]); o
+// arquivo de inscritos prevalece. Convidados aparecem no relatório, mas não
+// pontuam no campeonato: quem chegou atrás deles sobe uma posição na pontuação.
+// Tudo fica gravado no JSON publicado, então o rebuild não depende do arquivo.
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Entrada {
+    Classe(String),
+    Completa {
+        #[serde(default)]
+        class: Option<String>,
+        #[serde(default)]
+        guest: bool,
+        #[serde(default)]
+        status: Option<String>,
+    },
+}
+
+#[derive(Default)]
+pub struct Inscrito {
+    pub classe: Option<String>,
+    pub convidado: bool,
+    pub status: Option<String>,
+}
+
+pub fn caminho() -> Option<PathBuf> {
+    env::var_os("ENTRANTS_FILE").map(PathBuf::from).filter(|p| !p.as_os_str().is_empty())
+}
+
+/// Inscritos pelo nome. Vazio sem ENTRANTS_FILE; arquivo ilegível só gera aviso.
+pub fn carregar() -> HashMap<String, Inscrito> {
+    let Some(path) = caminho() else { return HashMap::new() };
+    let lido = fs::read_to_string(&path).map_err(|e| e.to_string())
+        .and_then(|s| serde_json::from_str::<HashMap<String, Entrada>>(&s).map_err(|e| e.to_string()));
+    match lido {
+        Ok(entradas) => entradas.into_iter().map(|(nome, entrada)| {
+            let (classe, convidado, status) = match entrada {
+                Entrada::Classe(c) => (Some(c), false, None),
+                Entrada::Completa { class, guest, status } => (class, guest, status),
+            };
+            let limpo = |v: Option<String>| v.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+            (nome.trim().to_string(), Inscrito { classe: limpo(classe), convidado, status: limpo(status) })
+        }).collect(),
+        Err(e) => {
+            erro!("⚠️ Arquivo de inscritos {} ignorado: {}", path.display(), e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Grava a classe de cada inscrito em data["pilots"][id]["class"], o status em
+/// ["status"] e marca os convidados com ["guest"] = true.
+pub fn anotar(data: &mut Value) {
+    let inscritos = carregar();
+    if inscritos.is_empty() { return; }
+    if let Some(pilotos) = data["pilots"].as_object_mut() {
+        for info in pilotos.values_mut() {
+            let inscrito = info["name"].as_str().and_then(|n| inscritos.get(n.trim()));
+            if let Some(classe) = inscrito.and_then(|i| i.classe.clone()) {
+                info["class"] = Value::String(classe);
+            }
+            if let Some(status) = inscrito.and_then(|i| i.status.clone()) {
+                info["status"] = Value::String(status);
+            }
+            if inscrito.is_some_and(|i| i.convidado) {
+                info["guest"] = Value::Bool(true);
+            }
+        }
+    }
+}
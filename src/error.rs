@@ -0,0 +1,126 @@
+use std::error::Error;
+
+// --- ERROS DO RACEDAY ---
+// storage, ini, prep, pipeline e notify devolvem RacedayError, para quem chama
+// saber o que deu errado sem ler a mensagem; os demais módulos (batch, live,
+// season...) ainda devolvem Box<dyn Error> (ou String):
+//   Entrada        arquivo ou opção inválida (INI ausente, overrides.toml, fendas...)
+//   Preparo        o preparador externo (PREP_COMMAND) falhou ou estourou o tempo
+//   Leitura        o .INI ou o JSON da prova não está no formato esperado
+//   Template       template ausente ou com erro de renderização (Tera)
+//   Armazenamento  envio para o bucket falhou depois de todas as tentativas
+//                  (ou a escrita condicional perdeu todas as disputas)
+//   Notificacao    e-mail (SMTP) não enviado ou ação de pós-publicação que falhou
+// Um Box<dyn Error> que já carrega um RacedayError é desembrulhado no `?`, e os
+// erros dos backends (backends::ErroStorage) viram RacedayError dentro de storage.rs.
+
+#[derive(Debug, thiserror::Error)]
+pub enum RacedayError {
+    #[error("{0}")]
+    Entrada(String),
+    #[error("{0}")]
+    Preparo(String),
+    #[error("{0}")]
+    Leitura(String),
+    #[error("❌ Erro no template: {0}")]
+    Template(String),
+    #[error("❌ Falha ao enviar {key} após {tentativas} tentativa(s): {erro}")]
+    Armazenamento { key: String, tentativas: u32, erro: String },
+    #[error("❌ Notificação não enviada: {0}")]
+    Notificacao(String),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Outro(String),
+}
+
+// --- CÓDIGOS DE SAÍDA ---
+// Para os scripts de lote repetirem só o que vale repetir (3 e 6, às vezes 4):
+//   0  sucesso
+//   1  outra falha
+//   2  entrada inválida: opção, INI, overrides, JSON fora do formato, PILOT_PRIVACY
+//   3  envio ao bucket falhou (os arquivos gerados ficam na pasta de saída)
+//   4  o preparador externo falhou ou estourou o tempo
+//   5  erro de template
+//   6  notificação ou pós-publicação falhou (a publicação foi feita)
+
+pub const SAIDA_FALHA: i32 = 1;
+pub const SAIDA_ENTRADA: i32 = 2;
+pub const SAIDA_ARMAZENAMENTO: i32 = 3;
+pub const SAIDA_PREPARO: i32 = 4;
+pub const SAIDA_TEMPLATE: i32 = 5;
+pub const SAIDA_NOTIFICACAO: i32 = 6;
+
+impl RacedayError {
+    pub fn codigo_saida(&self) -> i32 {
+        match self {
+            RacedayError::Entrada(_) | RacedayError::Leitura(_) => SAIDA_ENTRADA,
+            RacedayError::Armazenamento { .. } => SAIDA_ARMAZENAMENTO,
+            RacedayError::Preparo(_) => SAIDA_PREPARO,
+            RacedayError::Template(_) => SAIDA_TEMPLATE,
+            RacedayError::Notificacao(_) => SAIDA_NOTIFICACAO,
+            RacedayError::Io(_) | RacedayError::Outro(_) => SAIDA_FALHA,
+        }
+    }
+}
+
+/// Mensagem com as causas encadeadas: o Tera diz só "Failed to render" no topo.
+fn com_causas(e: &dyn Error) -> String {
+    let mut texto = e.to_string();
+    let mut causa = e.source();
+    while let Some(c) = causa {
+        texto.push_str(": ");
+        texto.push_str(&c.to_string());
+        causa = c.source();
+    }
+    texto
+}
+
+impl From<tera::Error> for RacedayError {
+    fn from(e: tera::Error) -> Self {
+        RacedayError::Template(com_causas(&e))
+    }
+}
+
+impl From<serde_json::Error> for RacedayError {
+    fn from(e: serde_json::Error) -> Self {
+        RacedayError::Leitura(format!("❌ JSON inválido: {}", e))
+    }
+}
+
+impl From<String> for RacedayError {
+    fn from(e: String) -> Self {
+        RacedayError::Outro(e)
+    }
+}
+
+/// Erros dos backends de armazenamento (backends::ErroStorage).
+impl From<Box<dyn Error + Send + Sync>> for RacedayError {
+    fn from(e: Box<dyn Error + Send + Sync>) -> Self {
+        let e = match e.downcast::<RacedayError>() {
+            Ok(r) => return *r,
+            Err(e) => e,
+        };
+        match e.downcast::<std::io::Error>() {
+            Ok(io) => RacedayError::Io(*io),
+            Err(e) => RacedayError::Outro(com_causas(e.as_ref())),
+        }
+    }
+}
+
+impl From<Box<dyn Error>> for RacedayError {
+    fn from(e: Box<dyn Error>) -> Self {
+        let e = match e.downcast::<RacedayError>() {
+            Ok(r) => return *r,
+            Err(e) => e,
+        };
+        let e = match e.downcast::<tera::Error>() {
+            Ok(t) => return RacedayError::from(*t),
+            Err(e) => e,
+        };
+        match e.downcast::<std::io::Error>() {
+            Ok(io) => RacedayError::Io(*io),
+            Err(e) => RacedayError::Outro(com_causas(e.as_ref())),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fs;
+
+use crate::history::Historico;
+use crate::pilots::slug_piloto;
+
+// --- EXPORTAÇÃO DE DADOS PESSOAIS (PEDIDOS DE ACESSO / EXCLUSÃO) ---
+// Uso: raceday export-pilot --id <nome ou slug> [--purge]
+// Junta tudo o que temos sobre o piloto em temp_out/export_<slug>.json:
+// resultados e índices do histórico, voltas dos JSONs publicados, fotos das
+// provas em que correu e registros da auditoria. Com --purge, remove o piloto
+// do histórico local depois de gerar o arquivo.
+
+/// Voltas do piloto em cada sessão do JSON da prova.
+fn voltas_na_prova(data: &Value, nome: &str) -> Vec<Value> {
+    let mut sessoes = Vec::new();
+    for bateria in data["races"].as_array().into_iter().flatten() {
+        for sessao in bateria["sessions"].as_array().into_iter().flatten() {
+            for (fenda, slot) in sessao["slots"].as_object().into_iter().flatten() {
+                if slot["name"].as_str() != Some(nome) { continue; }
+                sessoes.push(json!({
+                    "race": bateria["name"],
+                    "session": sessao["session"],
+                    "slot": fenda,
+                    "laps": slot["laps"],
+                    "best": slot["best"],
+                    "deslots": slot["deslots"],
+                    "lap_times": slot["lap_times"],
+                }));
+            }
+        }
+    }
+    sessoes
+}
+
+fn registros_auditoria(nome: &str) -> Vec<Value> {
+    fs::read_to_string(crate::audit::caminho_auditoria()).unwrap_or_default()
+        .lines()
+        .filter_map(|l| serde_json::from_str::<Value>(l).ok())
+        .filter(|r| r["details"]["pilot"].as_str() == Some(nome))
+        .collect()
+}
+
+/// `id`: nome ou slug do piloto. `purgar`: remove o piloto do histórico local depois.
+pub async fn executar(id: &str, purgar: bool) -> Result<(), Box<dyn Error>> {
+
+    let mut historico = Historico::carregar();
+    let nome = historico.eventos.iter()
+        .flat_map(|e| e.resultados.iter())
+        .map(|r| r.nome.as_str())
+        .find(|n| *n == id || slug_piloto(n) == slug_piloto(id))
+        .map(|n| n.to_string())
+        .ok_or_else(|| format!("❌ Piloto '{}' não encontrado no histórico", id))?;
+
+    let mut provas = Vec::new();
+    for e in historico.eventos.iter() {
+        let Some(r) = e.resultados.iter().find(|r| r.nome == nome) else { continue };
+        let key = crate::storage::chave_prova(&e.club, &e.track, &e.timestamp);
+        // Os JSONs publicados podem ter o nome trocado (privacidade); o original só existe no histórico
+        let bruto: Option<Value> = crate::storage::baixar_r2(&key).await.ok().flatten().and_then(|b| crate::schema::ler_publicada(&key, &b).ok());
+
+        provas.push(json!({
+            "club": e.club,
+            "track": e.track,
+            "title": e.titulo,
+            "date": e.data,
+            "timestamp": e.timestamp,
+            "position": r.posicao,
+            "total_laps": r.total_laps,
+            "best_lap": r.best_lap,
+            "performance_index": r.indice,
+            "penalty_laps": r.penalidades,
+            "sessions": bruto.as_ref().map(|d| voltas_na_prova(d, &nome)).unwrap_or_default(),
+            "event_photos": bruto.as_ref().map(|d| d["gallery"].clone()).unwrap_or(Value::Null),
+        }));
+    }
+
+    let exportacao = json!({
+        "pilot": nome,
+        "exported_at": crate::history::agora_timestamp(),
+        "public_name": crate::privacy::nome_publico(&nome),
+        "races": provas,
+        "audit_log": registros_auditoria(&nome),
+    });
+
+    let dir = crate::pasta_saida();
+    fs::create_dir_all(&dir)?;
+    let arquivo = dir.join(format!("export_{}.json", slug_piloto(&nome)));
+    fs::write(&arquivo, serde_json::to_string_pretty(&exportacao)?)?;
+    saida!("📦 Dados de {} exportados em {} ({} prova(s))", nome, arquivo.display(), provas.len());
+
+    if purgar {
+        for e in historico.eventos.iter_mut() {
+            let antes = e.resultados.len();
+            e.resultados.retain(|r| r.nome != nome);
+            if e.resultados.len() != antes { e.reclassificar(); }
+        }
+        crate::audit::registrar("export-pilot", "purge", "*", &json!({ "pilot": nome }))?;
+        historico.salvar()?;
+        saida!("🗑️ {} removido do histórico. Rode `raceday rebuild` e `raceday refresh-index` para atualizar o que já foi publicado.", nome);
+    }
+    Ok(())
+}
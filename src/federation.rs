@@ -0,0 +1,141 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::time::Duration;
+use tera::{Context, Tera};
+
+use crate::registry::Cadastro;
+
+// --- FEDERAÇÃO: CLASSIFICAÇÃO ENTRE CLUBES ---
+// Uso: raceday federation [--season 2025]
+// Lê o que cada clube já publica (index.json + api/races/...json) a partir de
+// FEDERATION_SOURCES="DG Slot=https://pub-aaa.r2.dev,Clube B=https://pub-bbb.r2.dev",
+// unifica os pilotos pelo cadastro (apelidos em "aliases") e publica
+// federation/standings.json e federation/index.html no bucket deste clube.
+
+const TIMEOUT_SECS: u64 = 30;
+
+#[derive(Serialize)]
+struct LinhaFederacao {
+    posicao: usize,
+    nome: String,
+    clubes: Vec<String>,
+    provas: usize,
+    vitorias: usize,
+    pontos: f64,
+}
+
+#[derive(Serialize)]
+struct ResumoClube {
+    nome: String,
+    url: String,
+    provas: usize,
+}
+
+/// "Clube=https://url-publica" -> (nome, url sem barra final)
+pub fn fonte(spec: &str) -> Option<(String, String)> {
+    let (nome, url) = spec.split_once('=')?;
+    let (nome, url) = (nome.trim().to_string(), url.trim().trim_end_matches('/').to_string());
+    (!nome.is_empty() && !url.is_empty()).then_some((nome, url))
+}
+
+fn fontes() -> Vec<(String, String)> {
+    env::var("FEDERATION_SOURCES").unwrap_or_default()
+        .split(',')
+        .filter_map(fonte)
+        .collect()
+}
+
+/// Chave da variante pública (api/races/...) de uma entrada do index.json.
+pub fn chave_api(item: &Value) -> String {
+    crate::public_api::chave(&crate::storage::chave_prova(item["club"].as_str().unwrap_or(""), item["track"].as_str().unwrap_or(""), item["timestamp"].as_str().unwrap_or("")))
+}
+
+pub async fn baixar_json(client: &reqwest::Client, url: &str) -> Result<Value, Box<dyn Error>> {
+    Ok(client.get(url).timeout(Duration::from_secs(TIMEOUT_SECS)).send().await?.error_for_status()?.json().await?)
+}
+
+pub async fn executar(temporada: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let fontes = fontes();
+    if fontes.is_empty() {
+        return Err("❌ Defina FEDERATION_SOURCES=\"Clube=https://url-publica,...\"".into());
+    }
+    let temporada = temporada.map(|t| t.to_string())
+        .unwrap_or_else(|| crate::history::agora_timestamp()[0..4].to_string());
+
+    let cadastro = Cadastro::carregar();
+    let client = reqwest::Client::new();
+
+    let mut pontos: HashMap<String, f64> = HashMap::new();
+    let mut provas: HashMap<String, usize> = HashMap::new();
+    let mut vitorias: HashMap<String, usize> = HashMap::new();
+    let mut clubes_do_piloto: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let mut clubes = Vec::new();
+
+    for (clube, base) in &fontes {
+        let indice = match baixar_json(&client, &format!("{}/index.json", base)).await {
+            Ok(v) => v,
+            Err(e) => { erro!("⚠️ {}: não foi possível ler o index.json ({})", clube, e); continue; }
+        };
+        let mut lidas = 0;
+        for item in indice.as_array().into_iter().flatten() {
+            let ts = item["timestamp"].as_str().unwrap_or("");
+            if !ts.starts_with(temporada.as_str()) { continue; }
+            let key = chave_api(item);
+            let prova = match baixar_json(&client, &format!("{}/{}", base, key)).await {
+                Ok(v) => v,
+                Err(e) => { erro!("⚠️ {}: {} indisponível ({})", clube, key, e); continue; }
+            };
+            lidas += 1;
+            for linha in prova["ranking"].as_array().into_iter().flatten() {
+                let Some(nome) = linha["name"].as_str() else { continue };
+                let nome = cadastro.nome_canonico(nome);
+                *pontos.entry(nome.clone()).or_insert(0.0) += linha["performance_index"].as_f64().unwrap_or(0.0);
+                *provas.entry(nome.clone()).or_insert(0) += 1;
+                if linha["position"].as_u64() == Some(1) { *vitorias.entry(nome.clone()).or_insert(0) += 1; }
+                clubes_do_piloto.entry(nome).or_default().insert(clube.clone());
+            }
+        }
+        saida!("🌐 {}: {} prova(s) da temporada {}", clube, lidas, temporada);
+        clubes.push(ResumoClube { nome: clube.clone(), url: base.clone(), provas: lidas });
+    }
+
+    let mut tabela: Vec<(String, f64)> = pontos.into_iter().collect();
+    tabela.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+    let linhas: Vec<LinhaFederacao> = tabela.into_iter().enumerate().map(|(i, (nome, p))| LinhaFederacao {
+        posicao: i + 1,
+        clubes: clubes_do_piloto.remove(&nome).unwrap_or_default().into_iter().collect(),
+        provas: provas.get(&nome).copied().unwrap_or(0),
+        vitorias: vitorias.get(&nome).copied().unwrap_or(0),
+        pontos: (p * 10.0).round() / 10.0,
+        nome,
+    }).collect();
+
+    crate::assets::publicar().await?;
+    let mut tera = Tera::default();
+    crate::assets::registrar(&mut tera);
+    crate::templates::carregar(&mut tera, "federation.html", "federation")?;
+    let mut context = Context::new();
+    context.insert("temporada", &temporada);
+    context.insert("clubes", &clubes);
+    context.insert("classificacao", &linhas);
+
+    let local_dir = crate::pasta_saida().join("federation");
+    fs::create_dir_all(&local_dir)?;
+    let standings = serde_json::json!({ "season": temporada, "clubs": clubes, "standings": linhas });
+    let arquivos: BTreeMap<&str, String> = BTreeMap::from([
+        ("standings.json", serde_json::to_string_pretty(&standings)?),
+        ("index.html", tera.render("federation", &context)?),
+    ]);
+    for (nome, conteudo) in &arquivos {
+        let local = local_dir.join(nome);
+        fs::write(&local, conteudo)?;
+        crate::storage::upload_se_mudou(&local, &format!("federation/{}", nome)).await?;
+    }
+
+    saida!("✨ Classificação da federação publicada: {} piloto(s) de {} clube(s)", linhas.len(), clubes.len());
+    Ok(())
+}
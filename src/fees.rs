@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::history::{agora_timestamp, Historico};
+
+// --- TAXAS DE INSCRIÇÃO E PAGAMENTOS (TESOURARIA) ---
+// Uso: raceday fees set-fee --event <timestamp> --amount 30,00
+//      raceday fees pay --event <timestamp> --pilot "Nome" --method pix [--amount 30,00]
+//      raceday fees report --event <timestamp>
+//      raceday fees season [--season 2025]
+// Livro-caixa local em FEES_PATH (padrão history/fees.json). Quem participou
+// vem do histórico de provas, então a conciliação mostra quem correu e não pagou.
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Pagamento {
+    pilot: String,
+    /// Valor em centavos, para não acumular erro de ponto flutuante
+    amount_cents: i64,
+    method: String,
+    recorded_at: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct TaxasEvento {
+    #[serde(default)]
+    fee_cents: i64,
+    #[serde(default)]
+    payments: Vec<Pagamento>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(transparent)]
+struct LivroCaixa {
+    /// Chave: timestamp da prova
+    eventos: BTreeMap<String, TaxasEvento>,
+}
+
+fn caminho_livro() -> PathBuf {
+    env::var_os("FEES_PATH").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("history").join("fees.json"))
+}
+
+impl LivroCaixa {
+    fn carregar() -> LivroCaixa {
+        fs::read_to_string(caminho_livro()).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn salvar(&self) -> Result<(), Box<dyn Error>> {
+        let path = caminho_livro();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+/// "30", "30,5", "30.50", "-5,50" -> 3000, 3050, 3050, -550
+fn centavos(valor: &str) -> Option<i64> {
+    let valor = valor.trim().replace(',', ".");
+    // O sinal vale para o valor inteiro (estorno), não só para os reais
+    let (sinal, valor) = match valor.strip_prefix('-') {
+        Some(resto) => (-1, resto),
+        None => (1, valor.as_str()),
+    };
+    let (inteiro, fracao) = valor.split_once('.').unwrap_or((valor, ""));
+    if inteiro.is_empty() || fracao.len() > 2 || !inteiro.chars().chain(fracao.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let fracao = format!("{:0<2}", fracao);
+    Some(sinal * (inteiro.parse::<i64>().ok()? * 100 + fracao.parse::<i64>().ok()?))
+}
+
+fn reais(cents: i64) -> String {
+    let sinal = if cents < 0 { "-" } else { "" };
+    format!("{}R$ {},{:02}", sinal, cents.abs() / 100, cents.abs() % 100)
+}
+
+fn relatorio_evento(livro: &LivroCaixa, historico: &Historico, ts: &str) {
+    let taxas = livro.eventos.get(ts);
+    let titulo = historico.eventos.iter().find(|e| e.timestamp == ts).map(|e| e.titulo.as_str()).unwrap_or("?");
+    let participantes: Vec<&str> = historico.eventos.iter()
+        .filter(|e| e.timestamp == ts)
+        .flat_map(|e| e.resultados.iter().map(|r| r.nome.as_str()))
+        .collect();
+    let pagamentos = taxas.map(|t| t.payments.as_slice()).unwrap_or(&[]);
+    let taxa = taxas.map(|t| t.fee_cents).unwrap_or(0);
+
+    saida!("💰 {} ({}) — taxa {}", titulo, ts, reais(taxa));
+    let mut por_metodo: BTreeMap<&str, i64> = BTreeMap::new();
+    for p in pagamentos { *por_metodo.entry(p.method.as_str()).or_insert(0) += p.amount_cents; }
+    for (metodo, total) in &por_metodo { saida!("   {}: {}", metodo, reais(*total)); }
+
+    let recebido: i64 = pagamentos.iter().map(|p| p.amount_cents).sum();
+    let esperado = taxa * participantes.len() as i64;
+    saida!("   Participantes: {} | Esperado: {} | Recebido: {} | Diferença: {}",
+        participantes.len(), reais(esperado), reais(recebido), reais(recebido - esperado));
+
+    let pendentes: Vec<&&str> = participantes.iter().filter(|n| !pagamentos.iter().any(|p| p.pilot == **n)).collect();
+    if !pendentes.is_empty() {
+        erro!("⚠️ Sem pagamento: {}", pendentes.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", "));
+    }
+    let sem_corrida: Vec<&str> = pagamentos.iter().map(|p| p.pilot.as_str()).filter(|n| !participantes.contains(n)).collect();
+    if !sem_corrida.is_empty() {
+        saida!("ℹ️ Pagaram mas não aparecem no resultado: {}", sem_corrida.join(", "));
+    }
+}
+
+/// `raceday fees set-fee`
+pub fn definir_taxa(ts: &str, valor: &str) -> Result<(), Box<dyn Error>> {
+    let valor = centavos(valor).ok_or("❌ Valor inválido em --amount (ex.: 30,00)")?;
+    let mut livro = LivroCaixa::carregar();
+    livro.eventos.entry(ts.to_string()).or_default().fee_cents = valor;
+    livro.salvar()?;
+    saida!("✅ Taxa da prova {} definida em {}", ts, reais(valor));
+    Ok(())
+}
+
+/// `raceday fees pay`: sem `valor`, registra a taxa da prova.
+pub fn pagar(ts: &str, piloto: &str, metodo: &str, valor: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let mut livro = LivroCaixa::carregar();
+    let taxas = livro.eventos.entry(ts.to_string()).or_default();
+    let valor = match valor {
+        Some(v) => centavos(v).ok_or("❌ Valor inválido em --amount")?,
+        None => taxas.fee_cents,
+    };
+    taxas.payments.push(Pagamento {
+        pilot: piloto.to_string(),
+        amount_cents: valor,
+        method: metodo.to_string(),
+        recorded_at: agora_timestamp(),
+    });
+    livro.salvar()?;
+    saida!("✅ Pagamento de {} registrado: {} ({})", piloto, reais(valor), metodo);
+    Ok(())
+}
+
+/// `raceday fees report`
+pub fn relatorio(ts: &str) {
+    relatorio_evento(&LivroCaixa::carregar(), &Historico::carregar(), ts);
+}
+
+/// `raceday fees season`: todas as provas do ano no histórico (padrão: o atual).
+pub fn temporada(ano: Option<&str>) {
+    let livro = LivroCaixa::carregar();
+    let historico = Historico::carregar();
+    let temporada = ano.map(|s| s.to_string()).unwrap_or_else(|| agora_timestamp()[0..4].to_string());
+    let mut total_esperado = 0;
+    let mut total_recebido = 0;
+    for e in historico.eventos.iter().filter(|e| e.timestamp.starts_with(&temporada)) {
+        relatorio_evento(&livro, &historico, &e.timestamp);
+        let taxas = livro.eventos.get(&e.timestamp);
+        total_esperado += taxas.map(|t| t.fee_cents).unwrap_or(0) * e.resultados.len() as i64;
+        total_recebido += taxas.map(|t| t.payments.iter().map(|p| p.amount_cents).sum::<i64>()).unwrap_or(0);
+    }
+    saida!("\n📊 Temporada {}: esperado {}, recebido {}, diferença {}",
+        temporada, reais(total_esperado), reais(total_recebido), reais(total_recebido - total_esperado));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::centavos;
+
+    #[test]
+    fn centavos_positivos() {
+        assert_eq!(centavos("30"), Some(3000));
+        assert_eq!(centavos("30,5"), Some(3050));
+        assert_eq!(centavos("30.50"), Some(3050));
+    }
+
+    #[test]
+    fn centavos_negativos() {
+        assert_eq!(centavos("-5,50"), Some(-550));
+        assert_eq!(centavos("-0,50"), Some(-50));
+    }
+
+    #[test]
+    fn centavos_invalidos() {
+        assert_eq!(centavos("5,505"), None);
+        assert_eq!(centavos("5,-5"), None);
+        assert_eq!(centavos("--5"), None);
+        assert_eq!(centavos(",50"), None);
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use tera::{Tera, Value};
+
+// --- FILTROS DE FORMATAÇÃO DOS TEMPLATES ---
+// O contexto leva os números crus (best_lap_secs, average_lap_secs, gap_laps,
+// laps_per_heat_avg...) e o template decide a apresentação:
+//   {{ p.best_lap_secs | laptime }}               12.345 ("---" sem volta válida)
+//   {{ p.best_lap_secs | laptime(decimals=2) }}   12.35
+//   {{ p.gap_laps | gap }}                        "—" para o líder, "+3" ou "+1.25"
+//   {{ p.laps_per_heat_avg | decimal(places=1) }} 12,5 (vírgula em pt-BR e es)
+//   {{ loop.index | ordinal }}                    1º (pt-BR, es) / 1st (en)
+// Aceitam número ou texto numérico ("12.345", "12,345"); texto que não for
+// número passa como está.
+
+/// Tempos a partir daqui são o "sem volta" (999.999) do cronometrador.
+const SEM_TEMPO: f64 = 900.0;
+
+fn numero(valor: &Value) -> Option<f64> {
+    match valor {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().replace(',', ".").parse().ok(),
+        _ => None,
+    }
+}
+
+fn inteiro_arg(args: &HashMap<String, Value>, nome: &str, padrao: usize) -> usize {
+    args.get(nome).and_then(|v| v.as_u64()).map(|n| n as usize).unwrap_or(padrao)
+}
+
+/// Vírgula decimal nos idiomas que usam (pt-BR, es).
+fn separador_decimal() -> char {
+    if crate::i18n::locale().starts_with("en") { '.' } else { ',' }
+}
+
+fn texto_original(valor: &Value) -> Value {
+    match valor {
+        Value::String(_) => valor.clone(),
+        Value::Null => Value::String("---".to_string()),
+        outro => Value::String(outro.to_string()),
+    }
+}
+
+fn laptime(valor: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let casas = inteiro_arg(args, "decimals", 3);
+    Ok(match numero(valor) {
+        Some(t) if t > 0.0 && t < SEM_TEMPO => Value::String(format!("{:.*}", casas, t)),
+        Some(_) => Value::String("---".to_string()),
+        None => texto_original(valor),
+    })
+}
+
+fn gap(valor: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    Ok(match numero(valor) {
+        Some(g) if g.abs() < 1e-9 => Value::String("—".to_string()),
+        Some(g) if g.fract() == 0.0 => Value::String(format!("+{}", g.abs() as i64)),
+        Some(g) => Value::String(format!("+{:.2}", g.abs())),
+        None => texto_original(valor),
+    })
+}
+
+fn decimal(valor: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let casas = inteiro_arg(args, "places", 1);
+    Ok(match numero(valor) {
+        Some(n) => Value::String(format!("{:.*}", casas, n).replace('.', &separador_decimal().to_string())),
+        None => texto_original(valor),
+    })
+}
+
+fn ordinal(valor: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    let Some(n) = numero(valor).filter(|n| n.fract() == 0.0 && *n >= 0.0).map(|n| n as u64) else {
+        return Ok(texto_original(valor));
+    };
+    if !crate::i18n::locale().starts_with("en") {
+        return Ok(Value::String(format!("{}º", n)));
+    }
+    let sufixo = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    Ok(Value::String(format!("{}{}", n, sufixo)))
+}
+
+/// Registra laptime, gap, decimal e ordinal no Tera.
+pub fn registrar(tera: &mut Tera) {
+    tera.register_filter("laptime", laptime);
+    tera.register_filter("gap", gap);
+    tera.register_filter("decimal", decimal);
+    tera.register_filter("ordinal", ordinal);
+}
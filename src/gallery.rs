@@ -0,0 +1,64 @@
+use image::imageops::FilterType;
+use image::ImageFormat;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// --- GALERIA DE FOTOS DO EVENTO ---
+// As fotos da pasta informada em --photos são redimensionadas (versão grande + miniatura)
+// e publicadas sob o prefixo do relatório: reports/<relatorio>/photos/...
+
+const LADO_MAXIMO: u32 = 1600;
+const LADO_MINIATURA: u32 = 400;
+const EXTENSOES: [&str; 4] = ["jpg", "jpeg", "png", "webp"];
+
+#[derive(Serialize)]
+pub struct Foto {
+    pub url: String,
+    pub thumb: String,
+}
+
+pub async fn publicar_galeria(dir: &Path, prefixo: &str) -> Result<Vec<Foto>, Box<dyn Error>> {
+    let mut arquivos: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| EXTENSOES.contains(&e.to_lowercase().as_str()))
+            .unwrap_or(false))
+        .collect();
+    arquivos.sort();
+
+    let local_dir = crate::pasta_saida().join("photos");
+    fs::create_dir_all(&local_dir)?;
+
+    let mut fotos = Vec::new();
+    for (i, arquivo) in arquivos.iter().enumerate() {
+        let img = match image::open(arquivo) {
+            Ok(img) => img,
+            Err(e) => { erro!("⚠️ Foto ignorada ({}): {}", arquivo.display(), e); continue; }
+        };
+        let nome = format!("{:03}.jpg", i + 1);
+
+        // resize() mantém a proporção dentro do limite informado
+        let grande = local_dir.join(&nome);
+        img.resize(LADO_MAXIMO, LADO_MAXIMO, FilterType::Lanczos3).to_rgb8().save_with_format(&grande, ImageFormat::Jpeg)?;
+        let miniatura = local_dir.join(format!("thumb_{}", nome));
+        img.thumbnail(LADO_MINIATURA, LADO_MINIATURA).to_rgb8().save_with_format(&miniatura, ImageFormat::Jpeg)?;
+
+        let key_grande = format!("{}/photos/{}", prefixo, nome);
+        let key_miniatura = format!("{}/photos/thumbs/{}", prefixo, nome);
+        // Reprocessar a mesma prova não reenvia as fotos que já estão no bucket
+        crate::storage::upload_se_mudou(&grande, &key_grande).await?;
+        crate::storage::upload_se_mudou(&miniatura, &key_miniatura).await?;
+
+        // No modo privado o bucket não é público: as fotos também vão com URL assinada
+        fotos.push(Foto {
+            url: crate::private_mode::link(&key_grande).await?,
+            thumb: crate::private_mode::link(&key_miniatura).await?,
+        });
+    }
+
+    saida!("📸 Galeria publicada com {} foto(s)", fotos.len());
+    Ok(fotos)
+}
@@ -0,0 +1,108 @@
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::history::{Historico, VoltaRanking};
+use crate::season::Campeao;
+
+// --- HALL DA FAMA DO CLUBE ---
+
+const TAMANHO_LISTA: usize = 10;
+
+#[derive(Serialize)]
+pub struct Contagem {
+    pub nome: String,
+    pub total: usize,
+}
+
+#[derive(Serialize)]
+pub struct RecordistaPista {
+    pub track: String,
+    pub volta: VoltaRanking,
+}
+
+#[derive(Serialize)]
+pub struct GrandeAtuacao {
+    pub nome: String,
+    pub indice: f64,
+    pub titulo: String,
+    pub track: String,
+    pub data: String,
+}
+
+#[derive(Serialize)]
+pub struct HallDaFama {
+    pub total_provas: usize,
+    /// Campeões de cada temporada (season.rs), da mais recente para a mais antiga
+    pub campeoes: Vec<Campeao>,
+    pub vitorias: Vec<Contagem>,
+    pub podios: Vec<Contagem>,
+    pub participacoes: Vec<Contagem>,
+    pub sequencia_vitorias: Option<Contagem>,
+    pub recordistas: Vec<RecordistaPista>,
+    pub grandes_atuacoes: Vec<GrandeAtuacao>,
+}
+
+fn ordenar(contagem: HashMap<String, usize>) -> Vec<Contagem> {
+    let mut lista: Vec<Contagem> = contagem.into_iter().map(|(nome, total)| Contagem { nome, total }).collect();
+    lista.sort_by(|a, b| b.total.cmp(&a.total).then(a.nome.cmp(&b.nome)));
+    lista.truncate(TAMANHO_LISTA);
+    lista
+}
+
+pub fn montar(historico: &Historico, club: &str, campeoes: Vec<Campeao>) -> HallDaFama {
+    let eventos: Vec<_> = historico.eventos.iter().filter(|e| e.club == club).collect();
+
+    let mut vitorias = HashMap::new();
+    let mut podios = HashMap::new();
+    let mut participacoes = HashMap::new();
+    let mut melhor_sequencia: Option<Contagem> = None;
+    let mut sequencia_atual: (String, usize) = (String::new(), 0);
+
+    // O histórico já vem em ordem cronológica, então a sequência de vitórias é direta
+    for e in &eventos {
+        for r in &e.resultados {
+            *participacoes.entry(r.nome.clone()).or_insert(0) += 1;
+            if r.posicao <= 3 { *podios.entry(r.nome.clone()).or_insert(0) += 1; }
+        }
+        if let Some(vencedor) = e.resultados.first() {
+            *vitorias.entry(vencedor.nome.clone()).or_insert(0) += 1;
+            if sequencia_atual.0 == vencedor.nome {
+                sequencia_atual.1 += 1;
+            } else {
+                sequencia_atual = (vencedor.nome.clone(), 1);
+            }
+            if melhor_sequencia.as_ref().map(|m| sequencia_atual.1 > m.total).unwrap_or(true) {
+                melhor_sequencia = Some(Contagem { nome: sequencia_atual.0.clone(), total: sequencia_atual.1 });
+            }
+        }
+    }
+
+    let pistas: BTreeSet<&str> = eventos.iter().map(|e| e.track.as_str()).collect();
+    let recordistas = pistas.into_iter()
+        .filter_map(|track| historico.top_voltas(club, track, 1).into_iter().next()
+            .map(|volta| RecordistaPista { track: track.to_string(), volta }))
+        .collect();
+
+    let mut grandes_atuacoes: Vec<GrandeAtuacao> = eventos.iter()
+        .flat_map(|e| e.resultados.iter().map(move |r| GrandeAtuacao {
+            nome: r.nome.clone(),
+            indice: r.indice,
+            titulo: e.titulo.clone(),
+            track: e.track.clone(),
+            data: e.data.clone(),
+        }))
+        .collect();
+    grandes_atuacoes.sort_by(|a, b| b.indice.partial_cmp(&a.indice).unwrap_or(std::cmp::Ordering::Equal));
+    grandes_atuacoes.truncate(5);
+
+    HallDaFama {
+        total_provas: eventos.len(),
+        campeoes,
+        vitorias: ordenar(vitorias),
+        podios: ordenar(podios),
+        participacoes: ordenar(participacoes),
+        sequencia_vitorias: melhor_sequencia,
+        recordistas,
+        grandes_atuacoes,
+    }
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+// --- HISTÓRICO DE EVENTOS ---
+// Arquivo JSON local com o resumo de cada prova processada. Serve de base para
+// comparações entre pistas, recordes e páginas de temporada.
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Historico {
+    pub eventos: Vec<EventoHistorico>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EventoHistorico {
+    pub timestamp: String,
+    pub club: String,
+    pub track: String,
+    pub titulo: String,
+    pub data: String,
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub report_link: String,
+    pub resultados: Vec<ResultadoHistorico>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ResultadoHistorico {
+    pub nome: String,
+    pub posicao: usize,
+    pub total_laps: i64,
+    pub best_lap: f64,
+    /// Índice de performance normalizado (0-100), comparável entre pistas
+    pub indice: f64,
+    /// Voltas descontadas por penalidades aplicadas depois da prova (API de direção de prova)
+    #[serde(default)]
+    pub penalidades: i64,
+    /// Pontos provisórios: associação vencida na data da prova
+    #[serde(default)]
+    pub provisorio: bool,
+    /// Convidado: fica no resultado, mas não entra na classificação do campeonato
+    #[serde(default)]
+    pub convidado: bool,
+    /// DNF, DNS ou DSQ (ranking::STATUS); vazio para quem terminou
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub status: String,
+    /// Voltas mais rápidas do piloto na prova, com a fenda (no máximo top_laps::TOP_VOLTAS).
+    /// Vazia nas provas registradas antes dela: vale então só o `best_lap`, sem fenda
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub voltas: Vec<VoltaHistorico>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VoltaHistorico {
+    pub tempo: f64,
+    /// Nome da fenda (config::fendas_pista)
+    pub fenda: String,
+}
+
+/// Entrada do ranking de voltas de todos os tempos de uma pista.
+#[derive(Serialize, Clone)]
+pub struct VoltaRanking {
+    pub posicao: usize,
+    pub nome: String,
+    pub tempo: f64,
+    /// Fenda da volta; vazia nas provas registradas sem as voltas
+    pub fenda: String,
+    pub titulo: String,
+    pub data: String,
+}
+
+pub fn caminho_historico() -> PathBuf {
+    env::var_os("HISTORY_PATH").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("history").join("history.json"))
+}
+
+impl EventoHistorico {
+    /// Reordena a classificação depois de uma correção (mesmo critério do relatório, classification.rs).
+    pub fn reclassificar(&mut self) {
+        crate::classification::criterio().reclassificar(&mut self.resultados);
+        for (i, r) in self.resultados.iter_mut().enumerate() { r.posicao = i + 1; }
+    }
+}
+
+impl Historico {
+    pub fn carregar() -> Historico {
+        fs::read_to_string(caminho_historico()).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn salvar(&self) -> Result<(), Box<dyn Error>> {
+        let path = caminho_historico();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        // Grava em arquivo temporário e renomeia para não corromper o histórico
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Insere o evento ou substitui a versão anterior (reprocessamento do mesmo .INI).
+    pub fn registrar(&mut self, evento: EventoHistorico) {
+        self.eventos.retain(|e| !(e.timestamp == evento.timestamp && e.club == evento.club && e.track == evento.track));
+        self.eventos.push(evento);
+        self.eventos.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    }
+
+    /// As `n` voltas mais rápidas da pista, volta a volta (um piloto pode aparecer várias vezes).
+    pub fn top_voltas(&self, club: &str, track: &str, n: usize) -> Vec<VoltaRanking> {
+        let mut voltas: Vec<VoltaRanking> = Vec::new();
+        for e in self.eventos.iter().filter(|e| e.club == club && e.track == track) {
+            for r in &e.resultados {
+                let volta = |tempo: f64, fenda: &str| VoltaRanking {
+                    posicao: 0,
+                    nome: r.nome.clone(),
+                    tempo,
+                    fenda: fenda.to_string(),
+                    titulo: e.titulo.clone(),
+                    data: e.data.clone(),
+                };
+                if r.voltas.is_empty() {
+                    if r.best_lap > 0.0 { voltas.push(volta(r.best_lap, "")); }
+                } else {
+                    voltas.extend(r.voltas.iter().filter(|v| v.tempo > 0.0).map(|v| volta(v.tempo, &v.fenda)));
+                }
+            }
+        }
+        voltas.sort_by(|a, b| a.tempo.total_cmp(&b.tempo));
+        voltas.truncate(n);
+        for (i, v) in voltas.iter_mut().enumerate() { v.posicao = i + 1; }
+        voltas
+    }
+
+    /// Melhor volta já registrada na pista (recorde), ignorando tempos zerados.
+    pub fn recorde_pista(&self, club: &str, track: &str) -> Option<f64> {
+        self.eventos.iter()
+            .filter(|e| e.club == club && e.track == track)
+            .flat_map(|e| e.resultados.iter())
+            .map(|r| r.best_lap)
+            .filter(|t| *t > 0.0)
+            .min_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+    }
+}
+
+/// Classificação pela soma dos índices de performance das provas informadas.
+pub fn classificacao(eventos: &[&EventoHistorico]) -> Vec<(String, f64)> {
+    let mut pontos: std::collections::HashMap<String, f64> = std::collections::HashMap::new();
+    for e in eventos {
+        for r in e.resultados.iter().filter(|r| !r.convidado && r.status != "DNS" && r.status != "DSQ") {
+            *pontos.entry(r.nome.clone()).or_insert(0.0) += r.indice;
+        }
+    }
+    let mut tabela: Vec<(String, f64)> = pontos.into_iter().collect();
+    tabela.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+    tabela
+}
+
+/// Pilotos com algum resultado provisório nas provas informadas.
+pub fn provisorios(eventos: &[&EventoHistorico]) -> std::collections::HashSet<String> {
+    eventos.iter()
+        .flat_map(|e| e.resultados.iter())
+        .filter(|r| r.provisorio)
+        .map(|r| r.nome.clone())
+        .collect()
+}
+
+/// Média entre a velocidade relativa ao recorde da pista e as voltas relativas ao vencedor.
+/// 100 significa igualar o recorde e vencer a prova.
+pub fn calcular_indice(best_lap: f64, recorde: f64, laps: i64, laps_vencedor: i64) -> f64 {
+    let ritmo = if best_lap > 0.0 && recorde > 0.0 { (recorde / best_lap * 100.0).min(100.0) } else { 0.0 };
+    let distancia = if laps_vencedor > 0 { laps as f64 / laps_vencedor as f64 * 100.0 } else { 0.0 };
+    ((ritmo + distancia) / 2.0 * 10.0).round() / 10.0
+}
+
+/// Data/hora atual (UTC) no mesmo formato dos timestamps dos .INI: YYYYMMDDHHMMSS.
+pub fn agora_timestamp() -> String {
+    timestamp_de(std::time::SystemTime::now())
+}
+
+/// Instante (UTC) no formato AAAAMMDDhhmmss.
+pub fn timestamp_de(instante: std::time::SystemTime) -> String {
+    let secs = instante
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let (dias, resto) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Conversão dias -> data civil (algoritmo de Howard Hinnant)
+    let z = dias + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let dia = doy - (153 * mp + 2) / 5 + 1;
+    let mes = if mp < 10 { mp + 3 } else { mp - 9 };
+    let ano = yoe + era * 400 + if mes <= 2 { 1 } else { 0 };
+
+    format!("{:04}{:02}{:02}{:02}{:02}{:02}", ano, mes, dia, resto / 3600, (resto % 3600) / 60, resto % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resultado(nome: &str, best_lap: f64, voltas: &[(f64, &str)]) -> ResultadoHistorico {
+        ResultadoHistorico {
+            nome: nome.to_string(),
+            posicao: 1,
+            total_laps: 0,
+            best_lap,
+            indice: 0.0,
+            penalidades: 0,
+            provisorio: false,
+            convidado: false,
+            status: String::new(),
+            voltas: voltas.iter().map(|(tempo, fenda)| VoltaHistorico { tempo: *tempo, fenda: fenda.to_string() }).collect(),
+        }
+    }
+
+    fn evento(timestamp: &str, resultados: Vec<ResultadoHistorico>) -> EventoHistorico {
+        EventoHistorico {
+            timestamp: timestamp.to_string(),
+            club: "Clube".to_string(),
+            track: "Pista".to_string(),
+            titulo: format!("Prova {}", timestamp),
+            data: String::new(),
+            slug: String::new(),
+            report_link: String::new(),
+            resultados,
+        }
+    }
+
+    #[test]
+    fn top_voltas_volta_a_volta() {
+        let historico = Historico { eventos: vec![
+            // Prova antiga, sem as voltas: entra o best_lap, sem fenda
+            evento("20250101000000", vec![resultado("Ana", 5.05, &[])]),
+            evento("20250201000000", vec![
+                resultado("Ana", 4.9, &[(4.9, "Verde"), (5.0, "Azul")]),
+                resultado("Bruno", 4.95, &[(4.95, "Amarela")]),
+            ]),
+        ] };
+        let top = historico.top_voltas("Clube", "Pista", 3);
+        let linhas: Vec<(usize, &str, f64, &str)> = top.iter().map(|v| (v.posicao, v.nome.as_str(), v.tempo, v.fenda.as_str())).collect();
+        assert_eq!(linhas, [(1, "Ana", 4.9, "Verde"), (2, "Bruno", 4.95, "Amarela"), (3, "Ana", 5.0, "Azul")]);
+        assert_eq!(historico.top_voltas("Clube", "Pista", 10).last().map(|v| v.fenda.as_str()), Some(""));
+    }
+}
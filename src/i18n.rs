@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tera::Tera;
+use toml::{Table, Value};
+
+// --- IDIOMA DOS RELATÓRIOS (i18n) ---
+// RACEDAY_LOCALE escolhe o idioma dos textos gerados: títulos e colunas do
+// relatório, insights, nomes padrão das fendas, card, Markdown e WhatsApp.
+//   pt-BR (padrão) | en | es
+// As tabelas ficam em locales/<idioma>.toml e vão embutidas no binário; um
+// arquivo com o mesmo nome em locales/ (ao lado do executável) sobrescreve as
+// chaves que definir, então o clube pode ajustar um texto ou criar um idioma
+// novo sem recompilar. Chave que faltar cai para o pt-BR.
+//
+// Nos templates: {{ t(key="report.standings") }}; marcadores {nome} da frase
+// são preenchidos pelos demais argumentos: {{ t(key="report.pace", janela=5) }}.
+
+const PADRAO: &str = "pt-BR";
+
+const EMBUTIDOS: [(&str, &str); 3] = [
+    ("pt-BR", include_str!("../locales/pt-BR.toml")),
+    ("en", include_str!("../locales/en.toml")),
+    ("es", include_str!("../locales/es.toml")),
+];
+
+static TABELA: OnceLock<Table> = OnceLock::new();
+
+pub fn locale() -> String {
+    env::var("RACEDAY_LOCALE").ok().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).unwrap_or_else(|| PADRAO.to_string())
+}
+
+/// Sobrepõe `outra` em `base`, tabela a tabela.
+fn mesclar(base: &mut Table, outra: Table) {
+    for (chave, valor) in outra {
+        match (base.get_mut(&chave), valor) {
+            (Some(Value::Table(b)), Value::Table(o)) => mesclar(b, o),
+            (_, valor) => { base.insert(chave, valor); }
+        }
+    }
+}
+
+fn carregar() -> Table {
+    let loc = locale();
+    let embutido = |nome: &str| EMBUTIDOS.iter().find(|(l, _)| l.eq_ignore_ascii_case(nome))
+        .and_then(|(_, texto)| texto.parse::<Table>().ok());
+
+    let mut tabela = embutido(PADRAO).unwrap_or_default();
+    let mut encontrado = loc.eq_ignore_ascii_case(PADRAO);
+    if let Some(t) = (!encontrado).then(|| embutido(&loc)).flatten() {
+        mesclar(&mut tabela, t);
+        encontrado = true;
+    }
+
+    let arquivo = crate::recurso(&PathBuf::from("locales").join(format!("{}.toml", loc)));
+    if arquivo.is_file() {
+        match fs::read_to_string(&arquivo).map_err(|e| e.to_string()).and_then(|s| s.parse::<Table>().map_err(|e| e.to_string())) {
+            Ok(t) => {
+                mesclar(&mut tabela, t);
+                encontrado = true;
+            }
+            Err(e) => erro!("⚠️ Idioma {} ignorado ({}): {}", loc, arquivo.display(), e),
+        }
+    }
+    if !encontrado {
+        erro!("⚠️ Idioma '{}' desconhecido (embutidos: {}); usando {}", loc, EMBUTIDOS.map(|(l, _)| l).join(", "), PADRAO);
+    }
+    tabela
+}
+
+fn buscar(chave: &str) -> Option<&'static Value> {
+    let mut partes = chave.split('.');
+    let mut valor = TABELA.get_or_init(carregar).get(partes.next()?)?;
+    for parte in partes {
+        valor = valor.as_table()?.get(parte)?;
+    }
+    Some(valor)
+}
+
+/// Texto da chave ("report.standings"); a própria chave se não existir.
+pub fn t(chave: &str) -> String {
+    buscar(chave).and_then(|v| v.as_str()).map(|s| s.to_string()).unwrap_or_else(|| chave.to_string())
+}
+
+/// Como `t`, preenchendo os marcadores {chave} da frase.
+pub fn tf(chave: &str, valores: &[(&str, String)]) -> String {
+    let mut texto = t(chave);
+    for (marcador, valor) in valores {
+        texto = texto.replace(&format!("{{{}}}", marcador), valor);
+    }
+    texto
+}
+
+/// Lista de textos ("slots"); vazia se não existir.
+pub fn lista(chave: &str) -> Vec<String> {
+    buscar(chave).and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default()
+}
+
+/// Registra `t(key="...", ...)` no Tera.
+pub fn registrar(tera: &mut Tera) {
+    tera.register_function("t", |args: &HashMap<String, tera::Value>| {
+        let chave = args.get("key").and_then(|v| v.as_str())
+            .ok_or_else(|| tera::Error::msg("t() requer o argumento key"))?;
+        let valores: Vec<(&str, String)> = args.iter()
+            .filter(|(k, _)| k.as_str() != "key")
+            .map(|(k, v)| (k.as_str(), v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string())))
+            .collect();
+        Ok(tera::Value::String(tf(chave, &valores)))
+    });
+}
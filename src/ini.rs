@@ -0,0 +1,321 @@
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::Path;
+
+use crate::error::RacedayError;
+use crate::pilots::slug_piloto;
+
+// --- LEITURA NATIVA DO .INI DO CRONOMETRADOR ---
+// Mesmo JSON intermediário que o scripts/raceday-prep.py gerava, sem depender
+// de Python no notebook da pista. O formato segue o configparser do Python:
+// chaves em minúsculas, "=" ou ":" como separador, comentários com # ou ;,
+// seções repetidas são mescladas e a última chave vence.
+
+const SECOES_OBRIGATORIAS: [&str; 5] = ["config", "pilots", "races", "gp_result_pilots", "gp_result_laps"];
+const BASE_RELATORIOS: &str = "https://pub-598608239bfb446e841ac7b4290a0223.r2.dev/reports";
+
+// Windows-1252 de 0x80 a 0x9F; o resto coincide com Latin-1
+const CP1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+pub struct Ini {
+    secoes: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl Ini {
+    pub fn interpretar(texto: &str) -> Ini {
+        let mut secoes: Vec<(String, Vec<(String, String)>)> = Vec::new();
+        let mut atual: Option<usize> = None;
+        let mut ultima_chave: Option<usize> = None;
+
+        for linha in texto.trim_start_matches('\u{feff}').lines() {
+            let limpa = linha.trim();
+            if limpa.is_empty() || limpa.starts_with('#') || limpa.starts_with(';') { continue; }
+
+            // Linha recuada continua o valor anterior
+            if let (true, Some(s), Some(k)) = (linha.starts_with(char::is_whitespace), atual, ultima_chave) {
+                let valor = &mut secoes[s].1[k].1;
+                valor.push('\n');
+                valor.push_str(limpa);
+                continue;
+            }
+
+            if limpa.starts_with('[') && limpa.ends_with(']') {
+                let nome = limpa[1..limpa.len() - 1].to_string();
+                atual = Some(match secoes.iter().position(|(n, _)| *n == nome) {
+                    Some(i) => i,
+                    None => { secoes.push((nome, Vec::new())); secoes.len() - 1 }
+                });
+                ultima_chave = None;
+                continue;
+            }
+
+            let Some(s) = atual else { continue };
+            let Some(pos) = limpa.find(['=', ':']) else { continue };
+            let chave = limpa[..pos].trim().to_lowercase();
+            let valor = limpa[pos + 1..].trim().to_string();
+            let entradas = &mut secoes[s].1;
+            ultima_chave = Some(match entradas.iter().position(|(k, _)| *k == chave) {
+                Some(i) => { entradas[i].1 = valor; i }
+                None => { entradas.push((chave, valor)); entradas.len() - 1 }
+            });
+        }
+        Ini { secoes }
+    }
+
+    /// Arquivo que não abre é erro de entrada (`Entrada`).
+    pub fn ler(path: &Path) -> Result<Ini, RacedayError> {
+        let bytes = fs::read(path)
+            .map_err(|e| RacedayError::Entrada(format!("⚠️ Erro ao ler o INI {}: {}", path.display(), e)))?;
+        // UTF-8 primeiro; arquivos de versões antigas do cronometrador vêm em Windows-1252
+        let texto = match String::from_utf8(bytes) {
+            Ok(t) => t,
+            Err(e) => e.into_bytes().iter().map(|&b| match b {
+                0x80..=0x9F => CP1252[(b - 0x80) as usize],
+                _ => b as char,
+            }).collect(),
+        };
+        Ok(Ini::interpretar(&texto))
+    }
+
+    pub fn secao(&self, nome: &str) -> Option<&[(String, String)]> {
+        self.secoes.iter().find(|(n, _)| n == nome).map(|(_, e)| e.as_slice())
+    }
+
+    pub fn valor(&self, secao: &str, chave: &str) -> Option<&str> {
+        self.secao(secao)?.iter().find(|(k, _)| k == chave).map(|(_, v)| v.as_str())
+    }
+
+    fn mapa(&self, secao: &str) -> Value {
+        let entradas = self.secao(secao).unwrap_or(&[]);
+        Value::Object(entradas.iter().map(|(k, v)| (k.clone(), Value::String(v.clone()))).collect::<Map<_, _>>())
+    }
+}
+
+fn slug(texto: &str) -> String {
+    let s = slug_piloto(texto);
+    if s.is_empty() { "corrida".to_string() } else { s }
+}
+
+fn inteiro(valor: &str) -> i64 {
+    valor.trim().parse().unwrap_or(0)
+}
+
+/// O cronometrador grava os tempos em décimos de milésimo (123456 -> 12.3456s).
+fn segundos(bruto: i64) -> f64 {
+    if bruto > 0 { bruto as f64 / 10000.0 } else { 0.0 }
+}
+
+/// Tempos volta a volta ("123456,124001,...") convertidos para segundos.
+fn tempos_de_volta(valor: &str) -> Vec<f64> {
+    valor.split([',', ';', ' ', '\t', '\n'])
+        .map(|t| segundos(inteiro(t)))
+        .filter(|t| *t > 0.0)
+        .collect()
+}
+
+/// Primeira sequência de 14 dígitos do nome do arquivo (YYYYMMDDHHMMSS).
+fn timestamp_do_arquivo(path: &Path) -> Option<String> {
+    let nome = path.file_name()?.to_string_lossy().to_string();
+    let bytes = nome.as_bytes();
+    (0..bytes.len().saturating_sub(13))
+        .find(|&i| bytes[i..i + 14].iter().all(u8::is_ascii_digit))
+        .map(|i| nome[i..i + 14].to_string())
+}
+
+/// "slot_3_best" -> 3
+fn numero_slot(chave: &str) -> Option<usize> {
+    let resto = chave.strip_prefix("slot_")?;
+    let fim = resto.find(|c: char| !c.is_ascii_digit())?;
+    if fim == 0 || !resto[fim..].starts_with('_') { return None; }
+    resto[..fim].parse().ok()
+}
+
+/// "race_2_1" -> (2, 1)
+fn numero_sessao(secao: &str) -> Option<(u32, u32)> {
+    let (bateria, sessao) = secao.strip_prefix("race_")?.split_once('_')?;
+    let numero = |s: &str| (!s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())).then(|| s.parse::<u32>().ok()).flatten();
+    Some((numero(bateria)?, numero(sessao)?))
+}
+
+/// JSON da prova a partir do .INI. Seções obrigatórias faltando dão `Leitura`.
+pub fn converter(path: &Path, club: &str, track: &str) -> Result<Value, RacedayError> {
+    let ini = Ini::ler(path)?;
+
+    // --- 1. VALIDAÇÃO DE INTEGRIDADE ---
+    let faltando: Vec<&str> = SECOES_OBRIGATORIAS.iter().copied().filter(|s| ini.secao(s).is_none()).collect();
+    if !faltando.is_empty() || ini.secao("race_1_1").is_none() {
+        return Err(RacedayError::Leitura(format!("⚠️ Erro ao ler o INI: arquivo incompleto. Faltam: {}", faltando.join(", "))));
+    }
+
+    // --- 2. METADADOS E SLUGS ---
+    let titulo = ini.valor("config", "name").unwrap_or("Corrida").trim_matches('"').to_string();
+    let slug_evento = slug(&titulo);
+    let timestamp = timestamp_do_arquivo(path).unwrap_or_else(crate::history::agora_timestamp);
+    let report_filename = format!("{}_{}_{}_{}.html", slug(club), slug(track), slug_evento, timestamp);
+
+    let max_slot = ini.secoes.iter()
+        .flat_map(|(_, entradas)| entradas.iter().filter_map(|(k, _)| numero_slot(k)))
+        .max()
+        .unwrap_or(0);
+
+    // Hora em que o cronometrador gravou o .INI, e não a desta execução: o mesmo
+    // arquivo gera sempre o mesmo JSON (storage::inalterado)
+    let gerado = std::fs::metadata(path).and_then(|m| m.modified())
+        .map(crate::history::timestamp_de)
+        .unwrap_or_else(|_| crate::history::agora_timestamp());
+    let gerado_em = format!("{}-{}-{}T{}:{}:{}+00:00", &gerado[0..4], &gerado[4..6], &gerado[6..8], &gerado[8..10], &gerado[10..12], &gerado[12..14]);
+
+    // --- 3. RANKING OFICIAL ---
+    let official_ranking: Vec<Value> = ini.secao("gp_result_pilots").unwrap_or(&[]).iter().map(|(p_id, nome)| json!({
+        "p_id": p_id,
+        "name": nome.trim_matches('"'),
+        "laps": inteiro(ini.valor("gp_result_laps", p_id).unwrap_or("0")),
+        "gap": ini.valor("gp_result_gap", p_id).unwrap_or("0"),
+        "best_lap": segundos(inteiro(ini.valor("gp_result_best_times", p_id).unwrap_or("0"))),
+    })).collect();
+
+    // --- 4. SESSÕES DE BATERIA ---
+    let mut sessoes: Vec<(u32, u32, &str)> = ini.secoes.iter()
+        .filter_map(|(nome, _)| numero_sessao(nome).map(|(r, s)| (r, s, nome.as_str())))
+        .collect();
+    sessoes.sort();
+    let mut races: Vec<Value> = Vec::new();
+    for (r_id, s_id, secao) in sessoes {
+        if races.last().and_then(|r| r["race_id"].as_u64()) != Some(r_id as u64) {
+            races.push(json!({ "race_id": r_id, "name": format!("Bateria {}", r_id), "sessions": [] }));
+        }
+        let mut slots = Map::new();
+        for slot in 1..=max_slot {
+            let campo = |nome: &str| ini.valor(secao, &format!("slot_{}_{}", slot, nome));
+            let piloto = campo("pilot_name").unwrap_or("").trim();
+            if piloto.is_empty() { continue; }
+            slots.insert(slot.to_string(), json!({
+                "p_id": campo("pilot_number").unwrap_or("0"),
+                "name": piloto,
+                "laps": inteiro(campo("laps").unwrap_or("0")),
+                "best": segundos(inteiro(campo("best").unwrap_or("0"))),
+                // Saídas de pista registradas pelos fiscais no cronometrador
+                "deslots": inteiro(campo("deslots").unwrap_or("0")),
+                "lap_times": tempos_de_volta(campo("lap_times").unwrap_or("")),
+            }));
+        }
+        if let Some(Value::Array(lista)) = races.last_mut().map(|r| &mut r["sessions"]) {
+            lista.push(json!({ "session": s_id, "slots": slots }));
+        }
+    }
+
+    Ok(json!({
+        "org_car_version": "1.1",
+        "club": club.to_uppercase(),
+        "track": track.to_uppercase(),
+        "report_link": format!("{}/{}", BASE_RELATORIOS, report_filename),
+        "event": {
+            "title": titulo,
+            "slug": slug_evento,
+            "date": ini.valor("config", "date").unwrap_or(""),
+            "timestamp": timestamp,
+        },
+        "metadata": {
+            "slots": max_slot,
+            "generated_at": gerado_em,
+            "report_filename": report_filename,
+        },
+        "official_ranking": official_ranking,
+        "pilots": ini.secao("pilots").unwrap_or(&[]).iter()
+            .map(|(k, v)| (k.clone(), json!({ "name": v.trim_matches('"') })))
+            .collect::<Map<_, _>>(),
+        "races": races,
+        "raw_results": {
+            "laps": ini.mapa("gp_result_laps"),
+            "best_times": ini.mapa("gp_result_best_times"),
+            "gaps": ini.mapa("gp_result_gap"),
+            "zones": ini.mapa("gp_result_zone"),
+            "penaltys": ini.mapa("gp_result_penaltys"),
+            "deslots": ini.mapa("gp_result_deslots"),
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXEMPLO: &str = "\u{feff}[config]\nName = \"GP Teste\"\ndate: 2026-10-16\n; comentário\n\n\
+        [pilots]\n1 = \"Ana\"\n2 = \"Bia\"\n\
+        [gp_result_pilots]\n1 = Ana\n2 = Bia\n\
+        [gp_result_laps]\n1 = 40\n2 = 39\n\
+        [gp_result_best_times]\n1 = 51234\n\
+        [races]\n1 = Bateria 1\n\
+        [race_1_1]\nslot_1_pilot_name = Ana\nslot_1_pilot_number = 1\nslot_1_laps = 20\nslot_1_best = 51234\n\
+        slot_1_lap_times = 52000,51234\n  53000\n\
+        slot_2_pilot_name = Bia\nslot_2_pilot_number = 2\nslot_2_laps = 19\n\
+        [race_1_2]\nslot_1_pilot_name = Bia\nslot_1_pilot_number = 2\nslot_1_laps = 20\n\
+        [config]\nname = \"GP Final\"\n";
+
+    #[test]
+    fn interpreta_como_o_configparser() {
+        let ini = Ini::interpretar(EXEMPLO);
+        // Seção repetida mesclada, chave em minúsculas, a última vence
+        assert_eq!(ini.valor("config", "name"), Some("\"GP Final\""));
+        assert_eq!(ini.valor("config", "date"), Some("2026-10-16"));
+        // Linha recuada continua o valor anterior
+        assert_eq!(ini.valor("race_1_1", "slot_1_lap_times"), Some("52000,51234\n53000"));
+        assert_eq!(tempos_de_volta(ini.valor("race_1_1", "slot_1_lap_times").unwrap()), vec![5.2, 5.1234, 5.3]);
+        assert!(ini.secao("inexistente").is_none());
+    }
+
+    #[test]
+    fn nomes_de_slot_sessao_e_arquivo() {
+        assert_eq!(numero_slot("slot_12_best"), Some(12));
+        assert_eq!(numero_slot("slot_x_best"), None);
+        assert_eq!(numero_slot("slot_3"), None);
+        assert_eq!(numero_sessao("race_2_1"), Some((2, 1)));
+        assert_eq!(numero_sessao("race_2_"), None);
+        assert_eq!(numero_sessao("gp_result_laps"), None);
+        assert_eq!(timestamp_do_arquivo(Path::new("/tmp/GP_20261016193000.INI")).as_deref(), Some("20261016193000"));
+        assert_eq!(timestamp_do_arquivo(Path::new("GP_2026.INI")), None);
+        assert_eq!(segundos(-5), 0.0);
+    }
+
+    #[test]
+    fn le_windows_1252() {
+        let path = std::env::temp_dir().join(format!("raceday_ini_{}_cp1252.INI", std::process::id()));
+        fs::write(&path, b"[pilots]\n1 = Jo\xe3o \x96 \x80\n").unwrap();
+        let ini = Ini::ler(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(ini.valor("pilots", "1"), Some("João – €"));
+    }
+
+    #[test]
+    fn converte_para_o_json_da_prova() {
+        let path = std::env::temp_dir().join(format!("raceday_{}_20261016193000.INI", std::process::id()));
+        fs::write(&path, EXEMPLO).unwrap();
+        let data = converter(&path, "Clube", "Pista").unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(data["event"]["title"], "GP Final");
+        assert_eq!(data["event"]["timestamp"], "20261016193000");
+        assert_eq!(data["metadata"]["report_filename"], "clube_pista_gp_final_20261016193000.html");
+        assert_eq!(data["metadata"]["slots"], 2);
+        assert_eq!(data["official_ranking"][0]["best_lap"], 5.1234);
+        assert_eq!(data["official_ranking"][1]["laps"], 39);
+        assert_eq!(data["pilots"]["2"]["name"], "Bia");
+        let sessoes = data["races"][0]["sessions"].as_array().unwrap();
+        assert_eq!(sessoes.len(), 2);
+        assert_eq!(sessoes[0]["slots"]["2"]["laps"], 19);
+        assert!(sessoes[1]["slots"].get("2").is_none());
+    }
+
+    #[test]
+    fn arquivo_incompleto() {
+        let path = std::env::temp_dir().join(format!("raceday_ini_{}_incompleto.INI", std::process::id()));
+        fs::write(&path, "[config]\nname = X\n[pilots]\n").unwrap();
+        let erro = converter(&path, "Clube", "Pista").unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(&erro, RacedayError::Leitura(m) if m.contains("races") && m.contains("gp_result_laps")), "{}", erro);
+        assert!(matches!(converter(&path, "Clube", "Pista"), Err(RacedayError::Entrada(_))));
+    }
+}
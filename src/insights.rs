@@ -0,0 +1,259 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+use crate::history::Historico;
+use crate::i18n;
+use crate::ranking::PilotoDisplay;
+use crate::records::RecordeBatido;
+
+// --- INSIGHTS DO RELATÓRIO ---
+// INSIGHTS_STYLE escolhe o tom ("playful", padrão, ou "formal"); as frases vêm
+// do idioma dos relatórios (RACEDAY_LOCALE, ver i18n.rs).
+// INSIGHTS_TEMPLATES aponta para um JSON com frases próprias por tipo de insight,
+// usando marcadores como {nome} e {tempo}. Uma frase vazia desliga aquele insight.
+// INSIGHTS_MAX limita quantos insights aparecem no relatório.
+//
+// Cada tipo é uma regra avaliada sobre a prova processada. INSIGHTS_RULES escolhe
+// quais regras rodam e em que ordem (padrão: todas, na ordem de TIPOS):
+//   INSIGHTS_RULES="winner,first_win,comeback,closest_gap,best_lap"
+// Regras novas e seus marcadores:
+//   comeback     maior recuperação desde a 1ª bateria ({nome}, {de}, {para}, {posicoes})
+//   closest_gap  disputa mais apertada entre posições vizinhas ({posicao}, {nome}, {anterior}, {diferenca})
+//   penalties    quem mais levou penalidades ({nome}, {penalidades}, {total})
+//   first_win    primeira vitória do piloto no clube ({nome}, {voltas}); vem do
+//                histórico, marcada pelo pipeline em data["first_win"]
+
+const TIPOS: [&str; 9] = ["best_lap", "track_record", "winner", "first_win", "comeback", "closest_gap", "penalties", "reliability", "consistency"];
+
+/// Posições ganhas a partir das quais a recuperação vira insight.
+const RECUPERACAO_MINIMA: usize = 2;
+
+type Valores = Vec<(&'static str, String)>;
+
+/// Frases do idioma configurado (locales/<idioma>.toml, [insights.playful] ou [insights.formal]).
+fn frases_padrao(estilo: &str) -> HashMap<String, String> {
+    let secao = if estilo == "formal" { "formal" } else { "playful" };
+    TIPOS.iter().map(|tipo| (tipo.to_string(), i18n::t(&format!("insights.{}.{}", secao, tipo)))).collect()
+}
+
+fn carregar_frases() -> HashMap<String, String> {
+    let estilo = env::var("INSIGHTS_STYLE").unwrap_or_else(|_| "playful".to_string());
+    let mut frases = frases_padrao(&estilo);
+
+    if let Ok(path) = env::var("INSIGHTS_TEMPLATES") {
+        match fs::read_to_string(&path).ok().and_then(|s| serde_json::from_str::<HashMap<String, String>>(&s).ok()) {
+            Some(proprias) => {
+                for (tipo, frase) in proprias {
+                    if !TIPOS.contains(&tipo.as_str()) {
+                        erro!("⚠️ Tipo de insight desconhecido em {}: '{}' (tipos: {})", path, tipo, TIPOS.join(", "));
+                        continue;
+                    }
+                    frases.insert(tipo, frase);
+                }
+            }
+            None => erro!("⚠️ Não foi possível ler as frases de insights em {}", path),
+        }
+    }
+    frases
+}
+
+/// Regras ativas, na ordem de INSIGHTS_RULES (padrão: todas).
+fn regras_ativas() -> Vec<&'static str> {
+    let Ok(lista) = env::var("INSIGHTS_RULES") else { return TIPOS.to_vec() };
+    let mut regras = Vec::new();
+    for nome in lista.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        match TIPOS.iter().find(|t| **t == nome) {
+            Some(tipo) if !regras.contains(tipo) => regras.push(*tipo),
+            Some(_) => {}
+            None => erro!("⚠️ Regra de insight desconhecida em INSIGHTS_RULES: '{}' (regras: {})", nome, TIPOS.join(", ")),
+        }
+    }
+    regras
+}
+
+/// Substitui os marcadores {chave} da frase. Retorna `None` se o insight estiver desligado.
+fn formatar(frases: &HashMap<String, String>, tipo: &str, valores: &[(&str, String)]) -> Option<String> {
+    let mut texto = frases.get(tipo)?.clone();
+    if texto.trim().is_empty() { return None; }
+    for (chave, valor) in valores {
+        texto = texto.replace(&format!("{{{}}}", chave), valor);
+    }
+    Some(texto)
+}
+
+/// Saídas de pista a cada 100 voltas (quanto menor, mais confiável o piloto).
+fn taxa_deslots(p: &PilotoDisplay) -> f64 {
+    if p.total_laps <= 0 { return f64::MAX; }
+    p.deslots as f64 * 100.0 / p.total_laps as f64
+}
+
+fn comparar(a: f64, b: f64) -> std::cmp::Ordering {
+    a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+}
+
+/// Diferença em voltas no formato do filtro `gap` (inteira ou com 2 casas).
+fn formatar_voltas(v: f64) -> String {
+    if v.fract() == 0.0 { format!("{}", v as i64) } else { format!("{:.2}", v) }
+}
+
+fn regra_best_lap(ranking: &[PilotoDisplay], _: &Value) -> Vec<Valores> {
+    ranking.iter().find(|p| p.is_overall_best).map(|top| vec![
+        ("nome", top.nome.clone()),
+        ("tempo", top.best_time.clone()),
+        ("fenda", top.best_slot_name.clone()),
+    ]).into_iter().collect()
+}
+
+// --- RECORDES DA PISTA ---
+fn regra_track_record(_: &[PilotoDisplay], data: &Value) -> Vec<Valores> {
+    let batidos: Vec<RecordeBatido> = serde_json::from_value(data["records"].clone()).unwrap_or_default();
+    batidos.iter().map(|r| vec![
+        ("onde", if r.fenda.is_empty() { i18n::t("texts.track_wide") } else { i18n::tf("texts.in_slot", &[("fenda", r.nome_fenda.clone())]) }),
+        ("nome", r.piloto.clone()),
+        ("tempo", format!("{:.3}", r.tempo)),
+        ("anterior", r.anterior.as_ref().map(|a| format!("{:.3}s, {}", a.tempo, a.piloto)).unwrap_or_else(|| i18n::t("texts.first_record"))),
+    ]).collect()
+}
+
+fn regra_winner(ranking: &[PilotoDisplay], _: &Value) -> Vec<Valores> {
+    ranking.first().map(|vencedor| vec![
+        ("nome", vencedor.nome.clone()),
+        ("voltas", vencedor.total_laps.to_string()),
+    ]).into_iter().collect()
+}
+
+fn regra_first_win(ranking: &[PilotoDisplay], data: &Value) -> Vec<Valores> {
+    if !data["first_win"].as_bool().unwrap_or(false) { return Vec::new(); }
+    regra_winner(ranking, data)
+}
+
+/// Vencedor sem nenhuma vitória anterior no clube. Um clube sem provas no
+/// histórico não conta: toda vitória seria "a primeira".
+pub fn primeira_vitoria(historico: &Historico, club: &str, nome: &str, timestamp: &str) -> bool {
+    let anteriores: Vec<_> = historico.eventos.iter().filter(|e| e.club == club && e.timestamp != timestamp).collect();
+    !anteriores.is_empty() && !anteriores.iter().any(|e| e.resultados.first().is_some_and(|r| r.nome == nome))
+}
+
+/// Posição de cada piloto (pelo nome) ao fim da 1ª bateria, pelas voltas dela.
+fn posicoes_primeira_bateria(data: &Value) -> HashMap<String, usize> {
+    let mut voltas: HashMap<String, i64> = HashMap::new();
+    if let Some(pilotos) = data["pilots"].as_object() {
+        for id in pilotos.keys() { voltas.insert(id.clone(), 0); }
+    }
+    let sessoes = data["races"][0]["sessions"].as_array().cloned().unwrap_or_default();
+    for slots in sessoes.iter().filter_map(|s| s["slots"].as_object()) {
+        for s_data in slots.values() {
+            let id = s_data["p_id"].as_str().map(|s| s.to_string()).unwrap_or_else(|| s_data["p_id"].to_string());
+            *voltas.entry(id).or_default() += s_data["laps"].as_i64().unwrap_or(0);
+        }
+    }
+    voltas.iter().map(|(id, l)| {
+        let nome = data["pilots"][id]["name"].as_str().unwrap_or("---").to_string();
+        (nome, voltas.values().filter(|outro| *outro > l).count() + 1)
+    }).collect()
+}
+
+fn regra_comeback(ranking: &[PilotoDisplay], data: &Value) -> Vec<Valores> {
+    if data["races"].as_array().is_none_or(|r| r.len() < 2) { return Vec::new(); }
+    let iniciais = posicoes_primeira_bateria(data);
+    ranking.iter().enumerate()
+        .filter_map(|(i, p)| iniciais.get(&p.nome).map(|de| (p, *de, i + 1)))
+        .filter(|(_, de, para)| de.saturating_sub(*para) >= RECUPERACAO_MINIMA)
+        .max_by_key(|(_, de, para)| (de - para, std::cmp::Reverse(*para)))
+        .map(|(p, de, para)| vec![
+            ("nome", p.nome.clone()),
+            ("de", de.to_string()),
+            ("para", para.to_string()),
+            ("posicoes", (de - para).to_string()),
+        ]).into_iter().collect()
+}
+
+fn regra_closest_gap(ranking: &[PilotoDisplay], _: &Value) -> Vec<Valores> {
+    // Com a classificação oficial vale o gap (com fração de volta); sem ela, as voltas
+    let usa_gap = ranking.iter().any(|p| p.gap_laps > 0.0);
+    ranking.windows(2).enumerate()
+        .map(|(i, par)| {
+            let diferenca = if usa_gap { par[1].gap_laps - par[0].gap_laps } else { (par[0].total_laps - par[1].total_laps) as f64 };
+            (i + 2, &par[1], &par[0], diferenca)
+        })
+        .filter(|(_, _, _, d)| *d > 0.0)
+        .min_by(|a, b| comparar(a.3, b.3))
+        .map(|(posicao, p, anterior, diferenca)| vec![
+            ("posicao", posicao.to_string()),
+            ("nome", p.nome.clone()),
+            ("anterior", anterior.nome.clone()),
+            ("diferenca", formatar_voltas(diferenca)),
+        ]).into_iter().collect()
+}
+
+fn regra_penalties(ranking: &[PilotoDisplay], _: &Value) -> Vec<Valores> {
+    let total: i64 = ranking.iter().map(|p| p.penalties).sum();
+    ranking.iter().filter(|p| p.penalties > 0).max_by_key(|p| p.penalties).map(|p| vec![
+        ("nome", p.nome.clone()),
+        ("penalidades", p.penalties.to_string()),
+        ("total", total.to_string()),
+    ]).into_iter().collect()
+}
+
+// --- SAÍDAS DE PISTA (DESLOTS) ---
+fn regra_reliability(ranking: &[PilotoDisplay], _: &Value) -> Vec<Valores> {
+    if !ranking.iter().any(|p| p.deslots > 0) { return Vec::new(); }
+    let total_saidas: i64 = ranking.iter().map(|p| p.deslots).sum();
+    ranking.iter()
+        .filter(|p| p.total_laps > 0)
+        .min_by(|a, b| comparar(taxa_deslots(a), taxa_deslots(b)))
+        .map(|mais_confiavel| vec![
+            ("total", total_saidas.to_string()),
+            ("nome", mais_confiavel.nome.clone()),
+            ("saidas", mais_confiavel.deslots.to_string()),
+            ("voltas", mais_confiavel.total_laps.to_string()),
+        ]).into_iter().collect()
+}
+
+/// Prêmio Consistência: menor taxa de saídas por 100 voltas entre a metade de cima do grid
+fn regra_consistency(ranking: &[PilotoDisplay], _: &Value) -> Vec<Valores> {
+    if !ranking.iter().any(|p| p.deslots > 0) { return Vec::new(); }
+    let elegiveis = ranking.len().div_ceil(2);
+    ranking.iter()
+        .take(elegiveis)
+        .filter(|p| p.total_laps > 0)
+        .min_by(|a, b| comparar(taxa_deslots(a), taxa_deslots(b)))
+        .map(|consistente| vec![
+            ("nome", consistente.nome.clone()),
+            ("taxa", format!("{:.1}", taxa_deslots(consistente))),
+        ]).into_iter().collect()
+}
+
+fn avaliar(regra: &str, ranking: &[PilotoDisplay], data: &Value) -> Vec<Valores> {
+    match regra {
+        "best_lap" => regra_best_lap(ranking, data),
+        "track_record" => regra_track_record(ranking, data),
+        "winner" => regra_winner(ranking, data),
+        "first_win" => regra_first_win(ranking, data),
+        "comeback" => regra_comeback(ranking, data),
+        "closest_gap" => regra_closest_gap(ranking, data),
+        "penalties" => regra_penalties(ranking, data),
+        "reliability" => regra_reliability(ranking, data),
+        "consistency" => regra_consistency(ranking, data),
+        _ => Vec::new(),
+    }
+}
+
+/// Avalia as regras ativas sobre a prova (data: JSON processado, com
+/// data["records"] e data["first_win"] quando o pipeline os preencheu).
+pub fn gerar(ranking: &[PilotoDisplay], data: &Value) -> Vec<String> {
+    let frases = carregar_frases();
+    let mut insights = Vec::new();
+    for regra in regras_ativas() {
+        for valores in avaliar(regra, ranking, data) {
+            insights.extend(formatar(&frases, regra, &valores));
+        }
+    }
+
+    if let Some(max) = env::var("INSIGHTS_MAX").ok().and_then(|v| v.parse::<usize>().ok()) {
+        insights.truncate(max);
+    }
+    insights
+}
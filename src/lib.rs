@@ -0,0 +1,112 @@
+//! RaceDay: converte o .INI do cronometrador de slot car em JSON e relatórios
+//! HTML, publica tudo no Cloudflare R2 e mantém histórico, portal e páginas de piloto.
+//! O binário `raceday` (main.rs) é só a linha de comando por cima desta biblioteca.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+#[macro_use]
+pub mod console;
+pub mod aliases;
+pub mod assets;
+pub mod audit;
+pub mod auth;
+pub mod backends;
+pub mod batch;
+pub mod card;
+pub mod challenge;
+pub mod classification;
+pub mod compare;
+pub mod config;
+pub mod csv_export;
+pub mod digest;
+pub mod duplicates;
+pub mod entrants;
+pub mod error;
+pub mod export;
+pub mod federation;
+pub mod fees;
+pub mod filters;
+pub mod gallery;
+pub mod hall_of_fame;
+pub mod history;
+pub mod i18n;
+pub mod ini;
+pub mod insights;
+pub mod live;
+pub mod manifest;
+pub mod markdown;
+pub mod marshals;
+pub mod metrics;
+pub mod migrate;
+pub mod notify;
+pub mod offline;
+pub mod og;
+pub mod overrides;
+pub mod pages;
+pub mod pdf;
+pub mod penalties;
+pub mod parallel;
+pub mod pilots;
+pub mod pipeline;
+pub mod portal;
+pub mod post_publish;
+pub mod private_mode;
+pub mod print;
+pub mod privacy;
+pub mod public_api;
+pub mod qr;
+pub mod prep;
+pub mod ranking;
+pub mod rating;
+pub mod rebuild;
+pub mod records;
+pub mod registry;
+pub mod replication;
+pub mod report;
+pub mod report_plugins;
+pub mod schema;
+pub mod season;
+pub mod serve;
+pub mod share;
+pub mod site_index;
+pub mod storage;
+pub mod templates;
+pub mod theme;
+pub mod throttle;
+pub mod top_laps;
+pub mod refresh;
+pub mod videos;
+pub mod views;
+pub mod watch;
+pub mod webhooks;
+pub mod xlsx_export;
+
+pub use ranking::{aplicar_indice, montar_ranking, PilotoDisplay};
+pub use storage::{base_relatorio, chave_prova};
+
+// --- CAMINHOS (PORTÁVEIS ENTRE WINDOWS, MACOS E LINUX) ---
+
+/// Resolve um recurso do projeto (bin/, templates/): primeiro na pasta atual,
+/// depois ao lado do executável (útil no Windows, rodando a partir de outra pasta).
+pub fn recurso(relativo: &Path) -> PathBuf {
+    if relativo.exists() { return relativo.to_path_buf(); }
+    env::current_exe().ok()
+        .and_then(|exe| exe.parent().map(|d| d.join(relativo)))
+        .filter(|p| p.exists())
+        .unwrap_or_else(|| relativo.to_path_buf())
+}
+
+/// Template pelo nome: em TEMPLATES_DIR quando definida, senão em templates/.
+pub fn template(nome: &str) -> PathBuf {
+    match env::var_os("TEMPLATES_DIR") {
+        Some(dir) => PathBuf::from(dir).join(nome),
+        None => recurso(&Path::new("templates").join(nome)),
+    }
+}
+
+/// Pasta local dos artefatos gerados (RACEDAY_OUT_DIR, padrão "temp_out").
+pub fn pasta_saida() -> PathBuf {
+    env::var_os("RACEDAY_OUT_DIR").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("temp_out"))
+}
+
@@ -0,0 +1,162 @@
+use fs_notify::{EventKind, RecursiveMode, Watcher};
+use serde_json::{json, Value};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tera::Tera;
+
+use crate::history::{self, Historico};
+use crate::pipeline::{self, Extras};
+use crate::ranking::{aplicar_indice, montar_ranking};
+use crate::{aliases, assets, entrants, overrides, prep, privacy, public_api, registry, report, storage, webhooks};
+
+// --- CRONOMETRAGEM AO VIVO ---
+// Uso: raceday live <arquivo.ini>
+// O cronometrador regrava o .INI entre as baterias. A cada regravação (depois de
+// WATCH_SETTLE_SECS sem mudanças) a classificação parcial é publicada em
+//   live/<clube>_<pista>.json   status "provisional"
+//   live/<clube>_<pista>.html   relatório com aviso "ao vivo", recarrega a cada 30s
+// e os webhooks de troca de liderança/melhor volta são disparados.
+// Ctrl+C, ou LIVE_IDLE_MIN minutos sem regravação (padrão 15; 0 desliga), encerram
+// a prova: o processamento completo publica em reports/ e o live/ passa a
+// apontar para o relatório final.
+
+const ESPERA_PADRAO_SECS: u64 = 5;
+const OCIOSO_PADRAO_MIN: u64 = 15;
+
+fn chave_live(club: &str, track: &str, extensao: &str) -> String {
+    format!("live/{}_{}.{}", storage::slug_chave(club), storage::slug_chave(track), extensao)
+}
+
+fn hora_utc() -> String {
+    let ts = history::agora_timestamp();
+    format!("{}:{}:{} UTC", &ts[8..10], &ts[10..12], &ts[12..14])
+}
+
+/// Publica a classificação parcial do arquivo como está agora.
+async fn publicar_parcial(ini: &Path, club: &str, track: &str, tera: &Tera) -> Result<usize, Box<dyn Error>> {
+    let mut data = prep::executar(ini, club, track).await?;
+    aliases::aplicar(&mut data, &aliases::carregar().await);
+    registry::anotar_categorias(&mut data);
+    entrants::anotar(&mut data);
+    overrides::aplicar(&mut data, ini)?;
+    let (mut ranking, best_lap_overall) = montar_ranking(&data);
+    aplicar_indice(&mut ranking, &Historico::carregar(), club, track, best_lap_overall);
+    privacy::anonimizar_prova(&mut data);
+    privacy::anonimizar_ranking(&mut ranking);
+
+    let ts = data["event"]["timestamp"].as_str().unwrap_or("000");
+    let lider = ranking.first().map(|p| p.nome.as_str());
+    let dono_melhor_volta = ranking.iter().find(|p| p.is_overall_best).map(|p| (best_lap_overall, p.nome.as_str()));
+    // O resultado final (gp_result_*) só aparece no .INI quando a prova termina
+    let encerrada = data["official_ranking"].as_array().is_some_and(|r| !r.is_empty());
+    let eventos = webhooks::detectar_eventos(ts, lider, dono_melhor_volta, encerrada);
+    webhooks::disparar(&eventos, club, data["event"]["title"].as_str().unwrap_or("Corrida")).await;
+
+    let atualizado_em = hora_utc();
+    let mut context = report::contexto_relatorio(&data, &ranking, best_lap_overall, club, track);
+    context.insert("ao_vivo", &true);
+    context.insert("atualizado_em", &atualizado_em);
+    let html = tera.render("report", &context)?;
+
+    let mut api = public_api::gerar(&data, &ranking, best_lap_overall);
+    api["status"] = json!("provisional");
+    api["updated_at"] = json!(atualizado_em);
+
+    let local_dir = crate::pasta_saida().join("live");
+    fs::create_dir_all(&local_dir)?;
+    let local_json = local_dir.join("live.json");
+    let local_html = local_dir.join("live.html");
+    fs::write(&local_json, serde_json::to_vec(&api)?)?;
+    fs::write(&local_html, html)?;
+    storage::upload_to_r2(&local_json, &chave_live(club, track, "json")).await?;
+    storage::upload_to_r2(&local_html, &chave_live(club, track, "html")).await?;
+    Ok(ranking.len())
+}
+
+/// Processamento completo (reports/, histórico, pós-publicação) e live/ apontando para o resultado final.
+async fn finalizar(ini: &Path, club: &str, track: &str) -> Result<(), Box<dyn Error>> {
+    saida!("\n🏁 Encerrando a prova: publicação final de {}", ini.display());
+    pipeline::processar(ini, club, track, &Extras::default()).await?;
+
+    let saida_dir = crate::pasta_saida();
+    let data: Value = serde_json::from_str(&fs::read_to_string(saida_dir.join("last_upload.json"))?)?;
+    let local_json = saida_dir.join("live").join("live.json");
+    fs::write(&local_json, serde_json::to_vec(&json!({
+        "status": "final",
+        "updated_at": hora_utc(),
+        "event": data["event"],
+        "report_link": data["report_link"],
+    }))?)?;
+    storage::upload_to_r2(&local_json, &chave_live(club, track, "json")).await?;
+    // O HTML ao vivo vira o relatório final (sem aviso e sem recarregar)
+    storage::upload_to_r2(&saida_dir.join("last_upload.html"), &chave_live(club, track, "html")).await?;
+    Ok(())
+}
+
+pub async fn executar(ini: &Path, club: &str, track: &str) -> Result<(), Box<dyn Error>> {
+    let espera = Duration::from_secs(env::var("WATCH_SETTLE_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(ESPERA_PADRAO_SECS));
+    let ocioso = Duration::from_secs(60 * env::var("LIVE_IDLE_MIN").ok().and_then(|v| v.parse().ok()).unwrap_or(OCIOSO_PADRAO_MIN));
+    let pasta = ini.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let nome = ini.file_name().ok_or("❌ Informe o arquivo .INI da prova")?.to_os_string();
+
+    // Vigiamos a pasta: o cronometrador pode apagar e recriar o arquivo a cada bateria
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = fs_notify::recommended_watcher(move |evento: fs_notify::Result<fs_notify::Event>| {
+        let _ = tx.send(evento);
+    })?;
+    watcher.watch(pasta, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("❌ Não foi possível vigiar {}: {}", pasta.display(), e))?;
+
+    assets::publicar().await?;
+    let mut tera = Tera::default();
+    assets::registrar(&mut tera);
+    crate::templates::carregar(&mut tera, "report.html", "report")?;
+
+    saida!("👀 Ao vivo: {} -> {}. Ctrl+C encerra a prova.", ini.display(), chave_live(club, track, "html"));
+    // Se o arquivo já existe, publica o estado atual logo de cara
+    let mut pendente: Option<Instant> = ini.exists().then(|| Instant::now().checked_sub(espera).unwrap_or_else(Instant::now));
+    let mut ultima_mudanca = Instant::now();
+    let mut publicacoes = 0;
+    let mut tique = tokio::time::interval(Duration::from_secs(1));
+
+    loop {
+        tokio::select! {
+            evento = rx.recv() => {
+                match evento {
+                    Some(Ok(evento)) if matches!(evento.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                        if evento.paths.iter().any(|p| p.file_name() == Some(nome.as_os_str())) {
+                            pendente = Some(Instant::now());
+                            ultima_mudanca = Instant::now();
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => erro!("⚠️ Erro do vigia: {}", e),
+                    None => break,
+                }
+            }
+            _ = tique.tick() => {
+                if pendente.is_some_and(|visto| visto.elapsed() >= espera) {
+                    pendente = None;
+                    // Arquivo no meio da gravação ou ainda sem a primeira bateria: tenta de novo na próxima mudança
+                    match publicar_parcial(ini, club, track, &tera).await {
+                        Ok(pilotos) => { publicacoes += 1; saida!("📊 Parcial #{} publicada ({} piloto(s), {})", publicacoes, pilotos, hora_utc()); }
+                        Err(e) => erro!("⚠️ Parcial ignorada: {}", e),
+                    }
+                }
+                if !ocioso.is_zero() && publicacoes > 0 && pendente.is_none() && ultima_mudanca.elapsed() >= ocioso {
+                    saida!("⏱️ {} min sem atualização do arquivo", ocioso.as_secs() / 60);
+                    break;
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    if publicacoes == 0 {
+        return Err("⚠️ Nenhuma parcial publicada; nada a finalizar".into());
+    }
+    finalizar(ini, club, track).await
+}
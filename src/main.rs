@@ -1,297 +1,510 @@
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::collections::HashMap;
+use clap::{Args, Parser, Subcommand};
 use std::env;
 use std::error::Error;
-use std::fs;
-use std::process::{exit, Command};
-use tera::{Context, Tera};
-use aws_sdk_s3::{Client, primitives::ByteStream};
-use std::path::Path;
-
-#[derive(Serialize, Deserialize)]
-struct PilotoDisplay {
-    nome: String,
-    total_laps: i64,
-    penalties: i64,
-    zona: String,
-    gap: String,
-    sessions: i64,
-    best_time: String,
-    average_time: String,
-    is_overall_best: bool,
-    best_slot_name: String, 
-    laps_per_slot: HashMap<String, String>,
-    times_per_slot: HashMap<String, String>,
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::sync::OnceLock;
+
+use raceday::{erro, saida};
+use raceday::error::{self, RacedayError};
+use raceday::{aliases, batch, challenge, compare, config, console, digest, duplicates, export, federation, fees, history, live, migrate, pipeline, portal, privacy, rebuild, refresh, replication, season, serve, storage, templates, watch};
+
+// --- LINHA DE COMANDO ---
+// As opções globais têm o mesmo efeito das variáveis de ambiente equivalentes
+// (CLUB, TRACK, RACEDAY_OUT_DIR, R2_ENDPOINT, R2_BUCKET, ENTRANTS_FILE) e prevalecem sobre elas,
+// que por sua vez prevalecem sobre o raceday.toml.
+// `raceday corrida.ini` continua funcionando como atalho de `raceday process corrida.ini`.
+
+#[derive(Parser)]
+#[command(name = "raceday", version, about = "Relatórios de slot car: .INI -> JSON/HTML -> Cloudflare R2")]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(after_help = "Códigos de saída:
+  0  sucesso
+  1  outra falha
+  2  entrada inválida: opção, INI, overrides, JSON fora do formato
+  3  envio ao bucket falhou; os arquivos gerados ficam em --out-dir (pode repetir)
+  4  o preparador externo falhou ou estourou o tempo (PREP_TIMEOUT_SECS)
+  5  erro de template
+  6  notificação ou pós-publicação falhou; a publicação foi feita (pode repetir)")]
+struct Cli {
+    #[command(subcommand)]
+    comando: Option<Comando>,
+
+    /// Arquivo .INI (atalho para `process`)
+    ini: Option<PathBuf>,
+
+    #[command(flatten)]
+    midias: Midias,
+
+    #[command(flatten)]
+    globais: Globais,
 }
 
-// --- INFRAESTRUTURA DE NUVEM (R2) ---
-
-async fn upload_to_r2(file_path: &str, target_key: &str) -> Result<(), Box<dyn Error>> {
-    let endpoint = env::var("R2_ENDPOINT").expect("❌ R2_ENDPOINT não definida");
-    let bucket = env::var("R2_BUCKET").unwrap_or_else(|_| "raceday-data".to_string());
-
-    let config = aws_config::from_env()
-        .endpoint_url(endpoint)
-        .region(aws_config::Region::new("auto"))
-        .load().await;
-
-    let client = Client::new(&config);
-    let body = ByteStream::from_path(Path::new(file_path)).await?;
-    
-    let content_type = if file_path.ends_with(".html") { "text/html" } else { "application/json" };
+#[derive(Args)]
+struct Globais {
+    /// Nome do clube (CLUB)
+    #[arg(long, global = true)]
+    club: Option<String>,
+    /// Nome da pista (TRACK)
+    #[arg(long, global = true)]
+    track: Option<String>,
+    /// Pasta dos arquivos gerados (RACEDAY_OUT_DIR, padrão temp_out)
+    #[arg(long, global = true)]
+    out_dir: Option<PathBuf>,
+    /// Endpoint S3 do R2 (R2_ENDPOINT)
+    #[arg(long, global = true)]
+    endpoint: Option<String>,
+    /// Bucket de destino (R2_BUCKET)
+    #[arg(long, global = true)]
+    bucket: Option<String>,
+    /// Inscritos da prova, com a classe de cada piloto (ENTRANTS_FILE)
+    #[arg(long, global = true)]
+    entrants: Option<PathBuf>,
+    /// Critério da classificação: laps, best_lap ou points (CLASSIFICATION)
+    #[arg(long, global = true, value_parser = ["laps", "best_lap", "points"])]
+    sort_by: Option<String>,
+    /// Arquivo de configuração (RACEDAY_CONFIG, padrão raceday.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Interrompe se o JSON do preparador estiver fora do formato (RACEDAY_STRICT)
+    #[arg(long, global = true)]
+    strict: bool,
+    /// Envia e notifica mesmo que o conteúdo publicado seja igual (RACEDAY_FORCE)
+    #[arg(long, global = true)]
+    force: bool,
+    /// Saída só em ASCII, sem emojis (RACEDAY_PLAIN)
+    #[arg(long, global = true)]
+    plain: bool,
+    /// Mostra detalhes, horário e o tempo de cada etapa
+    #[arg(short, long, global = true, conflicts_with = "quiet")]
+    verbose: bool,
+    /// Mostra só avisos e erros
+    #[arg(short, long, global = true)]
+    quiet: bool,
+    /// Formato do log: text ou json, um evento por linha na saída de erro (RACEDAY_LOG_FORMAT)
+    #[arg(long, global = true, value_parser = ["text", "json"])]
+    log_format: Option<String>,
+    /// Grava o resultado em JSON (URL do relatório, chaves, vencedor) neste arquivo em vez da saída padrão (RACEDAY_RESULT_FILE)
+    #[arg(long, global = true)]
+    result_file: Option<PathBuf>,
+    /// Gera tudo em <out-dir>/site sem tocar no R2 nem no Render (RACEDAY_OFFLINE)
+    #[arg(long, global = true, visible_alias = "local-only")]
+    offline: bool,
+}
 
-    client.put_object()
-        .bucket(bucket)
-        .key(target_key)
-        .body(body)
-        .content_type(content_type)
-        .send().await?;
+#[derive(Args)]
+struct Midias {
+    /// Pasta com as fotos do evento
+    #[arg(long)]
+    photos: Option<PathBuf>,
+    /// Vídeo do evento ou da bateria: <url>, "2=<url>" ou "Final=<url>" (repetível)
+    #[arg(long = "video")]
+    videos: Vec<String>,
+    /// Também publica o relatório em PDF (PDF_EXPORT)
+    #[arg(long)]
+    pdf: bool,
+}
 
-    println!("✅ Sincronizado no R2: {}", target_key);
-    Ok(())
+#[derive(Subcommand)]
+enum Comando {
+    /// Processa um .INI (ou uma pasta com --dir) e publica relatório, JSON, histórico e páginas de piloto
+    Process {
+        #[arg(required_unless_present = "dir")]
+        ini: Option<PathBuf>,
+        /// Processa todos os .INI da pasta, com resumo no final
+        #[arg(long, conflicts_with_all = ["ini", "photos", "videos"])]
+        dir: Option<PathBuf>,
+        #[command(flatten)]
+        midias: Midias,
+    },
+    /// Publica a classificação parcial em live/ a cada regravação do .INI e finaliza em reports/
+    Live {
+        ini: PathBuf,
+    },
+    /// Vigia a pasta do cronometrador e processa cada .INI novo
+    Watch {
+        pasta: PathBuf,
+    },
+    /// Envia um arquivo já gerado para o bucket
+    Upload {
+        arquivo: PathBuf,
+        /// Chave no bucket (padrão: nome do arquivo)
+        #[arg(long)]
+        key: Option<String>,
+    },
+    /// Lista as chaves publicadas no bucket
+    List {
+        #[arg(long, default_value = "races/")]
+        prefix: String,
+    },
+    /// Classificação da temporada a partir dos JSONs do bucket, publicada em reports/seasons/
+    Season {
+        /// Ano da temporada (padrão: o atual)
+        #[arg(long, visible_alias = "season")]
+        year: Option<String>,
+    },
+    /// Nomes das provas publicadas sem correspondência no mapa de apelidos ou no cadastro
+    Aliases {
+        /// Só as provas deste ano
+        #[arg(long, visible_alias = "season")]
+        year: Option<String>,
+    },
+    /// Compara dois pilotos fenda a fenda, em uma prova ou na temporada
+    Compare {
+        /// Os dois pilotos, separados por vírgula: "Ana,Bruno"
+        #[arg(long)]
+        pilots: String,
+        /// Timestamp da prova (usa CLUB e TRACK)
+        #[arg(long, conflicts_with = "season")]
+        race: Option<String>,
+        /// Ano: todas as provas do clube na temporada
+        #[arg(long)]
+        season: Option<String>,
+    },
+    /// Resumo da temporada por e-mail
+    Digest {
+        /// Período do resumo
+        #[arg(long, default_value = "month", value_parser = ["month", "season"])]
+        period: String,
+        /// Grava o resumo em <out-dir>/digest.html em vez de enviar
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Reescreve os JSONs de races/ numa versão mais nova do formato
+    Migrate {
+        /// Versão atual dos arquivos (ex.: v1)
+        #[arg(long)]
+        from: String,
+        /// Versão de destino (ex.: v2)
+        #[arg(long)]
+        to: String,
+        /// Só conta o que seria migrado
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Regenera relatórios e páginas a partir dos JSONs publicados
+    Rebuild {
+        /// Só estes artefatos, separados por vírgula (padrão: todos)
+        #[arg(long, value_delimiter = ',', value_parser = rebuild::ARTEFATOS)]
+        only: Vec<String>,
+    },
+    /// Atualiza índice, feed e sitemap
+    RefreshIndex,
+    /// Publica o portal do clube
+    Portal,
+    /// Servidor HTTP local com API
+    Serve {
+        #[arg(long, default_value_t = serve::PORTA_PADRAO)]
+        port: u16,
+        /// Endereço de escuta; fora do localhost exige SERVE_USERS ou SERVE_TOKENS
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+    },
+    /// Exporta (ou remove) os dados de um piloto
+    ExportPilot {
+        /// Nome ou slug do piloto
+        #[arg(long)]
+        id: String,
+        /// Remove o piloto do histórico local depois de exportar
+        #[arg(long)]
+        purge: bool,
+    },
+    /// Taxas de inscrição e pagamentos
+    Fees {
+        #[command(subcommand)]
+        operacao: OperacaoFees,
+    },
+    /// Auditoria dos dados publicados
+    Audit {
+        #[command(subcommand)]
+        alvo: Auditoria,
+    },
+    /// Classificação entre clubes
+    Federation {
+        /// Ano (padrão: o atual)
+        #[arg(long)]
+        season: Option<String>,
+    },
+    /// Desafio entre dois clubes
+    Challenge {
+        /// Clube da casa: "Clube=https://url-publica"
+        #[arg(long)]
+        home: String,
+        /// Provas da casa (timestamps separados por vírgula)
+        #[arg(long)]
+        home_race: String,
+        /// Clube visitante: "Clube=https://url-publica"
+        #[arg(long)]
+        away: String,
+        /// Provas do visitante (timestamps separados por vírgula)
+        #[arg(long)]
+        away_race: String,
+        /// Título do desafio (padrão: "Casa x Visitante")
+        #[arg(long)]
+        title: Option<String>,
+        /// Destino do relatório no bucket do visitante (CHALLENGE_AWAY_TARGET)
+        #[arg(long)]
+        away_target: Option<String>,
+    },
+    /// Grava os templates embutidos numa pasta, para personalizar
+    Templates {
+        /// Pasta de destino (padrão: templates)
+        #[arg(default_value = "templates")]
+        dir: PathBuf,
+    },
 }
 
-async fn trigger_render_sync() {
-    if let Ok(url) = env::var("RENDER_SYNC_URL") {
-        let client = reqwest::Client::new();
-        // O Render pode demorar para acordar, definimos timeout de 60s
-        let _ = client.post(url)
-            .timeout(std::time::Duration::from_secs(60))
-            .send().await;
-        println!("🔔 Notificação de rebuild enviada ao Render.com");
-    }
+#[derive(Subcommand)]
+enum OperacaoFees {
+    /// Define a taxa de inscrição da prova
+    SetFee {
+        /// Timestamp da prova
+        #[arg(long)]
+        event: String,
+        /// Valor, ex.: 30,00
+        #[arg(long)]
+        amount: String,
+    },
+    /// Registra o pagamento de um piloto
+    Pay {
+        #[arg(long)]
+        event: String,
+        #[arg(long)]
+        pilot: String,
+        #[arg(long, default_value = "dinheiro")]
+        method: String,
+        /// Valor pago (padrão: a taxa da prova)
+        #[arg(long)]
+        amount: Option<String>,
+    },
+    /// Conciliação de uma prova: quem correu e não pagou
+    Report {
+        #[arg(long)]
+        event: String,
+    },
+    /// Conciliação de todas as provas do ano
+    Season {
+        /// Ano (padrão: o atual)
+        #[arg(long)]
+        season: Option<String>,
+    },
 }
 
-// --- LÓGICA DE NEGÓCIO E RELATÓRIO ---
+#[derive(Subcommand)]
+enum Auditoria {
+    /// Nomes parecidos que provavelmente são o mesmo piloto
+    Pilots {
+        /// Só as provas deste ano
+        #[arg(long)]
+        year: Option<String>,
+        /// Semelhança mínima, de 0 a 1
+        #[arg(long, default_value_t = duplicates::LIMIAR_PADRAO)]
+        threshold: f64,
+        /// Grava as sugestões no mapa de apelidos local (PILOT_ALIASES)
+        #[arg(long)]
+        write: bool,
+    },
+}
 
-fn gerar_json_grafico(ranking: &Vec<PilotoDisplay>, slots_count: i64) -> String {
-    let mut datasets = Vec::new();
-    let cores_grafico = vec![
-        "#FF6384", "#36A2EB", "#FFCE56", "#4BC0C0", "#9966FF", "#FF9F40", "#8BC34A", "#000000",
-        "#E91E63", "#9C27B0", "#00BCD4", "#009688", "#CDDC39", "#FFEB3B", "#795548", "#607D8B"
+fn aplicar_globais(g: &Globais) {
+    let pares: [(&str, Option<&OsStr>); 9] = [
+        ("CLUB", g.club.as_deref().map(OsStr::new)),
+        ("TRACK", g.track.as_deref().map(OsStr::new)),
+        ("RACEDAY_OUT_DIR", g.out_dir.as_deref().map(Path::as_os_str)),
+        ("R2_ENDPOINT", g.endpoint.as_deref().map(OsStr::new)),
+        ("R2_BUCKET", g.bucket.as_deref().map(OsStr::new)),
+        ("RACEDAY_CONFIG", g.config.as_deref().map(Path::as_os_str)),
+        ("ENTRANTS_FILE", g.entrants.as_deref().map(Path::as_os_str)),
+        ("CLASSIFICATION", g.sort_by.as_deref().map(OsStr::new)),
+        ("RACEDAY_RESULT_FILE", g.result_file.as_deref().map(Path::as_os_str)),
     ];
-
-    let fenda_nomes_eixo = vec!["Vermelha", "Branca", "Verde", "Laranja", "Azul", "Amarela", "Roxa", "Preta"];
-
-    for (idx, piloto) in ranking.iter().enumerate() {
-        let mut data_pontos = Vec::new();
-        for s in 1..=slots_count {
-            let voltas = piloto.laps_per_slot.get(&s.to_string())
-                .and_then(|v| v.parse::<i64>().ok())
-                .unwrap_or(0);
-            data_pontos.push(voltas);
+    for (nome, valor) in pares {
+        if let Some(valor) = valor {
+            // SAFETY: chamado em main() antes de o runtime do tokio criar outras threads
+            unsafe { env::set_var(nome, valor) };
         }
-
-        datasets.push(serde_json::json!({
-            "label": piloto.nome,
-            "data": data_pontos,
-            "borderColor": cores_grafico.get(idx).unwrap_or(&"#CCCCCC"),
-            "backgroundColor": cores_grafico.get(idx).unwrap_or(&"#CCCCCC"),
-            "fill": false,
-            "tension": 0.1
-        }));
     }
-
-    let labels_clube: Vec<String> = fenda_nomes_eixo.iter()
-        .take(slots_count as usize)
-        .map(|s| s.to_string())
-        .collect();
-
-    serde_json::json!({
-        "labels": labels_clube,
-        "datasets": datasets
-    }).to_string()
+    if g.offline {
+        // SAFETY: idem
+        unsafe { env::set_var("RACEDAY_OFFLINE", "1") };
+    }
+    if g.strict {
+        // SAFETY: idem
+        unsafe { env::set_var("RACEDAY_STRICT", "1") };
+    }
+    if g.force {
+        // SAFETY: idem
+        unsafe { env::set_var("RACEDAY_FORCE", "1") };
+    }
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 { eprintln!("❌ Informe o arquivo .INI"); exit(1); }
-    let ini_path = &args[1];
-
-    let club = env::var("CLUB").expect("❌ CLUB não definida");
-    let track = env::var("TRACK").expect("❌ TRACK não definida");
+fn obrigatoria(nome: &str, flag: &str) -> String {
+    env::var(nome).unwrap_or_else(|_| {
+        erro!("❌ Informe {}, defina {} ou preencha o raceday.toml", flag, nome);
+        exit(error::SAIDA_ENTRADA);
+    })
+}
 
-    // "CUTUCAR" O RENDER NO INÍCIO (WARM-UP) ---
-    // Iniciamos o trigger sem esperar o resultado (em background) para ganhar tempo
-    let _ = tokio::spawn(async {
-        trigger_render_sync().await;
+fn main() {
+    let cli = Cli::parse();
+    let formato = console::Formato::de(cli.globais.log_format.as_deref()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(error::SAIDA_ENTRADA);
     });
-
-    // --- 2. DEFINIR EXECUTÁVEL PYTHON POR S.O. ---
-    #[cfg(target_os = "windows")]
-    let python_exe = "bin/raceday-prep.exe";
-    
-    #[cfg(not(target_os = "windows"))]
-    let python_exe = "bin/raceday-prep";
-
-    // Validação de existência do binário
-    if !Path::new(python_exe).exists() {
-        eprintln!("❌ ERRO FATAL: O executável Python não foi encontrado em: {}", python_exe);
-        eprintln!("Certifique-se de que o arquivo existe na pasta 'bin/' antes de continuar.");
-        exit(1);
+    // No JSON as mensagens vão sem emojis, para os scripts compararem texto
+    let plain = cli.globais.plain || formato == console::Formato::Json;
+    let args: Vec<String> = if plain { vec!["--plain".into()] } else { vec![] };
+    console::configurar(&args);
+    console::iniciar_log(match (cli.globais.verbose, cli.globais.quiet) {
+        (true, _) => console::Nivel::Detalhado,
+        (_, true) => console::Nivel::Quieto,
+        _ => console::Nivel::Normal,
+    }, formato);
+    aplicar_globais(&cli.globais);
+    // SAFETY: o runtime do tokio (e qualquer outra thread) só é criado abaixo
+    if let Err(e) = unsafe { config::aplicar_ambiente() } {
+        erro!("{}", e);
+        exit(error::SAIDA_ENTRADA);
     }
-
-    println!("🚀 [1/5] Iniciando processamento Python ({})", python_exe);
-    let output = Command::new(python_exe)
-        .arg("--input").arg(ini_path)
-        .arg("--club").arg(&club)
-        .arg("--track").arg(&track)
-        .output()?;
-
-    if !output.status.success() {
-        eprintln!("⚠️ Erro no preparador: {}", String::from_utf8_lossy(&output.stderr));
-        exit(1);
+    if let Err(e) = privacy::carregar() {
+        erro!("{}", e);
+        exit(e.codigo_saida());
     }
 
-    let data: Value = serde_json::from_slice(&output.stdout)?;
-    let ts = data["event"]["timestamp"].as_str().unwrap_or("000");
-
-    // --- PROCESSAMENTO DO RANKING ---
-    let mut ranking: Vec<PilotoDisplay> = Vec::new();
-    let mut best_lap_overall = 999.999;
-    let fenda_nomes = vec!["", "Vermelha", "Branca", "Verde", "Laranja", "Azul", "Amarela", "Roxa", "Preta"];
-
-    if let Some(pilots_map) = data["pilots"].as_object() {
-        for (id, p_info) in pilots_map {
-            let mut laps_map = HashMap::new();
-            let mut times_map = HashMap::new();
-            let mut total_voltas = 0;
-            let mut melhor_tempo_piloto = 999.999;
-            let mut best_slot_idx = 1;
-            let mut sessions_count = 0;
-
-            if let Some(races) = data["races"].as_array() {
-                for race in races {
-                    if let Some(sessions) = race["sessions"].as_array() {
-                        for session in sessions {
-                            if let Some(slots) = session["slots"].as_object() {
-                                for (slot_idx, s_data) in slots {
-                                    if s_data["p_id"].to_string() == format!("\"{}\"", id) || s_data["p_id"].to_string() == *id {
-                                        let l = s_data["laps"].as_i64().unwrap_or(0);
-                                        let t = s_data["best"].as_f64().unwrap_or(0.0);
-                                        if l > 0 { sessions_count += 1; }
-                                        total_voltas += l;
-                                        laps_map.insert(slot_idx.clone(), l.to_string());
-                                        times_map.insert(slot_idx.clone(), if t > 0.0 { format!("{:.3}", t) } else { "---".into() });
-                                        if t > 0.0 && t < melhor_tempo_piloto {
-                                            melhor_tempo_piloto = t;
-                                            best_slot_idx = slot_idx.parse().unwrap_or(1);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            if melhor_tempo_piloto < best_lap_overall && melhor_tempo_piloto > 0.0 { best_lap_overall = melhor_tempo_piloto; }
-            let display_best = if melhor_tempo_piloto >= 900.0 { "0.000".into() } else { format!("{:.3}", melhor_tempo_piloto) };
-            
-            // Aqui corrigimos para usar o total_laps OFICIAL do ranking se disponível
-            let mut final_laps = total_voltas;
-            let mut final_gap = "0".to_string();
-            // let mut final_zona = "000".to_string();
-
-            if let Some(off_rank) = data["official_ranking"].as_array() {
-                if let Some(p_off) = off_rank.iter().find(|x| x["p_id"].as_str().unwrap_or("") == id) {
-                    final_laps = p_off["laps"].as_i64().unwrap_or(total_voltas);
-                    final_gap = p_off["gap"].as_str().unwrap_or("0").to_string();
-                }
-            }
-
-            let media = if sessions_count > 0 { final_laps as f64 / sessions_count as f64 } else { 0.0 };
-
-            ranking.push(PilotoDisplay {
-                nome: p_info["name"].as_str().unwrap_or("---").to_string(),
-                total_laps: final_laps,
-                penalties: data["raw_results"]["penaltys"][id].as_i64().unwrap_or(0),
-                zona: data["raw_results"]["zones"][id].as_str().unwrap_or("000").to_string(),
-                gap: final_gap,
-                sessions: sessions_count,
-                best_time: display_best,
-                average_time: format!("{:.1}", media).replace(".", ","),
-                is_overall_best: false,
-                best_slot_name: fenda_nomes.get(best_slot_idx as usize).unwrap_or(&"---").to_string(),
-                laps_per_slot: laps_map,
-                times_per_slot: times_map,
-            });
+    let comando = match (cli.comando, cli.ini) {
+        (Some(c), _) => c,
+        (None, Some(ini)) => Comando::Process { ini: Some(ini), dir: None, midias: cli.midias },
+        (None, None) => {
+            erro!("❌ Informe o arquivo .INI ou um subcomando (raceday --help)");
+            exit(error::SAIDA_ENTRADA);
         }
+    };
+
+    let runtime = tokio::runtime::Runtime::new().expect("❌ Falha ao iniciar o runtime");
+    let mut resultado = runtime.block_on(executar(comando));
+    replication::imprimir_resumo();
+    // Depois do resumo dos destinos, para a linha JSON ser a última da saída padrão
+    if let (Ok(()), Some(publicado)) = (&resultado, RESULTADO.get()) {
+        resultado = entregar_resultado(publicado);
     }
-
-    ranking.sort_by(|a, b| b.total_laps.cmp(&a.total_laps));
-    let best_lap_str = format!("{:.3}", best_lap_overall);
-    for p in &mut ranking { if p.best_time == best_lap_str && best_lap_overall < 900.0 { p.is_overall_best = true; } }
-
-    // --- CÁLCULO MELHORES TEMPOS POR SLOT ---
-    let mut best_times_per_slot: HashMap<String, String> = HashMap::new();
-    for p in &ranking {
-        for (slot, time_str) in &p.times_per_slot {
-            if let Ok(t) = time_str.parse::<f64>() {
-                let current_best_str = best_times_per_slot.get(slot).cloned().unwrap_or("999.999".to_string());
-                let current_best = current_best_str.parse::<f64>().unwrap_or(999.999);
-                if t < current_best && t > 0.0 {
-                    best_times_per_slot.insert(slot.clone(), format!("{:.3}", t));
-                }
-            }
+    if let Err(e) = resultado {
+        // O código de saída vem da variante (ver error.rs e o --help)
+        let e = RacedayError::from(e);
+        tracing::error!(codigo = e.codigo_saida(), "{}", console::texto(&e.to_string()));
+        if let RacedayError::Armazenamento { key, .. } = &e {
+            erro!("⚠️ Os arquivos gerados continuam em {}; reenvie com `raceday upload` quando a rede voltar ({})",
+                raceday::pasta_saida().display(), key);
         }
+        exit(e.codigo_saida());
     }
+}
 
-    // --- TERA TEMPLATES ---
-    println!("📄 [2/5] Gerando relatório HTML...");
-    let mut tera = Tera::default();
-    tera.add_template_file("templates/report.html", Some("report"))?;
-    let mut context = Context::new();
+/// O que o `process` publicou, entregue por main() depois do resumo final.
+static RESULTADO: OnceLock<serde_json::Value> = OnceLock::new();
+
+/// Resultado da publicação para scripts: uma linha JSON no fim da saída padrão,
+/// ou o arquivo de --result-file (RACEDAY_RESULT_FILE).
+fn entregar_resultado(resultado: &serde_json::Value) -> Result<(), Box<dyn Error>> {
+    match env::var_os("RACEDAY_RESULT_FILE") {
+        Some(arquivo) => std::fs::write(&arquivo, serde_json::to_string_pretty(resultado)?)
+            .map_err(|e| format!("❌ Não foi possível gravar {}: {}", PathBuf::from(&arquivo).display(), e).into()),
+        None => {
+            println!("{}", serde_json::to_string(resultado)?);
+            Ok(())
+        }
+    }
+}
 
-    let mut insights = Vec::new();
-    if let Some(top) = ranking.iter().find(|p| p.is_overall_best) {
-        insights.push(format!("🔥 Melhor Volta Absoluta: Performance de elite por {} com o tempo de {}s na fenda {}.", top.nome, top.best_time, top.best_slot_name));
+async fn executar(comando: Comando) -> Result<(), Box<dyn Error>> {
+    if !matches!(comando, Comando::Upload { .. } | Comando::List { .. } | Comando::Templates { .. }) {
+        templates::sincronizar().await;
     }
-    if ranking.len() > 0 {
-        insights.push(format!("🏆 Domínio técnico: O vencedor {} demonstrou consistência extrema, completando {} voltas.", ranking[0].nome, ranking[0].total_laps));
+    match comando {
+        Comando::Process { ini, dir, midias } => {
+            let club = obrigatoria("CLUB", "--club");
+            let track = obrigatoria("TRACK", "--track");
+
+            let resultado = match (dir, ini) {
+                (Some(dir), _) => serde_json::to_value(batch::executar(&dir, &club, &track).await?)?,
+                (None, Some(ini)) => serde_json::to_value(pipeline::processar(&ini, &club, &track, &pipeline::Extras { fotos: midias.photos, videos: midias.videos, pdf: midias.pdf }).await?)?,
+                (None, None) => unreachable!("clap exige o .INI ou --dir"),
+            };
+            saida!("\n✨ Processo concluído com sucesso!");
+            let _ = RESULTADO.set(resultado);
+            Ok(())
+        }
+        Comando::Live { ini } => {
+            let club = obrigatoria("CLUB", "--club");
+            let track = obrigatoria("TRACK", "--track");
+            live::executar(&ini, &club, &track).await?;
+            saida!("\n✨ Prova encerrada e publicada!");
+            Ok(())
+        }
+        Comando::Watch { pasta } => {
+            let club = obrigatoria("CLUB", "--club");
+            let track = obrigatoria("TRACK", "--track");
+            watch::executar(&pasta, &club, &track).await
+        }
+        Comando::Upload { arquivo, key } => {
+            let key = match key {
+                Some(k) => k,
+                None => arquivo.file_name().ok_or("❌ Caminho de arquivo inválido")?.to_string_lossy().to_string(),
+            };
+            storage::upload_to_r2(&arquivo, &key).await?;
+            Ok(())
+        }
+        Comando::List { prefix } => {
+            let chaves = storage::listar_r2(&prefix).await?;
+            for chave in &chaves {
+                println!("{}", chave);
+            }
+            saida!("☁️ {} chave(s) em {}", chaves.len(), prefix);
+            Ok(())
+        }
+        Comando::Season { year } => {
+            let club = obrigatoria("CLUB", "--club");
+            let track = env::var("TRACK").ok();
+            let ano = year.unwrap_or_else(|| history::agora_timestamp()[0..4].to_string());
+            season::executar(&ano, &club, track.as_deref()).await
+        }
+        Comando::Aliases { year } => {
+            let club = obrigatoria("CLUB", "--club");
+            aliases::executar(year.as_deref(), &club).await
+        }
+        Comando::Compare { pilots, race, season } => {
+            let club = obrigatoria("CLUB", "--club");
+            let track = env::var("TRACK").ok();
+            compare::executar(&pilots, &club, track.as_deref(), race.as_deref(), season.as_deref()).await
+        }
+        Comando::Digest { period, dry_run } => digest::executar(&period, dry_run).await,
+        Comando::Migrate { from, to, dry_run } => migrate::executar(&from, &to, dry_run).await,
+        Comando::Rebuild { only } => rebuild::executar(&only).await,
+        Comando::RefreshIndex => refresh::executar().await,
+        Comando::Portal => portal::executar().await,
+        Comando::Serve { port, bind } => serve::executar(&bind, port).await,
+        Comando::ExportPilot { id, purge } => export::executar(&id, purge).await,
+        Comando::Fees { operacao } => {
+            match operacao {
+                OperacaoFees::SetFee { event, amount } => fees::definir_taxa(&event, &amount)?,
+                OperacaoFees::Pay { event, pilot, method, amount } => fees::pagar(&event, &pilot, &method, amount.as_deref())?,
+                OperacaoFees::Report { event } => fees::relatorio(&event),
+                OperacaoFees::Season { season } => fees::temporada(season.as_deref()),
+            }
+            Ok(())
+        }
+        Comando::Audit { alvo: Auditoria::Pilots { year, threshold, write } } => {
+            let club = obrigatoria("CLUB", "--club");
+            duplicates::executar(&club, year.as_deref(), threshold, write).await
+        }
+        Comando::Federation { season } => federation::executar(season.as_deref()).await,
+        Comando::Challenge { home, home_race, away, away_race, title, away_target } => {
+            challenge::executar(&home, &home_race, &away, &away_race, title.as_deref(), away_target.as_deref()).await
+        }
+        Comando::Templates { dir } => {
+            let gravados = templates::exportar(&dir)?;
+            saida!("📄 {} template(s) gravado(s) em {} (os existentes foram mantidos)", gravados, dir.display());
+            Ok(())
+        }
     }
-
-    context.insert("insights", &insights);
-    context.insert("best_times_per_slot", &best_times_per_slot);
-    context.insert("overall_best_time_formatted", &best_lap_str);
-    context.insert("club", &club); 
-    context.insert("track", &track);
-    context.insert("event", &data["event"]); 
-    context.insert("metadata", &data["metadata"]);
-    context.insert("ranking_display", &ranking); 
-    context.insert("dados_grafico", &gerar_json_grafico(&ranking, data["metadata"]["slots"].as_i64().unwrap_or(6)));
-
-    let html_output = tera.render("report", &context)?;
-    
-    // --- SALVAMENTO E UPLOAD ---
-    // Criamos identificadores limpos para os nomes dos arquivos
-    let club_slug = club.to_lowercase().replace(" ", "_");
-    let track_slug = track.to_lowercase().replace(" ", "_");
-    let race_slug = data["event"]["slug"].as_str().unwrap_or("race");
-
-    // O JSON agora é ÚNICO por clube e pista: races/clube_pista_timestamp.json
-    let r2_key_json = format!("races/{}_{}_{}.json", club_slug, track_slug, ts);
-    
-    // O HTML segue o padrão: reports/clube_pista_corrida_timestamp.html
-    let r2_key_html = format!("reports/{}_{}_{}_{}.html", club_slug, track_slug, race_slug, ts);
-    
-    fs::create_dir_all("temp_out")?;
-    let local_json_path = format!("temp_out/last_upload.json");
-    let local_html_path = format!("temp_out/last_upload.html");
-    
-    fs::write(&local_json_path, serde_json::to_string_pretty(&data)?)?;
-    fs::write(&local_html_path, &html_output)?;
-
-    println!("☁️ [3/5] Enviando JSON para o R2: {}", r2_key_json);
-    upload_to_r2(&local_json_path, &r2_key_json).await?;
-
-    println!("☁️ [4/5] Enviando HTML para o R2: {}", r2_key_html);
-    upload_to_r2(&local_html_path, &r2_key_html).await?;
-
-    println!("🔔 [5/5] Sincronizando com Render.com...");
-    trigger_render_sync().await;
-
-    println!("\n✨ Processo concluído com sucesso!");
-    Ok(())
-}
\ No newline at end of file
+}
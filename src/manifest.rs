@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+// --- MANIFESTO DAS PROVAS (races/index.json) ---
+// Lista legível por máquina de todas as provas publicadas, para os frontends.
+// Cada execução baixa o manifesto com o ETag, acrescenta (ou substitui) a prova
+// e regrava com If-Match: se outro PC publicou no meio do caminho, a escrita é
+// recusada e o ciclo recomeça a partir da versão nova, sem perder a entrada dele.
+
+pub const CHAVE: &str = "races/index.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EntradaManifesto {
+    pub club: String,
+    pub track: String,
+    pub slug: String,
+    pub timestamp: String,
+    pub title: String,
+    pub date: String,
+    pub json_key: String,
+    pub html_key: String,
+    pub winner: Option<String>,
+}
+
+/// Acrescenta as provas ao manifesto (a mesma json_key é substituída, para
+/// reprocessamentos não duplicarem a entrada). Mais recentes primeiro.
+fn mesclar(atual: Option<&[u8]>, entradas: &[EntradaManifesto]) -> Result<Vec<EntradaManifesto>, Box<dyn Error>> {
+    let mut lista: Vec<EntradaManifesto> = match atual {
+        Some(bytes) => serde_json::from_slice(bytes).map_err(|e| format!("❌ {} inválido: {}", CHAVE, e))?,
+        None => Vec::new(),
+    };
+    lista.retain(|e| entradas.iter().all(|n| n.json_key != e.json_key));
+    lista.extend(entradas.iter().cloned());
+    lista.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(lista)
+}
+
+pub async fn registrar(entrada: &EntradaManifesto) -> Result<usize, Box<dyn Error>> {
+    registrar_todas(std::slice::from_ref(entrada)).await
+}
+
+/// Várias provas numa escrita só (rebuild). Retorna o total de provas no manifesto.
+pub async fn registrar_todas(entradas: &[EntradaManifesto]) -> Result<usize, Box<dyn Error>> {
+    let local = crate::pasta_saida().join("races_index.json");
+    let provas = crate::storage::atualizar_condicional(CHAVE, &local, |atual| {
+        let lista = mesclar(atual, entradas)?;
+        Ok(Some((serde_json::to_string_pretty(&lista)?, lista.len())))
+    }).await?;
+    Ok(provas.unwrap_or_default())
+}
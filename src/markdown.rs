@@ -0,0 +1,52 @@
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+
+use crate::i18n::{t, tf};
+use crate::ranking::PilotoDisplay;
+
+// --- RESUMO EM MARKDOWN ---
+// Versão compacta do resultado (pódio, tabela de voltas e gaps, melhor volta e
+// link do relatório) para colar direto no fórum ou no grupo do Facebook.
+// Gravada em <pasta de saída>/last_upload.md e publicada em <base>.md.
+
+const MEDALHAS: [&str; 3] = ["🥇", "🥈", "🥉"];
+
+/// Escapa o que quebraria a tabela ou viraria formatação.
+fn escapar(texto: &str) -> String {
+    texto.replace('|', "\\|").replace('*', "\\*").replace('_', "\\_")
+}
+
+pub fn gerar(data: &Value, ranking: &[PilotoDisplay], club: &str, track: &str, report_url: &str) -> String {
+    let titulo = data["event"]["title"].as_str().map(|s| s.to_string()).unwrap_or_else(|| t("texts.race"));
+    let mut md = format!("## 🏁 {} — {} / {}\n", escapar(&titulo), escapar(club), escapar(track));
+    if let Some(date) = data["event"]["date"].as_str().filter(|d| !d.is_empty()) {
+        md.push_str(&format!("*{}*\n", date));
+    }
+
+    md.push('\n');
+    for (medalha, p) in MEDALHAS.iter().zip(ranking) {
+        md.push_str(&format!("{} **{}** — {}\n", medalha, escapar(&p.nome), tf("texts.n_laps", &[("voltas", p.total_laps.to_string())])));
+    }
+
+    md.push_str(&format!("\n| {} | {} | {} | {} | {} |\n|---:|---|---:|---:|---:|\n",
+        t("report.pos"), t("report.pilot"), t("report.laps"), t("report.gap"), t("report.best_lap")));
+    for (i, p) in ranking.iter().enumerate() {
+        md.push_str(&format!("| {} | {} | {} | {} | {} |\n", p.posicao_exibida(i), escapar(&p.nome), p.total_laps, p.gap, p.best_time));
+    }
+
+    if let Some(top) = ranking.iter().find(|p| p.is_overall_best) {
+        md.push_str(&format!("\n⏱️ {}: **{}** — {}s ({})\n", t("texts.best_lap"), escapar(&top.nome), top.best_time, top.best_slot_name));
+    }
+    md.push_str(&format!("\n[{}]({})\n", t("texts.full_report"), report_url));
+    md
+}
+
+/// Grava e publica <base>.md. Retorna a chave.
+pub async fn publicar(data: &Value, ranking: &[PilotoDisplay], club: &str, track: &str, report_url: &str, r2_key_base: &str) -> Result<String, Box<dyn Error>> {
+    let local = crate::pasta_saida().join("last_upload.md");
+    fs::write(&local, gerar(data, ranking, club, track, report_url))?;
+    let key = format!("{}.md", r2_key_base);
+    crate::storage::upload_to_r2(&local, &key).await?;
+    Ok(key)
+}
@@ -0,0 +1,108 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use tera::{Context, Tera};
+
+// --- ESCALA DE FISCAIS (MARSHALS) ---
+// Quem não está pilotando numa bateria fiscaliza uma das curvas configuradas.
+// A escolha prioriza quem fiscalizou menos vezes, para dividir o trabalho igualmente.
+
+#[derive(Serialize)]
+pub struct PostoFiscal {
+    pub curva: String,
+    pub fiscal: String,
+}
+
+#[derive(Serialize)]
+pub struct EscalaBateria {
+    pub bateria: String,
+    pub pilotando: Vec<String>,
+    pub postos: Vec<PostoFiscal>,
+}
+
+/// Curvas que precisam de fiscal, lidas de MARSHAL_CORNERS ("Curva 1,Curva 3,Reta").
+/// Retorna `None` quando a variável não está definida (escala desligada).
+pub fn curvas_configuradas() -> Option<Vec<String>> {
+    let raw = env::var("MARSHAL_CORNERS").ok()?;
+    let curvas: Vec<String> = raw.split(',')
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if curvas.is_empty() { None } else { Some(curvas) }
+}
+
+pub fn gerar_escala(data: &Value, curvas: &[String]) -> Vec<EscalaBateria> {
+    // Pilotos inscritos, em ordem de número para a escala ser reprodutível
+    let mut inscritos: Vec<(String, String)> = data["pilots"].as_object()
+        .map(|m| m.iter()
+            .map(|(id, p)| (id.clone(), p["name"].as_str().unwrap_or("---").to_string()))
+            .collect())
+        .unwrap_or_default();
+    inscritos.sort_by_key(|(id, _)| id.parse::<i64>().unwrap_or(i64::MAX));
+
+    let mut vezes_fiscal: HashMap<String, usize> = HashMap::new();
+    let mut escala = Vec::new();
+
+    for race in data["races"].as_array().into_iter().flatten() {
+        for session in race["sessions"].as_array().into_iter().flatten() {
+            let mut ids_pilotando = Vec::new();
+            let mut pilotando = Vec::new();
+            if let Some(slots) = session["slots"].as_object() {
+                for s_data in slots.values() {
+                    ids_pilotando.push(s_data["p_id"].as_str().unwrap_or("").to_string());
+                    pilotando.push(s_data["name"].as_str().unwrap_or("---").to_string());
+                }
+            }
+
+            let mut livres: Vec<&(String, String)> = inscritos.iter()
+                .filter(|(id, _)| !ids_pilotando.contains(id))
+                .collect();
+            livres.sort_by_key(|(id, _)| vezes_fiscal.get(id).copied().unwrap_or(0));
+
+            let postos = curvas.iter().enumerate().map(|(i, curva)| {
+                let fiscal = match livres.get(i) {
+                    Some((id, nome)) => {
+                        *vezes_fiscal.entry(id.clone()).or_insert(0) += 1;
+                        nome.clone()
+                    }
+                    None => "— sem fiscal —".to_string(),
+                };
+                PostoFiscal { curva: curva.clone(), fiscal }
+            }).collect();
+
+            escala.push(EscalaBateria {
+                bateria: format!("{} - Sessão {}", race["name"].as_str().unwrap_or("Bateria"), session["session"]),
+                pilotando,
+                postos,
+            });
+        }
+    }
+
+    escala
+}
+
+pub fn imprimir_escala(escala: &[EscalaBateria]) {
+    saida!("👮 Escala de fiscais:");
+    for bateria in escala {
+        let postos: Vec<String> = bateria.postos.iter()
+            .map(|p| format!("{}: {}", p.curva, p.fiscal))
+            .collect();
+        saida!("   {} | {}", bateria.bateria, postos.join(" | "));
+    }
+}
+
+pub fn carregar_template(tera: &mut Tera) -> tera::Result<()> {
+    crate::templates::carregar(tera, "marshals.html", "marshals")
+}
+
+/// Requer `carregar_template`; recebe `&Tera` para poder rodar em paralelo no rebuild.
+pub fn renderizar(tera: &Tera, data: &Value, club: &str, track: &str, curvas: &[String], escala: &[EscalaBateria]) -> tera::Result<String> {
+    let mut context = Context::new();
+    context.insert("club", club);
+    context.insert("track", track);
+    context.insert("event", &data["event"]);
+    context.insert("curvas", curvas);
+    context.insert("escala", escala);
+    tera.render("marshals", &context)
+}
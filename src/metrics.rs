@@ -0,0 +1,93 @@
+use rhai::{Dynamic, Engine, Scope, AST};
+use serde_json::{Map, Value};
+use std::env;
+use std::fs;
+use std::sync::OnceLock;
+
+use crate::ranking::PilotoDisplay;
+
+// --- MÉTRICAS PRÓPRIAS DO CLUBE (SCRIPT RHAI) ---
+// Estatísticas que só um clube quer ("voltas por minuto real", índice de
+// handicap...) sem recompilar. METRICS_SCRIPT aponta para um script Rhai que
+// define uma função chamada uma vez por piloto:
+//   fn metrics(race, pilot) {
+//       #{ handicap: pilot.total_laps + if pilot.categoria == "Master" { 5 } else { 0 } }
+//   }
+// `race` é o JSON processado da prova e `pilot` a linha do ranking (PilotoDisplay).
+// O mapa devolvido entra em `extra` do piloto: {{ p.extra.handicap }} no
+// template. Erro no script só gera aviso e o relatório sai sem as métricas.
+// METRICS_MAX_OPERATIONS (padrão 1000000) interrompe script em laço infinito.
+// Exemplo em scripts/metrics.rhai.
+
+const FUNCAO: &str = "metrics";
+const MAX_OPERACOES_PADRAO: u64 = 1_000_000;
+
+struct Script {
+    engine: Engine,
+    ast: AST,
+}
+
+/// Compila o script uma vez por execução. `None` sem METRICS_SCRIPT ou com erro.
+fn script() -> Option<&'static Script> {
+    static SCRIPT: OnceLock<Option<Script>> = OnceLock::new();
+    SCRIPT.get_or_init(|| {
+        let path = env::var("METRICS_SCRIPT").ok().filter(|p| !p.trim().is_empty())?;
+        let codigo = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                erro!("⚠️ Script de métricas {} não pôde ser lido: {}", path, e);
+                return None;
+            }
+        };
+        let mut engine = Engine::new();
+        engine.set_max_operations(env::var("METRICS_MAX_OPERATIONS").ok().and_then(|v| v.parse().ok()).unwrap_or(MAX_OPERACOES_PADRAO));
+        match engine.compile(&codigo) {
+            Ok(ast) if ast.iter_functions().any(|f| f.name == FUNCAO && f.params.len() == 2) => Some(Script { engine, ast }),
+            Ok(_) => {
+                erro!("⚠️ Script de métricas {} não define fn {}(race, pilot)", path, FUNCAO);
+                None
+            }
+            Err(e) => {
+                erro!("⚠️ Erro ao compilar o script de métricas {}: {}", path, e);
+                None
+            }
+        }
+    }).as_ref()
+}
+
+/// Roda o script para cada piloto e grava o resultado em `extra`. No primeiro
+/// erro para, para não repetir o mesmo aviso piloto a piloto.
+pub fn aplicar(data: &Value, ranking: &mut [PilotoDisplay]) {
+    let Some(script) = script() else { return };
+    let race = match rhai::serde::to_dynamic(data) {
+        Ok(r) => r,
+        Err(e) => {
+            erro!("⚠️ Métricas: prova não pôde ser convertida para o script: {}", e);
+            return;
+        }
+    };
+    for piloto in ranking.iter_mut() {
+        let pilot = match rhai::serde::to_dynamic(&*piloto) {
+            Ok(p) => p,
+            Err(e) => {
+                erro!("⚠️ Métricas: piloto {} não pôde ser convertido para o script: {}", piloto.nome, e);
+                return;
+            }
+        };
+        let resultado = script.engine.call_fn::<Dynamic>(&mut Scope::new(), &script.ast, FUNCAO, (race.clone(), pilot))
+            .map_err(|e| e.to_string())
+            .and_then(|r| rhai::serde::from_dynamic::<Value>(&r).map_err(|e| e.to_string()));
+        match resultado {
+            Ok(Value::Object(campos)) => piloto.extra = campos,
+            Ok(Value::Null) => piloto.extra = Map::new(),
+            Ok(outro) => {
+                erro!("⚠️ Métricas: {}() deve devolver um mapa #{{ ... }}, devolveu {}", FUNCAO, outro);
+                return;
+            }
+            Err(e) => {
+                erro!("⚠️ Métricas: erro no script para {}: {}", piloto.nome, e);
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+
+use crate::schema::{self, SCHEMA_VERSION};
+
+// --- MIGRAÇÃO DOS JSONS PUBLICADOS ---
+// Uso: raceday migrate --from v1 --to v2 [--dry-run]
+// Percorre races/, reescreve na versão `--to` cada prova que está na versão
+// `--from` (schema.rs) e envia de volta para a mesma chave, para o site do clube
+// ler o arquivo inteiro num formato só. Provas em outra versão ficam como estão.
+// --dry-run só conta o que seria migrado.
+
+/// "v2" ou "2" -> 2
+fn numero_versao(texto: &str) -> Result<u64, String> {
+    texto.trim().trim_start_matches(['v', 'V']).parse::<u64>().ok()
+        .filter(|v| (1..=SCHEMA_VERSION).contains(v))
+        .ok_or_else(|| format!("❌ Versão inválida: '{}' (de v1 a v{})", texto, SCHEMA_VERSION))
+}
+
+pub async fn executar(de: &str, para: &str, simular: bool) -> Result<(), Box<dyn Error>> {
+    let (de, para) = (numero_versao(de)?, numero_versao(para)?);
+    if para <= de {
+        return Err(format!("❌ --to (v{}) precisa ser maior que --from (v{})", para, de).into());
+    }
+    let saida_dir = crate::pasta_saida();
+    fs::create_dir_all(&saida_dir)?;
+    let local = saida_dir.join("migrate.json");
+
+    let chaves = crate::storage::listar_r2("races/").await?;
+    let (mut migradas, mut outras, mut falhas) = (0, 0, 0);
+    for key in chaves.iter().filter(|k| crate::season::timestamp_da_chave(k).is_some()) {
+        let Some(bytes) = crate::storage::baixar_r2(key).await? else { continue };
+        let mut data: Value = match serde_json::from_slice(&bytes) {
+            Ok(v) => v,
+            Err(e) => {
+                erro!("⚠️ JSON inválido em {}: {}", key, e);
+                falhas += 1;
+                continue;
+            }
+        };
+        if schema::versao(&data).ok() != Some(de) {
+            outras += 1;
+            continue;
+        }
+        if let Err(e) = schema::migrar(&mut data, para) {
+            erro!("⚠️ {}: {}", key, e);
+            falhas += 1;
+            continue;
+        }
+        migradas += 1;
+        if simular {
+            saida!("🔎 {} seria migrada para v{}", key, para);
+            continue;
+        }
+        fs::write(&local, serde_json::to_string_pretty(&data)?)?;
+        crate::storage::upload_to_r2(&local, key).await?;
+    }
+
+    let verbo = if simular { "seriam migrada(s)" } else { "migrada(s)" };
+    saida!("🗄️ {} prova(s) {} de v{} para v{}; {} em outra versão; {} com erro", migradas, verbo, de, para, outras, falhas);
+    Ok(())
+}
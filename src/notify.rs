@@ -0,0 +1,41 @@
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use std::env;
+use std::error::Error;
+
+// --- NOTIFICAÇÕES POR E-MAIL (SMTP) ---
+
+/// Envia um e-mail HTML para a lista de membros (MAILING_LIST, separada por vírgulas).
+pub fn enviar_email(assunto: &str, html: &str) -> Result<usize, crate::error::RacedayError> {
+    enviar(assunto, html).map_err(|e| crate::error::RacedayError::Notificacao(e.to_string()))
+}
+
+fn enviar(assunto: &str, html: &str) -> Result<usize, Box<dyn Error>> {
+    let host = env::var("SMTP_HOST").map_err(|_| "SMTP_HOST não definida")?;
+    let from = env::var("SMTP_FROM").map_err(|_| "SMTP_FROM não definida")?;
+    let lista = env::var("MAILING_LIST").map_err(|_| "MAILING_LIST não definida")?;
+
+    let destinatarios: Vec<&str> = lista.split(',').map(|d| d.trim()).filter(|d| !d.is_empty()).collect();
+    if destinatarios.is_empty() { return Err("MAILING_LIST está vazia".into()); }
+
+    // Os membros vão em cópia oculta para não expor os endereços da lista
+    let mut builder = Message::builder()
+        .from(from.parse()?)
+        .to(from.parse()?)
+        .subject(assunto)
+        .header(ContentType::TEXT_HTML);
+    for d in &destinatarios {
+        builder = builder.bcc(d.parse()?);
+    }
+    let email = builder.body(html.to_string())?;
+
+    let mut transport = SmtpTransport::relay(&host)?;
+    if let (Ok(user), Ok(pass)) = (env::var("SMTP_USER"), env::var("SMTP_PASS")) {
+        transport = transport.credentials(Credentials::new(user, pass));
+    }
+    transport.build().send(&email)?;
+
+    Ok(destinatarios.len())
+}
+
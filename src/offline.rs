@@ -0,0 +1,23 @@
+use std::env;
+use std::path::PathBuf;
+
+// --- MODO OFFLINE ---
+// Com --offline (ou RACEDAY_OFFLINE=1) nada sai da máquina: o R2 é substituído
+// por uma pasta local (<RACEDAY_OUT_DIR>/site, ou OFFLINE_DIR) que espelha as
+// chaves do bucket (backend "dir:", ver backends.rs), e o Render, os webhooks
+// e o pós-publicação são ignorados.
+// Útil em autódromos sem internet e para conferir o relatório antes de publicar.
+
+pub fn ativo() -> bool {
+    env::var("RACEDAY_OFFLINE").map(|v| v == "1" || v.eq_ignore_ascii_case("true")).unwrap_or(false)
+}
+
+/// Pasta que faz o papel do bucket no modo offline.
+pub fn pasta() -> PathBuf {
+    env::var("OFFLINE_DIR").map(PathBuf::from).unwrap_or_else(|_| crate::pasta_saida().join("site"))
+}
+
+/// Caminho local equivalente a uma chave do bucket.
+pub fn caminho(key: &str) -> PathBuf {
+    pasta().join(key)
+}
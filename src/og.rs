@@ -0,0 +1,57 @@
+use serde_json::Value;
+
+use crate::i18n::{t, tf};
+use crate::ranking::PilotoDisplay;
+
+// --- PRÉVIA DE LINK (OPEN GRAPH / TWITTER CARD) ---
+// Depois de renderizado e antes do envio, o relatório ganha as meta tags og:* e
+// twitter:* no <head>: título com clube, pista e data, descrição com vencedor e
+// melhor volta, e o card do resultado (card.rs) como imagem. Assim o link
+// compartilhado no WhatsApp, Facebook ou Telegram aparece com uma prévia.
+
+fn escapar(texto: &str) -> String {
+    texto.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pub fn meta_tags(data: &Value, ranking: &[PilotoDisplay], club: &str, track: &str, r2_key_html: &str, r2_key_base: &str) -> String {
+    let base = crate::refresh::base_url();
+    let mut titulo = format!("{} — {} / {}", data["event"]["title"].as_str().map(|s| s.to_string()).unwrap_or_else(|| t("texts.race")), club, track);
+    if let Some(date) = data["event"]["date"].as_str().filter(|d| !d.is_empty()) {
+        titulo.push_str(&format!(" ({})", date));
+    }
+    let mut descricao = Vec::new();
+    if let Some(vencedor) = ranking.first() {
+        descricao.push(tf("texts.winner", &[("nome", vencedor.nome.clone()), ("voltas", vencedor.total_laps.to_string())]));
+    }
+    if let Some(top) = ranking.iter().find(|p| p.is_overall_best) {
+        descricao.push(tf("texts.best_lap_by", &[("nome", top.nome.clone()), ("tempo", top.best_time.clone())]));
+    }
+    let descricao = descricao.join(" ");
+    let imagem = format!("{}/{}", base, crate::card::chave(r2_key_base));
+    let url = format!("{}/{}", base, r2_key_html);
+
+    [
+        ("property", "og:type", "article".to_string()),
+        ("property", "og:locale", crate::i18n::locale().replace('-', "_")),
+        ("property", "og:title", titulo.clone()),
+        ("property", "og:description", descricao.clone()),
+        ("property", "og:image", imagem.clone()),
+        ("property", "og:image:width", "1080".to_string()),
+        ("property", "og:image:height", "1080".to_string()),
+        ("property", "og:url", url),
+        ("name", "twitter:card", "summary_large_image".to_string()),
+        ("name", "twitter:title", titulo),
+        ("name", "twitter:description", descricao),
+        ("name", "twitter:image", imagem),
+    ].iter()
+        .map(|(atributo, nome, valor)| format!("    <meta {}=\"{}\" content=\"{}\">\n", atributo, nome, escapar(valor)))
+        .collect()
+}
+
+/// Insere as meta tags antes do </head>; sem </head>, o HTML volta como está.
+pub fn injetar(html: &str, tags: &str) -> String {
+    match html.find("</head>") {
+        Some(pos) => format!("{}{}{}", &html[..pos], tags, &html[pos..]),
+        None => html.to_string(),
+    }
+}
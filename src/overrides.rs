@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::pilots::slug_piloto;
+
+// --- CORREÇÕES DA DIREÇÃO DE PROVA (overrides.toml) ---
+// Ajustes feitos depois da prova, num TOML ao lado do .INI: <nome do ini>.overrides.toml
+// ou, se não houver, overrides.toml. Uma tabela por piloto, pelo nome do cronometrador:
+//   ["João Côrtes"]
+//   penalty_laps = 2            # desconta voltas e soma nas penalidades
+//   reason = "Queima de largada"
+//
+//   ["Maria Souza"]
+//   laps = 148                  # corrige o total de voltas
+//   gap = 1.25                  # gap oficial
+//   penalties = 1               # número de penalidades
+//   status = "DSQ"              # DNF, DNS ou DSQ (ranking::STATUS)
+// Tudo é aplicado no JSON da prova antes do relatório, e cada alteração fica em
+// data["corrections"], listada no rodapé do relatório (e preservada no rebuild).
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct Correcao {
+    laps: Option<i64>,
+    penalty_laps: Option<i64>,
+    penalties: Option<i64>,
+    gap: Option<f64>,
+    status: Option<String>,
+    reason: Option<String>,
+}
+
+/// Uma alteração aplicada, como aparece no rodapé do relatório.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Aplicada {
+    pub piloto: String,
+    pub campo: String,
+    pub antes: String,
+    pub depois: String,
+    #[serde(default)]
+    pub motivo: String,
+}
+
+/// Arquivo de correções do .INI, se existir.
+pub fn caminho(ini: &Path) -> Option<PathBuf> {
+    let stem = ini.file_stem()?.to_string_lossy().to_string();
+    [ini.with_file_name(format!("{}.overrides.toml", stem)), ini.with_file_name("overrides.toml")]
+        .into_iter()
+        .find(|p| p.is_file())
+}
+
+/// Voltas atuais do piloto: ranking oficial ou, sem ele, a soma das baterias.
+fn voltas_atuais(data: &Value, id: &str) -> i64 {
+    if let Some(p) = data["official_ranking"].as_array().and_then(|r| r.iter().find(|x| x["p_id"].as_str() == Some(id))) {
+        return p["laps"].as_i64().unwrap_or(0);
+    }
+    data["races"].as_array().into_iter().flatten()
+        .flat_map(|r| r["sessions"].as_array().into_iter().flatten())
+        .filter_map(|s| s["slots"].as_object())
+        .flat_map(|slots| slots.values())
+        .filter(|s| crate::ranking::mesmo_piloto(&s["p_id"], id))
+        .map(|s| s["laps"].as_i64().unwrap_or(0))
+        .sum()
+}
+
+/// Entrada do piloto no ranking oficial, criada se o .INI não trouxer.
+fn entrada_oficial<'a>(data: &'a mut Value, id: &str, nome: &str) -> &'a mut Value {
+    let laps = voltas_atuais(data, id);
+    if !data["official_ranking"].is_array() {
+        data["official_ranking"] = json!([]);
+    }
+    let ranking = data["official_ranking"].as_array_mut().expect("official_ranking é array");
+    let pos = match ranking.iter().position(|x| x["p_id"].as_str() == Some(id)) {
+        Some(pos) => pos,
+        None => {
+            ranking.push(json!({ "p_id": id, "name": nome, "laps": laps, "gap": "0" }));
+            ranking.len() - 1
+        }
+    };
+    &mut ranking[pos]
+}
+
+/// Número de penalidades no formato do .INI (texto) ou já numérico.
+fn penalidades_atuais(data: &Value, id: &str) -> i64 {
+    let v = &data["raw_results"]["penaltys"][id];
+    v.as_i64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok())).unwrap_or(0)
+}
+
+/// Aplica as correções do arquivo ao lado de `ini`. Retorna quantas alterações entraram.
+pub fn aplicar(data: &mut Value, ini: &Path) -> Result<usize, String> {
+    let Some(path) = caminho(ini) else { return Ok(0) };
+    let texto = fs::read_to_string(&path).map_err(|e| format!("❌ {}: {}", path.display(), e))?;
+    let correcoes: BTreeMap<String, Correcao> = toml::from_str(&texto).map_err(|e| format!("❌ {} inválido: {}", path.display(), e))?;
+
+    let pilotos: Vec<(String, String)> = data["pilots"].as_object().into_iter().flatten()
+        .map(|(id, info)| (id.clone(), info["name"].as_str().unwrap_or("").to_string()))
+        .collect();
+    let mut aplicadas: Vec<Aplicada> = serde_json::from_value(data["corrections"].clone()).unwrap_or_default();
+    let inicio = aplicadas.len();
+
+    for (nome, c) in correcoes {
+        let Some((id, nome_real)) = pilotos.iter().find(|(_, n)| slug_piloto(n) == slug_piloto(&nome)).cloned() else {
+            erro!("⚠️ {}: piloto '{}' não está nesta prova", path.display(), nome);
+            continue;
+        };
+        let motivo = c.reason.clone().unwrap_or_default();
+        let mut registrar = |campo: &str, antes: String, depois: String| aplicadas.push(Aplicada {
+            piloto: nome_real.clone(), campo: campo.to_string(), antes, depois, motivo: motivo.clone(),
+        });
+
+        if let Some(laps) = c.laps {
+            let antes = voltas_atuais(data, &id);
+            entrada_oficial(data, &id, &nome_real)["laps"] = json!(laps);
+            registrar("laps", antes.to_string(), laps.to_string());
+        }
+        if let Some(penalty) = c.penalty_laps.filter(|n| *n != 0) {
+            let antes = voltas_atuais(data, &id);
+            entrada_oficial(data, &id, &nome_real)["laps"] = json!(antes - penalty);
+            let penalidades = penalidades_atuais(data, &id);
+            data["raw_results"]["penaltys"][&id] = Value::String((penalidades + penalty).to_string());
+            // Já descontadas: as regras de penalidade (penalties.rs) não cobram de novo
+            let descontadas = data["raw_results"]["penalty_laps"][&id].as_str().and_then(|s| s.parse::<i64>().ok()).unwrap_or(0);
+            data["raw_results"]["penalty_laps"][&id] = Value::String((descontadas + penalty).to_string());
+            registrar("penalty_laps", antes.to_string(), (antes - penalty).to_string());
+        }
+        if let Some(penalties) = c.penalties {
+            let antes = penalidades_atuais(data, &id);
+            data["raw_results"]["penaltys"][&id] = Value::String(penalties.to_string());
+            registrar("penalties", antes.to_string(), penalties.to_string());
+        }
+        if let Some(gap) = c.gap {
+            let entrada = entrada_oficial(data, &id, &nome_real);
+            let antes = entrada["gap"].as_str().unwrap_or("0").to_string();
+            entrada["gap"] = Value::String(gap.to_string());
+            registrar("gap", antes, gap.to_string());
+        }
+        if let Some(status) = c.status.map(|s| s.trim().to_uppercase()) {
+            let antes = data["pilots"][&id]["status"].as_str().unwrap_or("").to_string();
+            data["pilots"][&id]["status"] = Value::String(status.clone());
+            registrar("status", antes, status);
+        }
+    }
+
+    let novas = aplicadas.len() - inicio;
+    if novas > 0 {
+        saida!("✏️ {} correção(ões) da direção de prova aplicada(s) de {}", novas, path.display());
+        data["corrections"] = serde_json::to_value(&aplicadas).map_err(|e| e.to_string())?;
+    }
+    Ok(novas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pasta temporária com o .INI (só o nome) e o TOML de correções ao lado.
+    fn preparar(nome: &str, toml: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("raceday_overrides_{}_{}", std::process::id(), nome));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("GP_1.overrides.toml"), toml).unwrap();
+        dir.join("GP_1.INI")
+    }
+
+    fn prova() -> Value {
+        json!({
+            "pilots": { "1": { "name": "João Côrtes" }, "2": { "name": "Maria Souza" } },
+            "official_ranking": [{ "p_id": "1", "name": "João Côrtes", "laps": 150, "gap": "0" }],
+            "races": [{ "sessions": [{ "slots": { "1": { "p_id": 2, "laps": 70 } } }, { "slots": { "2": { "p_id": "2", "laps": 75 } } }] }],
+            "raw_results": { "penaltys": { "1": "1" } },
+        })
+    }
+
+    #[test]
+    fn sem_arquivo_nada_muda() {
+        let ini = std::env::temp_dir().join(format!("raceday_overrides_{}_inexistente", std::process::id())).join("GP_1.INI");
+        let mut data = prova();
+        assert_eq!(aplicar(&mut data, &ini), Ok(0));
+        assert_eq!(data, prova());
+    }
+
+    #[test]
+    fn penalidade_em_voltas_e_status() {
+        let ini = preparar("penalidade", "[\"joao cortes\"]\npenalty_laps = 2\nreason = \"Queima de largada\"\n\n[\"Maria Souza\"]\nstatus = \"dsq\"\n");
+        let mut data = prova();
+        let novas = aplicar(&mut data, &ini);
+        fs::remove_dir_all(ini.parent().unwrap()).unwrap();
+
+        assert_eq!(novas, Ok(2));
+        assert_eq!(data["official_ranking"][0]["laps"], 148);
+        assert_eq!(data["raw_results"]["penaltys"]["1"], "3");
+        assert_eq!(data["raw_results"]["penalty_laps"]["1"], "2");
+        assert_eq!(data["pilots"]["2"]["status"], "DSQ");
+        let aplicadas: Vec<Aplicada> = serde_json::from_value(data["corrections"].clone()).unwrap();
+        let joao = aplicadas.iter().find(|a| a.campo == "penalty_laps").unwrap();
+        assert_eq!(joao.piloto, "João Côrtes");
+        assert_eq!((joao.antes.as_str(), joao.depois.as_str(), joao.motivo.as_str()), ("150", "148", "Queima de largada"));
+    }
+
+    #[test]
+    fn voltas_e_gap_criam_entrada_oficial() {
+        let ini = preparar("voltas", "[\"Maria Souza\"]\ngap = 1.25\n");
+        let mut data = prova();
+        let novas = aplicar(&mut data, &ini);
+        fs::remove_dir_all(ini.parent().unwrap()).unwrap();
+
+        assert_eq!(novas, Ok(1));
+        // Sem entrada no ranking oficial: as voltas vêm da soma das baterias (p_id numérico ou texto)
+        let maria = &data["official_ranking"][1];
+        assert_eq!((maria["p_id"].as_str(), maria["laps"].as_i64(), maria["gap"].as_str()), (Some("2"), Some(145), Some("1.25")));
+    }
+
+    #[test]
+    fn piloto_desconhecido_e_campo_invalido() {
+        let ini = preparar("desconhecido", "[\"Fulano\"]\nlaps = 10\n");
+        let mut data = prova();
+        assert_eq!(aplicar(&mut data, &ini), Ok(0));
+        assert!(data.get("corrections").is_none());
+
+        fs::write(ini.with_file_name("GP_1.overrides.toml"), "[\"Maria Souza\"]\nvoltas = 10\n").unwrap();
+        let erro = aplicar(&mut data, &ini).unwrap_err();
+        fs::remove_dir_all(ini.parent().unwrap()).unwrap();
+        assert!(erro.contains("inválido"), "{}", erro);
+    }
+}
@@ -0,0 +1,136 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// --- CLOUDFLARE PAGES (DEPLOY DIRETO) ---
+// Com STORAGE_BACKEND=pages:<projeto> os arquivos publicados vão para uma pasta
+// local (PAGES_SITE_DIR, padrão <RACEDAY_OUT_DIR>/pages) que é o site inteiro,
+// com o index.html de site_index.rs; cada prova cria um deployment no projeto do Pages.
+// Um deployment do Pages é um retrato completo do site, por isso a pasta precisa
+// ser mantida entre as execuções (só os arquivos novos são enviados de fato).
+//   CLOUDFLARE_ACCOUNT_ID   conta da Cloudflare
+//   CLOUDFLARE_API_TOKEN    token com permissão "Cloudflare Pages: Edit"
+//   PAGES_BRANCH            branch do deployment (padrão: a de produção do projeto)
+
+const API: &str = "https://api.cloudflare.com/client/v4";
+const ARQUIVOS_POR_LOTE: usize = 50;
+
+/// Projeto do Pages configurado em STORAGE_BACKEND, se houver.
+pub fn projeto() -> Option<String> {
+    if crate::offline::ativo() { return None; }
+    env::var("STORAGE_BACKEND").ok()?.trim().strip_prefix("pages:").map(|p| p.to_string())
+}
+
+pub fn pasta_site() -> PathBuf {
+    env::var("PAGES_SITE_DIR").map(PathBuf::from).unwrap_or_else(|_| crate::pasta_saida().join("pages"))
+}
+
+fn tipo_conteudo(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html",
+        Some("xml") => "application/xml",
+        Some("jpg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("csv") => "text/csv; charset=utf-8",
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        Some("gz") => "application/gzip",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        _ => "application/json",
+    }
+}
+
+/// Mesmo hash do wrangler: blake3(base64(conteúdo) + extensão), 32 caracteres hex.
+fn hash_arquivo(conteudo_b64: &str, path: &Path) -> String {
+    let extensao = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    blake3::hash(format!("{}{}", conteudo_b64, extensao).as_bytes()).to_hex()[..32].to_string()
+}
+
+fn coletar(raiz: &Path, dir: &Path, arquivos: &mut Vec<(String, PathBuf)>) {
+    let Ok(entradas) = fs::read_dir(dir) else { return };
+    for entrada in entradas.flatten() {
+        let path = entrada.path();
+        if path.is_dir() {
+            coletar(raiz, &path, arquivos);
+        } else if let Ok(relativo) = path.strip_prefix(raiz) {
+            let partes: Vec<String> = relativo.components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            arquivos.push((format!("/{}", partes.join("/")), path));
+        }
+    }
+}
+
+async fn resposta(requisicao: reqwest::RequestBuilder) -> Result<Value, Box<dyn Error>> {
+    let corpo: Value = requisicao.send().await?.json().await?;
+    if corpo["success"].as_bool() != Some(true) {
+        return Err(format!("API do Cloudflare Pages: {}", corpo["errors"]).into());
+    }
+    Ok(corpo["result"].clone())
+}
+
+/// Cria um deployment com o conteúdo atual da pasta do site (inclusive o index.html).
+pub async fn implantar(projeto: &str, mensagem: &str) -> Result<String, Box<dyn Error>> {
+    let conta = env::var("CLOUDFLARE_ACCOUNT_ID").map_err(|_| "❌ CLOUDFLARE_ACCOUNT_ID não definida")?;
+    let token = env::var("CLOUDFLARE_API_TOKEN").map_err(|_| "❌ CLOUDFLARE_API_TOKEN não definida")?;
+    let site = pasta_site();
+
+    let mut arquivos = Vec::new();
+    coletar(&site, &site, &mut arquivos);
+    let mut manifesto = BTreeMap::new();
+    let mut conteudos = BTreeMap::new();
+    for (rota, path) in &arquivos {
+        let b64 = STANDARD.encode(fs::read(path)?);
+        let hash = hash_arquivo(&b64, path);
+        manifesto.insert(rota.clone(), hash.clone());
+        conteudos.insert(hash, (b64, tipo_conteudo(path)));
+    }
+
+    // Os assets são enviados com um JWT de curta duração emitido para o projeto
+    let client = reqwest::Client::new();
+    let projeto_url = format!("{}/accounts/{}/pages/projects/{}", API, conta, projeto);
+    let jwt = resposta(client.get(format!("{}/upload-token", projeto_url)).bearer_auth(&token)).await?["jwt"]
+        .as_str().ok_or("❌ Token de upload do Pages não retornado")?.to_string();
+
+    let hashes: Vec<&String> = conteudos.keys().collect();
+    let faltando: Vec<String> = resposta(client.post(format!("{}/pages/assets/check-missing", API))
+        .bearer_auth(&jwt)
+        .json(&json!({ "hashes": hashes }))).await?
+        .as_array().map(|l| l.iter().filter_map(|h| h.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+
+    for lote in faltando.chunks(ARQUIVOS_POR_LOTE) {
+        let itens: Vec<Value> = lote.iter().filter_map(|h| conteudos.get(h).map(|(b64, tipo)| json!({
+            "key": h,
+            "value": b64,
+            "metadata": { "contentType": tipo },
+            "base64": true,
+        }))).collect();
+        resposta(client.post(format!("{}/pages/assets/upload", API)).bearer_auth(&jwt).json(&itens)).await?;
+    }
+    resposta(client.post(format!("{}/pages/assets/upsert-hashes", API))
+        .bearer_auth(&jwt)
+        .json(&json!({ "hashes": hashes }))).await?;
+
+    let mut formulario = reqwest::multipart::Form::new()
+        .text("manifest", serde_json::to_string(&manifesto)?)
+        .text("commit_message", mensagem.to_string());
+    if let Ok(branch) = env::var("PAGES_BRANCH") {
+        formulario = formulario.text("branch", branch);
+    }
+    let deployment = resposta(client.post(format!("{}/deployments", projeto_url))
+        .bearer_auth(&token)
+        .multipart(formulario)).await?;
+    let url = deployment["url"].as_str().unwrap_or_default().to_string();
+
+    saida!("🚀 Deploy no Cloudflare Pages ({}): {} arquivo(s), {} novo(s) — {}", projeto, manifesto.len(), faltando.len(), url);
+    Ok(url)
+}
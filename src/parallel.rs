@@ -0,0 +1,31 @@
+use std::env;
+use std::num::NonZeroUsize;
+use std::thread;
+
+// --- RENDERIZAÇÃO EM PARALELO ---
+// O render do Tera é CPU-bound: dividimos as páginas entre threads e devolvemos
+// os resultados na ordem original. RACEDAY_THREADS limita o número de threads.
+
+fn numero_threads() -> usize {
+    env::var("RACEDAY_THREADS").ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or_else(|| thread::available_parallelism().map(NonZeroUsize::get).unwrap_or(1))
+}
+
+pub fn mapear<T: Sync, R: Send>(itens: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let threads = numero_threads().min(itens.len());
+    if threads <= 1 {
+        return itens.iter().map(&f).collect();
+    }
+    let tamanho = itens.len().div_ceil(threads);
+    let f = &f;
+    thread::scope(|s| {
+        let partes: Vec<_> = itens.chunks(tamanho)
+            .map(|parte| s.spawn(move || parte.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        partes.into_iter()
+            .flat_map(|p| p.join().expect("❌ Thread de renderização falhou"))
+            .collect()
+    })
+}
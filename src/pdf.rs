@@ -0,0 +1,84 @@
+use std::env;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+// --- EXPORTAÇÃO EM PDF ---
+// `raceday process corrida.ini --pdf` (ou PDF_EXPORT=1, que vale também para
+// watch, live e --dir) converte o relatório em PDF com o Chrome/Edge/Chromium
+// em modo headless e publica reports/<base>.pdf, para entregar impresso aos
+// pilotos e arquivar como documento oficial. Se a versão para impressão
+// (PRINT_CHART) foi gerada, ela é a usada; senão, o relatório completo.
+//   CHROME_PATH       executável do navegador (padrão: procura Chrome, Edge e Chromium)
+//   PDF_TIMEOUT_SECS  tempo máximo da conversão (padrão: 60)
+
+const TIMEOUT_PADRAO_SECS: u64 = 60;
+/// Tempo virtual para o Chart.js desenhar antes da impressão.
+const ESPERA_JS_MS: u32 = 5000;
+
+pub fn ativo() -> bool {
+    env::var("PDF_EXPORT").is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes" | "sim"))
+}
+
+fn navegador() -> Option<PathBuf> {
+    if let Some(caminho) = env::var_os("CHROME_PATH") {
+        return Some(PathBuf::from(caminho));
+    }
+    if cfg!(target_os = "windows") {
+        let instalados = [
+            r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+            r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+            r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+            r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
+        ];
+        return instalados.iter().map(PathBuf::from).find(|p| p.exists());
+    }
+    let nomes = ["chromium", "chromium-browser", "google-chrome", "google-chrome-stable", "microsoft-edge"];
+    let pastas: Vec<PathBuf> = env::var_os("PATH").map(|p| env::split_paths(&p).collect()).unwrap_or_default();
+    if cfg!(target_os = "macos") {
+        let app = PathBuf::from("/Applications/Google Chrome.app/Contents/MacOS/Google Chrome");
+        if app.exists() { return Some(app); }
+    }
+    nomes.iter().flat_map(|n| pastas.iter().map(move |d| d.join(n))).find(|p| p.is_file())
+}
+
+/// Converte o HTML local em PDF.
+pub async fn gerar(html: &Path, destino: &Path) -> Result<(), Box<dyn Error>> {
+    let programa = navegador().ok_or("❌ Chrome/Edge/Chromium não encontrado para gerar o PDF (defina CHROME_PATH)")?;
+    let html = std::path::absolute(html)?;
+    let url = format!("file:///{}", html.to_string_lossy().replace('\\', "/").trim_start_matches('/'));
+    let timeout = Duration::from_secs(env::var("PDF_TIMEOUT_SECS").ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(TIMEOUT_PADRAO_SECS));
+
+    let mut comando = Command::new(&programa);
+    comando.args([
+        "--headless".to_string(),
+        "--disable-gpu".to_string(),
+        "--no-pdf-header-footer".to_string(),
+        format!("--virtual-time-budget={}", ESPERA_JS_MS),
+        format!("--print-to-pdf={}", destino.display()),
+        url,
+    ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+
+    let status = tokio::time::timeout(timeout, comando.status()).await
+        .map_err(|_| format!("❌ Conversão para PDF excedeu {}s", timeout.as_secs()))??;
+    if !status.success() || !destino.exists() {
+        return Err(format!("❌ {} não gerou o PDF ({})", programa.display(), status).into());
+    }
+    Ok(())
+}
+
+/// Gera o PDF a partir de `html` e publica em <base>.pdf. Retorna a chave.
+pub async fn publicar(html: &Path, r2_key_base: &str) -> Result<String, Box<dyn Error>> {
+    let local = crate::pasta_saida().join("last_upload.pdf");
+    gerar(html, &local).await?;
+    let key = format!("{}.pdf", r2_key_base);
+    crate::storage::upload_to_r2(&local, &key).await?;
+    Ok(key)
+}
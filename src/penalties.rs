@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+
+use crate::ranking::PilotoDisplay;
+
+// --- REGRAS DE PENALIDADE ---
+// O .INI só conta as penalidades de cada piloto; o que cada uma custa é do
+// regulamento do clube. Por penalidade (valores somados; 0 ou ausente desliga):
+//   PENALTY_LAPS      voltas descontadas do total
+//   PENALTY_SECONDS   segundos somados: na classificação por voltas viram fração de
+//                     volta (pela volta média, ou a melhor sem tempos volta a volta),
+//                     descontando voltas inteiras e o resto no gap; na best_lap, somam
+//                     na melhor volta
+//   PENALTY_POINTS    pontos descontados no campeonato (e na classificação por pontos)
+// No raceday.toml: [penalties] laps, seconds, points. Sem regras as penalidades
+// só aparecem no relatório, como antes. As regras valem ao montar o ranking, então
+// relatório, exportações, temporada e rebuild usam os mesmos valores.
+// Voltas já descontadas pelo overrides.toml (penalty_laps) não são cobradas de novo.
+
+#[derive(Default)]
+pub struct Regras {
+    pub voltas: i64,
+    pub segundos: f64,
+    pub pontos: f64,
+}
+
+/// O que as penalidades custaram a um piloto, para o relatório e a temporada.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Aplicada {
+    pub voltas: i64,
+    pub segundos: f64,
+    pub pontos: f64,
+    /// Parte do tempo de penalidade que não chegou a uma volta inteira, em voltas
+    /// (tira distância no gap calculado sem o ranking oficial, ranking.rs)
+    #[serde(skip)]
+    pub fracao_volta: f64,
+}
+
+impl Aplicada {
+    pub fn vazia(&self) -> bool {
+        self.voltas == 0 && self.segundos == 0.0 && self.pontos == 0.0
+    }
+}
+
+/// PENALTY_LAPS, PENALTY_SECONDS e PENALTY_POINTS.
+pub fn regras() -> Regras {
+    let numero = |nome: &str| env::var(nome).ok().and_then(|v| v.trim().replace(',', ".").parse::<f64>().ok()).unwrap_or(0.0);
+    Regras {
+        voltas: numero("PENALTY_LAPS").round() as i64,
+        segundos: numero("PENALTY_SECONDS"),
+        pontos: numero("PENALTY_POINTS"),
+    }
+}
+
+/// Voltas de penalidade que o overrides.toml já descontou, pelo nome do piloto.
+fn ja_descontadas(data: &Value) -> HashMap<String, i64> {
+    data["pilots"].as_object().into_iter().flatten()
+        .filter_map(|(id, info)| {
+            let v = &data["raw_results"]["penalty_laps"][id];
+            let voltas = v.as_i64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))?;
+            Some((info["name"].as_str()?.to_string(), voltas))
+        })
+        .collect()
+}
+
+/// Aplica as regras no ranking antes da classificação.
+pub fn aplicar(ranking: &mut [PilotoDisplay], data: &Value) {
+    let por_tempo = crate::classification::criterio().nome() == "best_lap";
+    aplicar_regras(ranking, data, &regras(), por_tempo);
+}
+
+fn aplicar_regras(ranking: &mut [PilotoDisplay], data: &Value, regras: &Regras, por_tempo: bool) {
+    if regras.voltas == 0 && regras.segundos == 0.0 && regras.pontos == 0.0 { return; }
+    let descontadas = ja_descontadas(data);
+
+    for p in ranking.iter_mut() {
+        // Penalidades lançadas no overrides.toml com penalty_laps já tiraram as voltas
+        let ja = descontadas.get(&p.nome).copied().unwrap_or(0);
+        let contadas = p.penalties - if regras.voltas > 0 { ja / regras.voltas } else { 0 };
+        if contadas <= 0 { continue; }
+        let mut aplicada = Aplicada {
+            voltas: contadas * regras.voltas,
+            segundos: contadas as f64 * regras.segundos,
+            pontos: contadas as f64 * regras.pontos,
+            fracao_volta: 0.0,
+        };
+        p.total_laps -= aplicada.voltas;
+        // Cada volta descontada é uma volta a mais de distância para o líder
+        p.gap_laps += aplicada.voltas as f64;
+
+        if aplicada.segundos > 0.0 {
+            if por_tempo {
+                if p.best_lap_secs > 0.0 { p.best_lap_secs += aplicada.segundos; }
+            } else {
+                let volta = if p.average_lap_secs > 0.0 { p.average_lap_secs } else { p.best_lap_secs };
+                if volta > 0.0 {
+                    // O gap é em voltas para o líder: a fração que passar da volta custa uma volta inteira
+                    let tempo = aplicada.segundos / volta;
+                    let inteiras = (p.gap_laps.fract() + tempo).floor() as i64;
+                    p.total_laps -= inteiras;
+                    aplicada.voltas += inteiras;
+                    aplicada.fracao_volta = tempo - inteiras as f64;
+                    p.gap_laps += tempo;
+                }
+            }
+        }
+        if !aplicada.vazia() { p.gap = format!("{:.3}", p.gap_laps); }
+        p.penalidade = aplicada;
+    }
+    rebasear_gaps(ranking);
+}
+
+/// Depois das penalidades o líder pode ser outro: os gaps passam a contar a partir
+/// de quem ficou com a menor distância (entre os que largaram).
+fn rebasear_gaps(ranking: &mut [PilotoDisplay]) {
+    let base = ranking.iter().filter(|p| p.largou_valido()).map(|p| p.gap_laps).fold(f64::INFINITY, f64::min);
+    if !base.is_finite() || base == 0.0 { return; }
+    for p in ranking.iter_mut() {
+        p.gap_laps = ((p.gap_laps - base).max(0.0) * 1000.0).round() / 1000.0;
+        p.gap = format!("{:.3}", p.gap_laps);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn piloto(nome: &str, voltas: i64, gap: f64, penalidades: i64) -> PilotoDisplay {
+        PilotoDisplay {
+            nome: nome.to_string(),
+            total_laps: voltas,
+            gap_laps: gap,
+            gap: format!("{:.3}", gap),
+            penalties: penalidades,
+            average_lap_secs: 5.0,
+            ..Default::default()
+        }
+    }
+
+    fn sem_descontos() -> Value {
+        json!({ "pilots": {} })
+    }
+
+    #[test]
+    fn voltas_descontadas_entram_no_gap() {
+        let mut ranking = vec![piloto("Ana", 50, 0.0, 0), piloto("Bruno", 49, 1.0, 1)];
+        aplicar_regras(&mut ranking, &sem_descontos(), &Regras { voltas: 2, ..Default::default() }, false);
+        assert_eq!(ranking[1].total_laps, 47);
+        assert_eq!(ranking[1].gap_laps, 3.0);
+        assert_eq!(ranking[1].penalidade.voltas, 2);
+    }
+
+    #[test]
+    fn lider_penalizado_rebaseia_os_gaps() {
+        let mut ranking = vec![piloto("Ana", 50, 0.0, 2), piloto("Bruno", 49, 1.0, 0)];
+        aplicar_regras(&mut ranking, &sem_descontos(), &Regras { voltas: 1, ..Default::default() }, false);
+        assert_eq!(ranking[0].gap_laps, 1.0);
+        assert_eq!(ranking[1].gap_laps, 0.0);
+        assert_eq!(ranking[1].gap, "0.000");
+    }
+
+    #[test]
+    fn segundos_viram_fracao_de_volta() {
+        // 2 penalidades de 2s com volta média de 5s: 0,8 volta; com 0,5 de gap passa de uma volta
+        let mut ranking = vec![piloto("Ana", 50, 0.0, 0), piloto("Bruno", 49, 0.5, 2)];
+        aplicar_regras(&mut ranking, &sem_descontos(), &Regras { segundos: 2.0, ..Default::default() }, false);
+        assert_eq!(ranking[1].total_laps, 48);
+        assert_eq!(ranking[1].penalidade.voltas, 1);
+        assert!((ranking[1].gap_laps - 1.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn segundos_na_melhor_volta_por_tempo() {
+        let mut ranking = vec![PilotoDisplay { best_lap_secs: 4.9, penalties: 1, ..Default::default() }];
+        aplicar_regras(&mut ranking, &sem_descontos(), &Regras { segundos: 0.5, ..Default::default() }, true);
+        assert!((ranking[0].best_lap_secs - 5.4).abs() < 1e-9);
+        assert_eq!(ranking[0].total_laps, 0);
+    }
+
+    #[test]
+    fn nao_cobra_de_novo_o_penalty_laps() {
+        let data = json!({
+            "pilots": { "1": { "name": "Bruno" } },
+            "raw_results": { "penalty_laps": { "1": 2 } },
+        });
+        // As 2 penalidades já tiraram 2 voltas pelo overrides.toml
+        let mut ranking = vec![piloto("Ana", 50, 0.0, 0), piloto("Bruno", 47, 3.0, 2)];
+        aplicar_regras(&mut ranking, &data, &Regras { voltas: 1, pontos: 1.0, ..Default::default() }, false);
+        assert_eq!(ranking[1].total_laps, 47);
+        assert_eq!(ranking[1].penalidade.pontos, 0.0);
+    }
+}
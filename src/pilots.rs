@@ -0,0 +1,177 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use tera::{Context, Tera};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::history::Historico;
+use crate::parallel;
+
+// --- PÁGINAS DE PILOTO ---
+// pilots/<clube>_<piloto>.html, refeitas a cada prova processada para quem correu:
+// evolução do índice na temporada, todas as provas do piloto no clube (com link
+// para o relatório), melhor volta em cada pista e a tendência de voltas por etapa.
+
+#[derive(Serialize)]
+pub struct PontoTendencia {
+    pub timestamp: String,
+    pub titulo: String,
+    pub track: String,
+    pub data: String,
+    pub posicao: usize,
+    pub indice: f64,
+}
+
+/// Mesma regra do preparador Python: 'João Côrtes' -> 'joao_cortes'
+pub fn slug_piloto(nome: &str) -> String {
+    let ascii: String = nome.nfkd().filter(|c| c.is_ascii()).collect();
+    ascii.chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '_' || *c == '-')
+        .collect::<String>()
+        .trim()
+        .replace(' ', "_")
+        .to_lowercase()
+}
+
+/// Evolução do índice de performance do piloto nas provas da temporada (ano do timestamp).
+pub fn tendencia_piloto(historico: &Historico, club: &str, nome: &str, temporada: &str) -> Vec<PontoTendencia> {
+    historico.eventos.iter()
+        .filter(|e| e.club == club && e.timestamp.starts_with(temporada))
+        .filter_map(|e| e.resultados.iter().find(|r| r.nome == nome).map(|r| PontoTendencia {
+            timestamp: e.timestamp.clone(),
+            titulo: e.titulo.clone(),
+            track: e.track.clone(),
+            data: e.data.clone(),
+            posicao: r.posicao,
+            indice: r.indice,
+        }))
+        .collect()
+}
+
+/// Uma prova da carreira do piloto no clube.
+#[derive(Serialize)]
+pub struct ProvaPiloto {
+    pub timestamp: String,
+    pub titulo: String,
+    pub track: String,
+    pub data: String,
+    pub posicao: usize,
+    pub total_laps: i64,
+    pub best_lap: f64,
+    pub link: String,
+}
+
+#[derive(Serialize)]
+pub struct MelhorVoltaPista {
+    pub track: String,
+    pub best_lap: f64,
+    pub data: String,
+    pub titulo: String,
+}
+
+/// Todas as provas do piloto no clube, da mais antiga para a mais recente.
+pub fn carreira_piloto(historico: &Historico, club: &str, nome: &str) -> Vec<ProvaPiloto> {
+    historico.eventos.iter()
+        .filter(|e| e.club == club)
+        .filter_map(|e| e.resultados.iter().find(|r| r.nome == nome).map(|r| ProvaPiloto {
+            timestamp: e.timestamp.clone(),
+            titulo: e.titulo.clone(),
+            track: e.track.clone(),
+            data: e.data.clone(),
+            posicao: r.posicao,
+            total_laps: r.total_laps,
+            best_lap: r.best_lap,
+            link: crate::refresh::link_relatorio(e),
+        }))
+        .collect()
+}
+
+fn melhores_por_pista(provas: &[ProvaPiloto]) -> Vec<MelhorVoltaPista> {
+    let mut melhores: BTreeMap<&str, &ProvaPiloto> = BTreeMap::new();
+    for p in provas.iter().filter(|p| p.best_lap > 0.0) {
+        let atual = melhores.entry(p.track.as_str()).or_insert(p);
+        if p.best_lap < atual.best_lap { *atual = p; }
+    }
+    melhores.into_iter().map(|(track, p)| MelhorVoltaPista {
+        track: track.to_string(),
+        best_lap: p.best_lap,
+        data: p.data.clone(),
+        titulo: p.titulo.clone(),
+    }).collect()
+}
+
+fn gerar_json_voltas(provas: &[ProvaPiloto]) -> String {
+    let labels: Vec<String> = provas.iter().map(|p| format!("{} ({})", p.data, p.track)).collect();
+    let valores: Vec<i64> = provas.iter().map(|p| p.total_laps).collect();
+    serde_json::json!({
+        "labels": labels,
+        "datasets": [{
+            "label": "Voltas por etapa",
+            "data": valores,
+            "backgroundColor": "#22c55e",
+        }]
+    }).to_string()
+}
+
+fn gerar_json_tendencia(nome: &str, pontos: &[PontoTendencia]) -> String {
+    let labels: Vec<String> = pontos.iter().map(|p| format!("{} ({})", p.data, p.track)).collect();
+    let valores: Vec<f64> = pontos.iter().map(|p| p.indice).collect();
+    serde_json::json!({
+        "labels": labels,
+        "datasets": [{
+            "label": format!("Índice de performance - {}", nome),
+            "data": valores,
+            "borderColor": "#3b82f6",
+            "backgroundColor": "#3b82f6",
+            "fill": false,
+            "tension": 0.2
+        }]
+    }).to_string()
+}
+
+/// Renderiza e publica a página de cada piloto, retornando quantas foram enviadas.
+/// Com `somente_alterados`, páginas idênticas às publicadas não são reenviadas.
+pub async fn publicar_paginas(tera: &mut Tera, historico: &Historico, club: &str, nomes: &[String], temporada: &str, somente_alterados: bool) -> Result<usize, Box<dyn Error>> {
+    crate::templates::carregar(tera, "pilot.html", "pilot")?;
+    let club_slug = club.to_lowercase().replace(" ", "_");
+    let local_dir = crate::pasta_saida().join("pilots");
+    fs::create_dir_all(&local_dir)?;
+
+    let ratings = if crate::rating::ativo() { Some(crate::rating::carregar(club).await?) } else { None };
+    let paginas: Vec<(&String, Vec<PontoTendencia>, Vec<ProvaPiloto>)> = nomes.iter()
+        .map(|nome| (nome, tendencia_piloto(historico, club, nome, temporada), carreira_piloto(historico, club, nome)))
+        .filter(|(_, _, carreira)| !carreira.is_empty())
+        .collect();
+
+    // Renderiza todas em paralelo; o upload continua sequencial
+    let tera: &Tera = tera;
+    let renderizadas = parallel::mapear(&paginas, |(nome, pontos, carreira)| {
+        let mut context = Context::new();
+        context.insert("club", club);
+        context.insert("nome", nome);
+        context.insert("temporada", temporada);
+        context.insert("provas", pontos);
+        context.insert("dados_tendencia", &gerar_json_tendencia(nome, pontos));
+        context.insert("carreira", &carreira.iter().rev().collect::<Vec<_>>());
+        context.insert("melhores_voltas", &melhores_por_pista(carreira));
+        context.insert("dados_voltas", &gerar_json_voltas(carreira));
+        context.insert("rating", &ratings.as_ref().and_then(|r| r.pilotos.get(*nome)));
+        tera.render("pilot", &context)
+    });
+
+    let mut enviadas = 0;
+    for ((nome, _, _), html) in paginas.iter().zip(renderizadas) {
+        let slug = slug_piloto(nome);
+        let local_path = local_dir.join(format!("{}.html", slug));
+        fs::write(&local_path, html?)?;
+        let key = format!("pilots/{}_{}.html", club_slug, slug);
+        if somente_alterados {
+            if crate::storage::upload_se_mudou(&local_path, &key).await? { enviadas += 1; }
+        } else {
+            crate::storage::upload_to_r2(&local_path, &key).await?;
+            enviadas += 1;
+        }
+    }
+    Ok(enviadas)
+}
@@ -0,0 +1,313 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tera::Tera;
+
+use crate::error::RacedayError;
+use crate::ranking::{aplicar_indice, montar_ranking};
+use crate::{aliases, console, assets, card, csv_export, entrants, gallery, history, insights, markdown, marshals, og, overrides, pdf, pilots, post_publish, prep, print, private_mode, privacy, public_api, qr, rating, records, registry, report, share, storage, top_laps, videos, views, xlsx_export};
+
+// --- PROCESSAMENTO DE UMA PROVA ---
+// .INI -> JSON -> relatório HTML -> R2 -> histórico -> páginas de piloto -> pós-publicação.
+
+/// Mídias e saídas opcionais da prova (--photos, --video, --pdf).
+#[derive(Default)]
+pub struct Extras {
+    pub fotos: Option<PathBuf>,
+    pub videos: Vec<String>,
+    pub pdf: bool,
+}
+
+/// O que foi publicado, para quem chama o `raceday` por script (--result-file).
+#[derive(Serialize)]
+pub struct ResultadoProva {
+    pub report_url: String,
+    pub json_key: String,
+    pub html_key: String,
+    pub slug: String,
+    pub timestamp: String,
+    pub winner: Option<String>,
+    pub club: String,
+    pub track: String,
+    /// Falso quando a prova já estava publicada igual e nada foi enviado.
+    pub changed: bool,
+}
+
+pub async fn processar(ini_path: &Path, club: &str, track: &str, extras: &Extras) -> Result<ResultadoProva, RacedayError> {
+    privacy::carregar()?;
+
+    // --- PRÉ-PROCESSAMENTO (PARSER NATIVO OU PREPARADOR EXTERNO) ---
+    let mut data: Value = console::etapa(1, "preparo", prep::executar(ini_path, club, track)).await?;
+
+    // --- GALERIA DE FOTOS (OPCIONAL: --photos <pasta>) ---
+    // Fica gravada no JSON da prova para que o rebuild preserve a galeria
+    if let Some(photos_dir) = &extras.fotos {
+        let prefixo = storage::base_relatorio(club, track,
+            data["event"]["slug"].as_str().unwrap_or("race"),
+            data["event"]["timestamp"].as_str().unwrap_or("000"));
+        let fotos = gallery::publicar_galeria(photos_dir, &prefixo).await?;
+        data["gallery"] = serde_json::to_value(&fotos)?;
+    }
+
+    // --- VÍDEOS (OPCIONAL: --video [bateria=]<url>) ---
+    let videos = videos::interpretar(&extras.videos);
+    if !videos.is_empty() {
+        data["videos"] = serde_json::to_value(&videos)?;
+    }
+
+    aliases::aplicar(&mut data, &aliases::carregar().await);
+    registry::anotar_categorias(&mut data);
+    entrants::anotar(&mut data);
+    overrides::aplicar(&mut data, ini_path).map_err(RacedayError::Entrada)?;
+    let (mut ranking, best_lap_overall) = montar_ranking(&data);
+
+    let mut historico = history::Historico::carregar();
+    aplicar_indice(&mut ranking, &historico, club, track, best_lap_overall);
+
+    // --- VALIDAÇÃO DE ELEGIBILIDADE (CADASTRO DE PILOTOS) ---
+    let nomes_reais: Vec<String> = ranking.iter().map(|p| p.nome.clone()).collect();
+    let provisorios = registry::validar_elegibilidade(&nomes_reais, data["event"]["timestamp"].as_str().unwrap_or(""));
+
+    // As voltas mais rápidas de cada piloto, com a fenda, para o top de voltas da pista
+    let fendas = crate::config::fendas_pista(crate::ranking::total_fendas(&data));
+    let voltas_historico = |p: &crate::ranking::PilotoDisplay| {
+        let mut voltas: Vec<history::VoltaHistorico> = p.tempos_por_fenda.iter()
+            .flat_map(|(fenda, tempos)| {
+                let nome = fenda.parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|i| fendas.get(i))
+                    .map(|f| f.nome.clone())
+                    .unwrap_or_else(|| format!("Fenda {}", fenda));
+                tempos.iter().map(move |t| history::VoltaHistorico { tempo: *t, fenda: nome.clone() })
+            })
+            .collect();
+        voltas.sort_by(|a, b| a.tempo.total_cmp(&b.tempo));
+        voltas.truncate(crate::top_laps::TOP_VOLTAS);
+        voltas
+    };
+
+    // O histórico local guarda os nomes reais; daqui em diante tudo é publicado
+    let resultados_historico: Vec<history::ResultadoHistorico> = ranking.iter().enumerate().map(|(i, p)| history::ResultadoHistorico {
+        nome: p.nome.clone(),
+        posicao: i + 1,
+        total_laps: p.total_laps,
+        best_lap: p.best_time.parse::<f64>().unwrap_or(0.0),
+        indice: p.performance_index,
+        penalidades: 0,
+        provisorio: provisorios.contains(&p.nome),
+        convidado: p.convidado,
+        status: p.status.clone(),
+        voltas: voltas_historico(p),
+    }).collect();
+    // Primeira vitória no clube: comparada pelo nome real, antes da anonimização
+    let primeira_vitoria = ranking.first().is_some_and(|v| insights::primeira_vitoria(&historico, club, &v.nome, data["event"]["timestamp"].as_str().unwrap_or("")));
+    data["first_win"] = Value::Bool(primeira_vitoria);
+    privacy::anonimizar_prova(&mut data);
+    privacy::anonimizar_ranking(&mut ranking);
+
+    // --- RECORDES DA PISTA ---
+    // Comparados com a prova já anonimizada, que é o que vai para o arquivo público
+    let recordes_batidos = records::detectar(&records::carregar(club, track).await?, &data);
+    for r in &recordes_batidos {
+        saida!("📈 Novo recorde {}: {} em {:.3}s", if r.fenda.is_empty() { "da pista".to_string() } else { format!("na {}", r.nome_fenda) }, r.piloto, r.tempo);
+    }
+    data["records"] = serde_json::to_value(&recordes_batidos)?;
+
+    // Cópia própria: o JSON ainda é carimbado (schema::carimbar) antes do envio
+    let ts = data["event"]["timestamp"].as_str().unwrap_or("000").to_string();
+    let ts = ts.as_str();
+
+    // --- SALVAMENTO E UPLOAD ---
+    let race_slug = data["event"]["slug"].as_str().unwrap_or("race").to_string();
+    let race_slug = race_slug.as_str();
+    let r2_key_json = storage::chave_prova(club, track, ts);
+    let r2_key_base = storage::base_relatorio(club, track, race_slug, ts);
+    let r2_key_html = format!("{}.html", r2_key_base);
+    let saida_dir = crate::pasta_saida();
+    let local_json_path = saida_dir.join("last_upload.json");
+    let local_html_path = saida_dir.join("last_upload.html");
+
+    // --- TERA TEMPLATES ---
+    let mut tera = console::etapa(2, "relatorio", async {
+        saida!("📄 [2/5] Gerando relatório HTML...");
+        assets::publicar().await?;
+        let mut tera = Tera::default();
+        assets::registrar(&mut tera);
+        crate::templates::carregar(&mut tera, "report.html", "report")?;
+        let html_output = report::renderizar_relatorio(&tera, &data, &ranking, best_lap_overall, club, track)?;
+        // Prévia do link nos chats: meta tags Open Graph / Twitter com o card do resultado
+        let html_output = og::injetar(&html_output, &og::meta_tags(&data, &ranking, club, track, &r2_key_html, &r2_key_base));
+
+        fs::create_dir_all(&saida_dir)?;
+        crate::schema::carimbar(&mut data);
+        fs::write(&local_json_path, serde_json::to_string_pretty(&data)?)?;
+        fs::write(&local_html_path, &html_output)?;
+
+        // --- ESCALA DE FISCAIS (OPCIONAL) ---
+        if let Some(curvas) = marshals::curvas_configuradas() {
+            let escala = marshals::gerar_escala(&data, &curvas);
+            marshals::imprimir_escala(&escala);
+
+            let local_marshals_path = saida_dir.join("last_marshals.html");
+            marshals::carregar_template(&mut tera)?;
+            fs::write(&local_marshals_path, marshals::renderizar(&tera, &data, club, track, &curvas, &escala)?)?;
+
+            let r2_key_marshals = format!("{}_fiscais.html", r2_key_base);
+            saida!("☁️ Enviando escala de fiscais para o R2: {}", r2_key_marshals);
+            storage::upload_se_mudou(&local_marshals_path, &r2_key_marshals).await?;
+        }
+        Ok::<_, RacedayError>(tera)
+    }).await?;
+
+    let resultado = |report_url: String, changed: bool| ResultadoProva {
+        report_url,
+        json_key: r2_key_json.clone(),
+        html_key: r2_key_html.clone(),
+        slug: race_slug.to_string(),
+        timestamp: ts.to_string(),
+        winner: ranking.first().map(|p| p.nome.clone()),
+        club: club.to_string(),
+        track: track.to_string(),
+        changed,
+    };
+
+    // Mesmo .INI processado de novo: nada a enviar nem a notificar (--force envia assim mesmo)
+    if storage::inalterado(&local_json_path, &r2_key_json).await? && storage::inalterado(&local_html_path, &r2_key_html).await? {
+        saida!("⏭️ Prova já publicada sem alterações: {} (use --force para publicar de novo)", r2_key_json);
+        return Ok(resultado(private_mode::link(&r2_key_html).await?, false));
+    }
+
+    console::etapa(3, "envio", async {
+        saida!("☁️ [3/5] Enviando JSON e HTML para o R2: {} | {}", r2_key_json, r2_key_html);
+        let inicio = Instant::now();
+        let (envio_json, envio_html) = tokio::join!(
+            storage::upload_to_r2(&local_json_path, &r2_key_json),
+            storage::upload_to_r2(&local_html_path, &r2_key_html),
+        );
+        let (tempo_json, tempo_html) = (envio_json?, envio_html?);
+        saida!("⏱️ JSON em {:.1}s, HTML em {:.1}s ({:.1}s no total)",
+            tempo_json.as_secs_f64(), tempo_html.as_secs_f64(), inicio.elapsed().as_secs_f64());
+
+        let slots_count = crate::ranking::total_fendas(&data);
+        let key_csv = csv_export::publicar(&ranking, slots_count, &r2_key_base).await?;
+        saida!("📊 Classificação em CSV: {}", key_csv);
+        let key_xlsx = xlsx_export::publicar(&data, &ranking, &r2_key_base).await?;
+        saida!("📊 Planilha Excel: {}", key_xlsx);
+
+        // Card do resultado para as redes sociais (logo, pista, pódio, melhor volta)
+        let key_card = card::publicar(&data, &ranking, club, track, &r2_key_base).await?;
+        saida!("📸 Card do resultado: {}", private_mode::link(&key_card).await?);
+
+        // --- VERSÃO PARA IMPRESSÃO (OPCIONAL: PRINT_CHART=svg|png) ---
+        let mut html_pdf = local_html_path.clone();
+        if let Some((key, local)) = print::publicar(&mut tera, &data, &ranking, best_lap_overall, club, track, &r2_key_base).await? {
+            saida!("🖨️ Versão para impressão: {}", key);
+            html_pdf = local;
+        }
+
+        // --- VISÕES EXTRAS (OPCIONAL: REPORT_VIEWS, ex.: painel da TV) ---
+        for key in views::publicar(&mut tera, &data, &ranking, best_lap_overall, club, track, &r2_key_base).await? {
+            saida!("📺 Visão extra: {}", key);
+        }
+
+        // --- PDF (OPCIONAL: --pdf ou PDF_EXPORT=1) ---
+        if extras.pdf || pdf::ativo() {
+            let key = pdf::publicar(&html_pdf, &r2_key_base).await?;
+            saida!("🖨️ PDF publicado: {}", key);
+        }
+
+        let provas = crate::manifest::registrar(&crate::manifest::EntradaManifesto {
+            club: club.to_string(),
+            track: track.to_string(),
+            slug: race_slug.to_string(),
+            timestamp: ts.to_string(),
+            title: data["event"]["title"].as_str().unwrap_or("Corrida").to_string(),
+            date: data["event"]["date"].as_str().unwrap_or("").to_string(),
+            json_key: r2_key_json.clone(),
+            html_key: r2_key_html.clone(),
+            winner: ranking.first().map(|p| p.nome.clone()),
+        }).await?;
+        saida!("📚 {} atualizado: {} prova(s)", crate::manifest::CHAVE, provas);
+
+        records::registrar(club, track, &data).await?;
+        saida!("📈 Recordes atualizados: {}", records::chave(club, track));
+
+        if rating::ativo() {
+            let ordem: Vec<(String, i64)> = ranking.iter().map(|p| (p.nome.clone(), p.total_laps)).collect();
+            match rating::atualizar(club, ts, &ordem).await? {
+                Some(ratings) => saida!("📊 Ratings atualizados: {} piloto(s) em {}", ratings.pilotos.len(), rating::chave(club)),
+                None => saida!("⏭️ Prova já contabilizada em {}", rating::chave(club)),
+            }
+        }
+
+        let relatorios = crate::site_index::publicar().await?;
+        saida!("🏠 index.html com {} relatório(s)", relatorios);
+        Ok::<_, RacedayError>(())
+    }).await?;
+
+    console::etapa(4, "api", async {
+        // Variante enxuta para o site do clube (api/races/...json e .json.gz)
+        saida!("☁️ [4/5] Publicando a API pública...");
+        let api = public_api::gerar(&data, &ranking, best_lap_overall);
+        public_api::publicar(&saida_dir, &public_api::chave(&r2_key_json), &api, false).await?;
+
+        historico.registrar(history::EventoHistorico {
+            timestamp: ts.to_string(),
+            club: club.to_string(),
+            track: track.to_string(),
+            titulo: data["event"]["title"].as_str().unwrap_or("Corrida").to_string(),
+            data: data["event"]["date"].as_str().unwrap_or("").to_string(),
+            slug: race_slug.to_string(),
+            report_link: data["report_link"].as_str().unwrap_or("").to_string(),
+            resultados: resultados_historico,
+        });
+        historico.salvar()?;
+        saida!("📚 Histórico atualizado: {}", history::caminho_historico().display());
+
+        // Páginas de piloto com a tendência do índice na temporada (ano do evento)
+        let publico = privacy::historico_publico(&historico);
+        let temporada = ts.get(0..4).unwrap_or("");
+        let nomes: Vec<String> = ranking.iter().map(|p| p.nome.clone()).collect();
+        let enviadas = pilots::publicar_paginas(&mut tera, &publico, club, &nomes, temporada, false).await?;
+        saida!("👤 {} página(s) de piloto atualizada(s)", enviadas);
+
+        if let Some(key) = top_laps::publicar(&mut tera, &publico, club, track).await? {
+            saida!("⏱️ Top {} voltas da pista: {}", top_laps::TOP_VOLTAS, key);
+        }
+
+        if let Some(projeto) = crate::pages::projeto() {
+            crate::pages::implantar(&projeto, data["event"]["title"].as_str().unwrap_or("Corrida")).await?;
+        }
+        Ok::<_, RacedayError>(())
+    }).await?;
+
+    let report_url = console::etapa(5, "pos_publicacao", async {
+        saida!("🔔 [5/5] Executando ações pós-publicação...");
+        let report_url = private_mode::link(&r2_key_html).await?;
+
+        // Resumo em Markdown para fóruns e grupos (pódio, tabela, melhor volta)
+        let key_md = markdown::publicar(&data, &ranking, club, track, &report_url, &r2_key_base).await?;
+        saida!("📝 Resumo em Markdown: {} ({})", key_md, saida_dir.join("last_upload.md").display());
+        share::salvar(&data, &ranking, club, &report_url)?;
+        if !crate::offline::ativo() {
+            let (local_qr, key_qr) = qr::publicar(&report_url, &r2_key_base).await?;
+            saida!("📸 QR code do relatório: {} ({})", key_qr, local_qr.display());
+        }
+
+        let publicacao = post_publish::Publicacao {
+            club: club.to_string(),
+            track: track.to_string(),
+            titulo: data["event"]["title"].as_str().unwrap_or("Corrida").to_string(),
+            urls: vec![report_url.clone(), private_mode::link(&r2_key_json).await?],
+            report_url: report_url.clone(),
+        };
+        let falhas = post_publish::executar(&publicacao).await?;
+        // A prova já foi publicada: a falha aqui vira código 6, que vale repetir
+        if falhas > 0 {
+            return Err(RacedayError::Notificacao(format!("{} ação(ões) pós-publicação falharam ({})", falhas, report_url)));
+        }
+        Ok::<_, RacedayError>(report_url)
+    }).await?;
+
+    Ok(resultado(report_url, true))
+}
@@ -0,0 +1,184 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::env;
+use std::error::Error;
+use std::fs;
+use tera::{Context, Tera};
+
+use crate::{hall_of_fame, parallel};
+use crate::history::{EventoHistorico, Historico, VoltaRanking};
+use crate::refresh::base_url;
+
+// --- PORTAL DO CLUBE (UMA PÁGINA POR PISTA + HOME) ---
+// Uso: raceday portal
+// Layout no bucket configurável por PORTAL_TRACK_KEY, PORTAL_HOME_KEY e PORTAL_HALL_KEY,
+// com os marcadores {club} e {track}.
+
+const ULTIMAS_PROVAS: usize = 5;
+const TOP_VOLTAS: usize = 10;
+
+#[derive(Serialize)]
+struct ResumoProva {
+    titulo: String,
+    data: String,
+    vencedor: String,
+    voltas: i64,
+    link: String,
+}
+
+#[derive(Serialize)]
+struct RecordesPista {
+    melhor_volta: f64,
+    melhor_volta_piloto: String,
+    melhor_volta_prova: String,
+    mais_voltas: i64,
+    mais_voltas_piloto: String,
+    mais_voltas_prova: String,
+}
+
+#[derive(Serialize)]
+struct PaginaPista {
+    track: String,
+    url: String,
+    total_provas: usize,
+    ultimas: Vec<ResumoProva>,
+    recordes: RecordesPista,
+    top_voltas: Vec<VoltaRanking>,
+    /// Página com as top_laps::TOP_VOLTAS voltas da pista
+    top_voltas_url: String,
+    proximas: Vec<String>,
+}
+
+fn slug(s: &str) -> String {
+    s.to_lowercase().replace(" ", "_")
+}
+
+pub(crate) fn chave_layout(var: &str, padrao: &str, club: &str, track: &str) -> String {
+    env::var(var).unwrap_or_else(|_| padrao.to_string())
+        .replace("{club}", &slug(club))
+        .replace("{track}", &slug(track))
+}
+
+/// Chave do Hall da Fama do clube (PORTAL_HALL_KEY).
+pub fn chave_hall(club: &str) -> String {
+    chave_layout("PORTAL_HALL_KEY", "portal/{club}/hall-of-fame.html", club, "")
+}
+
+fn recordes_pista(eventos: &[&EventoHistorico]) -> RecordesPista {
+    let mut rec = RecordesPista {
+        melhor_volta: 0.0,
+        melhor_volta_piloto: "---".into(),
+        melhor_volta_prova: "---".into(),
+        mais_voltas: 0,
+        mais_voltas_piloto: "---".into(),
+        mais_voltas_prova: "---".into(),
+    };
+    for e in eventos {
+        for r in &e.resultados {
+            if r.best_lap > 0.0 && (rec.melhor_volta == 0.0 || r.best_lap < rec.melhor_volta) {
+                rec.melhor_volta = r.best_lap;
+                rec.melhor_volta_piloto = r.nome.clone();
+                rec.melhor_volta_prova = e.titulo.clone();
+            }
+            if r.total_laps > rec.mais_voltas {
+                rec.mais_voltas = r.total_laps;
+                rec.mais_voltas_piloto = r.nome.clone();
+                rec.mais_voltas_prova = e.titulo.clone();
+            }
+        }
+    }
+    rec
+}
+
+fn montar_pagina(historico: &Historico, club: &str, track: &str, proximas: &[String]) -> PaginaPista {
+    let eventos: Vec<&EventoHistorico> = historico.eventos.iter()
+        .filter(|e| e.club == club && e.track == track)
+        .collect();
+    let ultimas = eventos.iter().rev().take(ULTIMAS_PROVAS).map(|e| ResumoProva {
+        titulo: e.titulo.clone(),
+        data: e.data.clone(),
+        vencedor: e.resultados.first().map(|r| r.nome.clone()).unwrap_or_else(|| "---".into()),
+        voltas: e.resultados.first().map(|r| r.total_laps).unwrap_or(0),
+        link: e.report_link.clone(),
+    }).collect();
+    let track_lower = track.to_lowercase();
+
+    PaginaPista {
+        track: track.to_string(),
+        url: format!("{}/{}", base_url(), chave_layout("PORTAL_TRACK_KEY", "portal/{club}/{track}.html", club, track)),
+        total_provas: eventos.len(),
+        ultimas,
+        recordes: recordes_pista(&eventos),
+        top_voltas: historico.top_voltas(club, track, TOP_VOLTAS),
+        top_voltas_url: format!("{}/{}", base_url(), crate::top_laps::chave(club, track)),
+        proximas: proximas.iter().filter(|p| p.to_lowercase().contains(&track_lower)).cloned().collect(),
+    }
+}
+
+pub async fn executar() -> Result<(), Box<dyn Error>> {
+    let historico = crate::privacy::historico_publico(&Historico::carregar());
+    let proximas: Vec<String> = env::var("NEXT_EVENTS").unwrap_or_default()
+        .split(';')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    crate::assets::publicar().await?;
+    let mut tera = Tera::default();
+    crate::assets::registrar(&mut tera);
+    crate::templates::carregar(&mut tera, "portal_track.html", "portal_track")?;
+    crate::templates::carregar(&mut tera, "portal_home.html", "portal_home")?;
+    crate::templates::carregar(&mut tera, "hall_of_fame.html", "hall_of_fame")?;
+    let local_dir = crate::pasta_saida().join("portal");
+    fs::create_dir_all(&local_dir)?;
+
+    let clubes: BTreeSet<&str> = historico.eventos.iter().map(|e| e.club.as_str()).collect();
+    if clubes.is_empty() {
+        saida!("ℹ️ Histórico vazio ({}). Nada a gerar.", crate::history::caminho_historico().display());
+        return Ok(());
+    }
+
+    for club in clubes {
+        let pistas: BTreeSet<&str> = historico.eventos.iter()
+            .filter(|e| e.club == club)
+            .map(|e| e.track.as_str())
+            .collect();
+
+        let pistas: Vec<&str> = pistas.into_iter().collect();
+        let paginas: Vec<PaginaPista> = parallel::mapear(&pistas, |track| montar_pagina(&historico, club, track, &proximas));
+        let renderizadas = parallel::mapear(&paginas, |pagina| {
+            let mut context = Context::new();
+            context.insert("club", club);
+            context.insert("pista", pagina);
+            tera.render("portal_track", &context)
+        });
+        for (pagina, html) in paginas.iter().zip(renderizadas) {
+            let local = local_dir.join(format!("{}_{}.html", slug(club), slug(&pagina.track)));
+            fs::write(&local, html?)?;
+            crate::storage::upload_to_r2(&local, &chave_layout("PORTAL_TRACK_KEY", "portal/{club}/{track}.html", club, &pagina.track)).await?;
+        }
+
+        // Hall da Fama do clube, com os campeões das temporadas publicadas no bucket
+        let hall_key = chave_hall(club);
+        let mut context = Context::new();
+        context.insert("club", club);
+        context.insert("hall", &hall_of_fame::montar(&historico, club, crate::season::campeoes(club).await?));
+        let local = local_dir.join(format!("{}_hall_of_fame.html", slug(club)));
+        fs::write(&local, tera.render("hall_of_fame", &context)?)?;
+        crate::storage::upload_to_r2(&local, &hall_key).await?;
+
+        let mut context = Context::new();
+        context.insert("club", club);
+        context.insert("pistas", &paginas);
+        context.insert("hall_url", &format!("{}/{}", base_url(), hall_key));
+        let local = local_dir.join(format!("{}_index.html", slug(club)));
+        fs::write(&local, tera.render("portal_home", &context)?)?;
+        crate::storage::upload_to_r2(&local, &chave_layout("PORTAL_HOME_KEY", "portal/{club}/index.html", club, "")).await?;
+
+        saida!("🏠 Portal de {} publicado com {} pista(s)", club, paginas.len());
+    }
+
+    // O index.html passa a apontar para o Hall da Fama recém-publicado
+    crate::site_index::publicar().await?;
+    Ok(())
+}
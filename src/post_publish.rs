@@ -0,0 +1,187 @@
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::time::Duration;
+use tokio::process::Command;
+
+// --- PIPELINE PÓS-PUBLICAÇÃO ---
+// Ações executadas em ordem depois do upload (deploy hooks, purge de cache,
+// avisos, comando próprio). Configuradas em um JSON apontado por POST_PUBLISH_PIPELINE:
+// [
+//   {"name": "render", "kind": "deploy_hook", "url": "https://api.render.com/deploy/..."},
+//   {"name": "cdn", "kind": "cache_purge", "url": "https://api.cloudflare.com/client/v4/zones/<id>/purge_cache", "token_env": "CF_TOKEN"},
+//   {"name": "discord", "kind": "notify", "url": "https://discord.com/api/webhooks/...", "message": "🏁 {title} publicado: {report}"},
+//   {"name": "backup", "kind": "shell", "command": "rsync -a temp_out/ nas:/raceday", "enabled": false, "retries": 0}
+// ]
+// Sem o arquivo, o pipeline é só o sync do Render (RENDER_SYNC_URL), como antes.
+
+const TENTATIVAS_PADRAO: u32 = 2;
+const TIMEOUT_PADRAO_SECS: u64 = 60;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Tipo {
+    DeployHook,
+    CachePurge,
+    Notify,
+    Shell,
+}
+
+#[derive(Deserialize)]
+struct Passo {
+    name: String,
+    kind: Tipo,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+    /// Variável de ambiente com o token (Bearer), para não gravar segredos no JSON
+    #[serde(default)]
+    token_env: Option<String>,
+    #[serde(default = "ativo_padrao")]
+    enabled: bool,
+    #[serde(default = "tentativas_padrao")]
+    retries: u32,
+    #[serde(default = "timeout_padrao")]
+    timeout_secs: u64,
+}
+
+fn ativo_padrao() -> bool { true }
+fn tentativas_padrao() -> u32 { TENTATIVAS_PADRAO }
+fn timeout_padrao() -> u64 { TIMEOUT_PADRAO_SECS }
+
+/// O que acabou de ser publicado, disponível para os passos.
+pub struct Publicacao {
+    pub club: String,
+    pub track: String,
+    pub titulo: String,
+    pub report_url: String,
+    /// URLs públicas alteradas nesta execução (usadas no purge de cache)
+    pub urls: Vec<String>,
+}
+
+enum Resultado {
+    Ok(u32),
+    Falhou(String),
+    Ignorado,
+}
+
+fn carregar_passos() -> Result<Vec<Passo>, Box<dyn Error>> {
+    if let Ok(caminho) = env::var("POST_PUBLISH_PIPELINE") {
+        let conteudo = fs::read_to_string(&caminho)
+            .map_err(|e| format!("❌ Não foi possível ler POST_PUBLISH_PIPELINE ({}): {}", caminho, e))?;
+        return Ok(serde_json::from_str(&conteudo)
+            .map_err(|e| format!("❌ POST_PUBLISH_PIPELINE inválido ({}): {}", caminho, e))?);
+    }
+    Ok(env::var("RENDER_SYNC_URL").ok().map(|url| Passo {
+        name: "render".to_string(),
+        kind: Tipo::DeployHook,
+        url: Some(url),
+        command: None,
+        message: None,
+        token_env: None,
+        enabled: true,
+        retries: TENTATIVAS_PADRAO,
+        timeout_secs: TIMEOUT_PADRAO_SECS,
+    }).into_iter().collect())
+}
+
+fn preencher(modelo: &str, p: &Publicacao) -> String {
+    modelo.replace("{club}", &p.club)
+        .replace("{track}", &p.track)
+        .replace("{title}", &p.titulo)
+        .replace("{report}", &p.report_url)
+}
+
+async fn executar_passo(client: &reqwest::Client, passo: &Passo, p: &Publicacao) -> Result<(), Box<dyn Error>> {
+    let timeout = Duration::from_secs(passo.timeout_secs);
+    let url = || passo.url.as_deref().ok_or_else(|| format!("passo '{}' sem \"url\"", passo.name));
+
+    let requisicao = match passo.kind {
+        Tipo::DeployHook => client.post(url()?),
+        Tipo::CachePurge => client.post(url()?).json(&serde_json::json!({ "files": p.urls })),
+        Tipo::Notify => {
+            let mensagem = preencher(passo.message.as_deref().unwrap_or("🏁 {title} publicado: {report}"), p);
+            // "content" é o campo lido pelos webhooks do Discord
+            client.post(url()?).json(&serde_json::json!({ "content": mensagem, "club": p.club, "report": p.report_url }))
+        }
+        Tipo::Shell => {
+            let linha = passo.command.as_deref().ok_or_else(|| format!("passo '{}' sem \"command\"", passo.name))?;
+            let mut comando = if cfg!(target_os = "windows") {
+                let mut c = Command::new("cmd");
+                c.arg("/C").arg(linha);
+                c
+            } else {
+                let mut c = Command::new("sh");
+                c.arg("-c").arg(linha);
+                c
+            };
+            comando.env("RACEDAY_CLUB", &p.club)
+                .env("RACEDAY_TRACK", &p.track)
+                .env("RACEDAY_TITLE", &p.titulo)
+                .env("RACEDAY_REPORT_URL", &p.report_url)
+                .kill_on_drop(true);
+            let status = tokio::time::timeout(timeout, comando.status()).await
+                .map_err(|_| format!("tempo limite de {}s excedido", passo.timeout_secs))??;
+            if !status.success() {
+                return Err(format!("comando terminou com {}", status).into());
+            }
+            return Ok(());
+        }
+    };
+
+    let requisicao = match passo.token_env.as_deref().and_then(|v| env::var(v).ok()) {
+        Some(token) => requisicao.bearer_auth(token),
+        None => requisicao,
+    };
+    requisicao.timeout(timeout).send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// Executa todos os passos em ordem; uma falha não interrompe os seguintes.
+/// Retorna o número de passos que falharam.
+pub async fn executar(publicacao: &Publicacao) -> Result<usize, Box<dyn Error>> {
+    let passos = carregar_passos()?;
+    if passos.is_empty() { return Ok(0); }
+    if crate::offline::ativo() {
+        saida!("📴 Modo offline: {} passo(s) de pós-publicação ignorado(s)", passos.len());
+        return Ok(0);
+    }
+
+    let client = reqwest::Client::new();
+    let mut resumo = Vec::new();
+    for passo in &passos {
+        if !passo.enabled {
+            resumo.push((passo.name.as_str(), Resultado::Ignorado));
+            continue;
+        }
+        let mut tentativa = 0;
+        let resultado = loop {
+            tentativa += 1;
+            match executar_passo(&client, passo, publicacao).await {
+                Ok(()) => break Resultado::Ok(tentativa),
+                Err(e) if tentativa > passo.retries => break Resultado::Falhou(e.to_string()),
+                Err(e) => {
+                    erro!("⚠️ Passo '{}' falhou (tentativa {}): {}", passo.name, tentativa, e);
+                    tokio::time::sleep(Duration::from_secs(2u64.pow(tentativa))).await;
+                }
+            }
+        };
+        resumo.push((passo.name.as_str(), resultado));
+    }
+
+    saida!("📋 Pós-publicação:");
+    let mut falhas = 0;
+    for (nome, resultado) in &resumo {
+        match resultado {
+            Resultado::Ok(1) => saida!("   ✅ {}", nome),
+            Resultado::Ok(n) => saida!("   ✅ {} ({} tentativas)", nome, n),
+            Resultado::Falhou(e) => { falhas += 1; saida!("   ❌ {}: {}", nome, e) }
+            Resultado::Ignorado => saida!("   ⏭️ {} (desativado)", nome),
+        }
+    }
+    Ok(falhas)
+}
@@ -0,0 +1,118 @@
+use serde_json::Value;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::error::RacedayError;
+
+// --- PRÉ-PROCESSAMENTO (INI -> JSON) ---
+// Por padrão o .INI é lido pelo parser nativo (ini.rs). Um preparador externo
+// continua disponível, configurável por variáveis de ambiente, sem recompilar:
+//   PREP_COMMAND      comando (ex: `py -3 scripts\raceday-prep.py`); ativa o preparador externo
+//   PREP_ARGS         modelo dos argumentos; {input}, {club} e {track} são substituídos
+//   PREP_WORKDIR      pasta de trabalho do processo (padrão: a atual)
+//   PREP_TIMEOUT_SECS tempo máximo de execução (padrão: 120)
+// O preparador deve escrever o JSON da prova na saída padrão.
+
+const ARGS_PADRAO: &str = "--input {input} --club {club} --track {track}";
+const TIMEOUT_PADRAO_SECS: u64 = 120;
+
+struct Preparador {
+    programa: PathBuf,
+    args_fixos: Vec<String>,
+    modelo_args: String,
+    pasta_trabalho: Option<PathBuf>,
+    timeout: Duration,
+}
+
+/// Divide uma linha de comando respeitando aspas: "C:\Program Files\Python\python.exe" script.py
+pub fn dividir_comando(linha: &str) -> Vec<String> {
+    let mut partes = Vec::new();
+    let mut atual = String::new();
+    let mut entre_aspas = false;
+    for c in linha.chars() {
+        match c {
+            '"' => entre_aspas = !entre_aspas,
+            c if c.is_whitespace() && !entre_aspas => {
+                if !atual.is_empty() { partes.push(std::mem::take(&mut atual)); }
+            }
+            c => atual.push(c),
+        }
+    }
+    if !atual.is_empty() { partes.push(atual); }
+    partes
+}
+
+fn configuracao(comando: &str) -> Preparador {
+    let partes = dividir_comando(comando);
+    Preparador {
+        programa: PathBuf::from(&partes[0]),
+        args_fixos: partes[1..].to_vec(),
+        modelo_args: env::var("PREP_ARGS").unwrap_or_else(|_| ARGS_PADRAO.to_string()),
+        pasta_trabalho: env::var_os("PREP_WORKDIR").map(PathBuf::from),
+        timeout: Duration::from_secs(env::var("PREP_TIMEOUT_SECS").ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(TIMEOUT_PADRAO_SECS)),
+    }
+}
+
+/// Substitui os marcadores em cada argumento já separado, para que caminhos
+/// com espaço (comuns no Windows) continuem sendo um único argumento.
+fn montar_args(modelo: &str, input: &Path, club: &str, track: &str) -> Vec<String> {
+    let input = input.to_string_lossy();
+    dividir_comando(modelo).into_iter()
+        .map(|a| a.replace("{input}", &input).replace("{club}", club).replace("{track}", track))
+        .collect()
+}
+
+/// JSON da prova, com a estrutura (schema.rs) e as fendas (ranking::validar_fendas) conferidas.
+pub async fn executar(input: &Path, club: &str, track: &str) -> Result<Value, RacedayError> {
+    let data = preparar(input, club, track).await?;
+    crate::schema::validar(&data).map_err(RacedayError::Leitura)?;
+    crate::ranking::validar_fendas(&data).map_err(RacedayError::Leitura)?;
+    Ok(data)
+}
+
+async fn preparar(input: &Path, club: &str, track: &str) -> Result<Value, RacedayError> {
+    let Some(comando) = env::var("PREP_COMMAND").ok().filter(|c| !dividir_comando(c).is_empty()) else {
+        saida!("🚀 [1/5] Lendo {} (parser nativo)", input.display());
+        return crate::ini::converter(input, club, track);
+    };
+    let prep = configuracao(&comando);
+
+    // Com PREP_WORKDIR o processo roda em outra pasta, então o .ini vai como caminho absoluto
+    let input = if prep.pasta_trabalho.is_some() {
+        std::path::absolute(input).unwrap_or_else(|_| input.to_path_buf())
+    } else {
+        input.to_path_buf()
+    };
+
+    saida!("🚀 [1/5] Iniciando preparador externo ({})", prep.programa.display());
+    let args = montar_args(&prep.modelo_args, &input, club, track);
+    detalhe!("🚀 {} {} {}", prep.programa.display(), prep.args_fixos.join(" "), args.join(" "));
+    let mut comando = Command::new(&prep.programa);
+    comando.args(&prep.args_fixos)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(dir) = &prep.pasta_trabalho {
+        comando.current_dir(dir);
+    }
+
+    let processo = comando.spawn()
+        .map_err(|e| RacedayError::Preparo(format!("❌ Não foi possível iniciar o preparador '{}': {}", prep.programa.display(), e)))?;
+    let output = match tokio::time::timeout(prep.timeout, processo.wait_with_output()).await {
+        Ok(resultado) => resultado.map_err(|e| RacedayError::Preparo(format!("⚠️ Erro no preparador: {}", e)))?,
+        Err(_) => return Err(RacedayError::Preparo(format!("⚠️ Preparador excedeu o tempo limite de {}s (PREP_TIMEOUT_SECS)", prep.timeout.as_secs()))),
+    };
+
+    if !output.status.success() {
+        return Err(RacedayError::Preparo(format!("⚠️ Erro no preparador: {}", String::from_utf8_lossy(&output.stderr))));
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| RacedayError::Preparo(format!("⚠️ O preparador não devolveu um JSON válido: {}", e)))
+}
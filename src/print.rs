@@ -0,0 +1,126 @@
+use plotters::prelude::*;
+use serde_json::Value;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tera::Tera;
+
+use crate::ranking::PilotoDisplay;
+
+// --- VERSÃO PARA IMPRESSÃO ---
+// O Chart.js não aparece no papel. Com PRINT_CHART=svg (ou png) o gráfico de
+// voltas por fenda é desenhado no servidor (plotters), enviado ao lado do
+// relatório como <base>_grafico.svg/.png e referenciado por um HTML próprio
+// para impressão (templates/report_print.html), publicado em <base>_impressao.html.
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Formato {
+    Svg,
+    Png,
+}
+
+impl Formato {
+    fn extensao(self) -> &'static str {
+        match self {
+            Formato::Svg => "svg",
+            Formato::Png => "png",
+        }
+    }
+}
+
+const TAMANHO: (u32, u32) = (1000, 500);
+
+/// PRINT_CHART: "svg" ou "png"; qualquer outro valor (ou ausente) desliga.
+pub fn formato() -> Option<Formato> {
+    match env::var("PRINT_CHART").ok()?.trim().to_lowercase().as_str() {
+        "svg" | "1" | "true" => Some(Formato::Svg),
+        "png" => Some(Formato::Png),
+        outro => {
+            erro!("⚠️ PRINT_CHART='{}' desconhecido (use svg ou png)", outro);
+            None
+        }
+    }
+}
+
+/// "#36A2EB" -> RGBColor; cinza se não for uma cor hexadecimal.
+fn cor(hex: &str) -> RGBColor {
+    let canal = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+    match (canal(1), canal(3), canal(5)) {
+        (Some(r), Some(g), Some(b)) if hex.starts_with('#') => RGBColor(r, g, b),
+        _ => RGBColor(204, 204, 204),
+    }
+}
+
+fn desenhar<DB: DrawingBackend>(area: DrawingArea<DB, plotters::coord::Shift>, ranking: &[PilotoDisplay], slots_count: usize) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let nomes: Vec<String> = crate::config::fendas_pista(slots_count).into_iter().take(slots_count).map(|f| f.nome).collect();
+    let series: Vec<Vec<i64>> = ranking.iter()
+        .map(|p| (1..=slots_count).map(|s| p.laps_per_slot.get(&s.to_string()).and_then(|v| v.parse().ok()).unwrap_or(0)).collect())
+        .collect();
+    let max_voltas = series.iter().flatten().copied().max().unwrap_or(0).max(1);
+    let cores = crate::config::cores_grafico();
+
+    area.fill(&WHITE)?;
+    let mut grafico = ChartBuilder::on(&area)
+        .caption(crate::i18n::t("report.laps_per_slot"), ("sans-serif", 22))
+        .margin(15)
+        .x_label_area_size(35)
+        .y_label_area_size(45)
+        .build_cartesian_2d(0usize..slots_count.saturating_sub(1).max(1), 0i64..max_voltas + max_voltas / 10 + 1)?;
+    grafico.configure_mesh()
+        .x_labels(slots_count)
+        .x_label_formatter(&|i| nomes.get(*i).cloned().unwrap_or_default())
+        .y_desc(crate::i18n::t("report.laps"))
+        .light_line_style(WHITE)
+        .draw()?;
+
+    for (idx, (piloto, voltas)) in ranking.iter().zip(&series).enumerate() {
+        let c = cor(crate::report::cor_grafico(&cores, idx));
+        grafico.draw_series(LineSeries::new(voltas.iter().enumerate().map(|(i, v)| (i, *v)), c.stroke_width(2)))?
+            .label(piloto.nome.clone())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 15, y)], c.stroke_width(2)));
+    }
+    grafico.configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .position(SeriesLabelPosition::LowerRight)
+        .draw()?;
+    area.present()?;
+    Ok(())
+}
+
+/// Desenha o gráfico de voltas por fenda no arquivo `destino`.
+pub fn renderizar_grafico(ranking: &[PilotoDisplay], slots_count: usize, destino: &Path, formato: Formato) -> Result<(), Box<dyn Error>> {
+    match formato {
+        Formato::Svg => desenhar(SVGBackend::new(destino, TAMANHO).into_drawing_area(), ranking, slots_count),
+        Formato::Png => desenhar(BitMapBackend::new(destino, TAMANHO).into_drawing_area(), ranking, slots_count),
+    }
+}
+
+/// Gera e envia o gráfico e a página de impressão. Retorna a chave e o arquivo local
+/// da página, ou `None` se PRINT_CHART não estiver definida.
+pub async fn publicar(tera: &mut Tera, data: &Value, ranking: &[PilotoDisplay], best_lap_overall: f64, club: &str, track: &str, r2_key_base: &str) -> Result<Option<(String, PathBuf)>, Box<dyn Error>> {
+    let Some(formato) = formato() else { return Ok(None) };
+    let saida_dir = crate::pasta_saida();
+    let slots_count = crate::ranking::total_fendas(data);
+
+    // Relatório e gráfico ficam na mesma pasta (reports/): o link é relativo, e o
+    // arquivo local tem o mesmo nome para a página funcionar também em disco (PDF)
+    let key_grafico = format!("{}_grafico.{}", r2_key_base, formato.extensao());
+    let nome_grafico = key_grafico.rsplit('/').next().unwrap_or(&key_grafico);
+    let local_grafico = saida_dir.join(nome_grafico);
+    renderizar_grafico(ranking, slots_count, &local_grafico, formato)?;
+    crate::storage::upload_to_r2(&local_grafico, &key_grafico).await?;
+
+    crate::templates::carregar(tera, "report_print.html", "report_print")?;
+    let mut context = crate::report::contexto_relatorio(data, ranking, best_lap_overall, club, track);
+    context.insert("grafico", nome_grafico);
+    let local_html = saida_dir.join("last_impressao.html");
+    fs::write(&local_html, tera.render("report_print", &context)?)?;
+    let key_html = format!("{}_impressao.html", r2_key_base);
+    crate::storage::upload_to_r2(&local_html, &key_html).await?;
+    Ok(Some((key_html, local_html)))
+}
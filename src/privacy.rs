@@ -0,0 +1,114 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::sync::OnceLock;
+
+use crate::error::RacedayError;
+use crate::history::Historico;
+use crate::ranking::PilotoDisplay;
+
+// --- PRIVACIDADE DOS PILOTOS (APELIDO / INICIAIS / EXCLUSÃO) ---
+// Preferências em um JSON apontado por PILOT_PRIVACY, pelo nome completo:
+// {
+//   "João Côrtes": "initials",             -> "J. C."
+//   "Maria Souza": {"nickname": "Flash"},  -> "Flash"
+//   "Pedro Lima": "exclude"                -> some das páginas públicas
+// }
+// O histórico local guarda sempre o nome real; a troca acontece em tudo que é
+// publicado (relatório, JSONs, feed, páginas de piloto, portal, e-mail, gráficos).
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Preferencia {
+    Modo(String),
+    Apelido { nickname: String },
+}
+
+fn lidas() -> &'static Result<HashMap<String, Preferencia>, String> {
+    static PREFERENCIAS: OnceLock<Result<HashMap<String, Preferencia>, String>> = OnceLock::new();
+    PREFERENCIAS.get_or_init(|| {
+        let Ok(caminho) = env::var("PILOT_PRIVACY") else { return Ok(HashMap::new()) };
+        fs::read_to_string(&caminho).map_err(|e| e.to_string())
+            .and_then(|s| serde_json::from_str(&s).map_err(|e| e.to_string()))
+            .map_err(|e| format!("❌ PILOT_PRIVACY inválido ({}): {}", caminho, e))
+    })
+}
+
+/// Lê e confere PILOT_PRIVACY. Sem as preferências não dá para publicar com
+/// segurança, então main e pipeline::processar chamam antes de qualquer publicação.
+pub fn carregar() -> Result<(), RacedayError> {
+    lidas().as_ref().map(|_| ()).map_err(|e| RacedayError::Entrada(e.clone()))
+}
+
+fn preferencias() -> &'static HashMap<String, Preferencia> {
+    static VAZIO: OnceLock<HashMap<String, Preferencia>> = OnceLock::new();
+    // Arquivo inválido já foi recusado em carregar()
+    lidas().as_ref().unwrap_or_else(|_| VAZIO.get_or_init(HashMap::new))
+}
+
+fn iniciais(nome: &str) -> String {
+    nome.split_whitespace()
+        .filter_map(|p| p.chars().next())
+        .map(|c| format!("{}.", c.to_uppercase()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Nome a exibir publicamente. `None` quando o piloto pediu para não aparecer.
+pub fn nome_publico(nome: &str) -> Option<String> {
+    match preferencias().get(nome.trim()) {
+        None => Some(nome.to_string()),
+        Some(Preferencia::Apelido { nickname }) => Some(nickname.clone()),
+        Some(Preferencia::Modo(modo)) => match modo.as_str() {
+            "initials" => Some(iniciais(nome)),
+            "exclude" => None,
+            _ => Some(nome.to_string()),
+        },
+    }
+}
+
+/// Troca os campos "name" do JSON da prova e remove os objetos de pilotos excluídos
+/// (ranking oficial, cadastro de pilotos e fendas das sessões).
+pub fn anonimizar_prova(data: &mut Value) {
+    if preferencias().is_empty() { return; }
+    fn limpar(v: &mut Value) -> bool {
+        match v {
+            Value::Object(mapa) => {
+                if let Some(Value::String(nome)) = mapa.get("name") {
+                    match nome_publico(nome) {
+                        Some(publico) => { mapa.insert("name".to_string(), Value::String(publico)); }
+                        None => return true,
+                    }
+                }
+                mapa.retain(|_, filho| !limpar(filho));
+            }
+            Value::Array(itens) => itens.retain_mut(|filho| !limpar(filho)),
+            _ => {}
+        }
+        false
+    }
+    limpar(data);
+}
+
+pub fn anonimizar_ranking(ranking: &mut Vec<PilotoDisplay>) {
+    if preferencias().is_empty() { return; }
+    ranking.retain_mut(|p| match nome_publico(&p.nome) {
+        Some(publico) => { p.nome = publico; true }
+        None => false,
+    });
+}
+
+/// Cópia do histórico pronta para publicação (portal, feed, páginas de piloto, e-mail).
+pub fn historico_publico(historico: &Historico) -> Historico {
+    let mut publico = Historico { eventos: historico.eventos.clone() };
+    if preferencias().is_empty() { return publico; }
+    for e in publico.eventos.iter_mut() {
+        e.resultados.retain_mut(|r| match nome_publico(&r.nome) {
+            Some(nome) => { r.nome = nome; true }
+            None => false,
+        });
+    }
+    publico
+}
@@ -0,0 +1,57 @@
+use aws_sdk_s3::presigning::PresigningConfig;
+use std::env;
+use std::error::Error;
+use std::time::Duration;
+
+use crate::error::RacedayError;
+
+// --- MODO PRIVADO (RESULTADOS PROVISÓRIOS) ---
+// Com RACEDAY_PRIVATE=1 tudo vai para R2_PRIVATE_BUCKET (sem acesso público) e os
+// links do índice e das notificações são URLs assinadas que expiram após
+// SIGNED_URL_TTL_HOURS (padrão 72h, máximo 7 dias pela assinatura SigV4).
+// Rodar o refresh-index diariamente renova os links.
+
+const TTL_PADRAO_HORAS: u64 = 72;
+const TTL_MAXIMO_HORAS: u64 = 7 * 24;
+
+pub fn ativo() -> bool {
+    env::var("RACEDAY_PRIVATE").map(|v| v == "1" || v.eq_ignore_ascii_case("true")).unwrap_or(false)
+}
+
+/// Bucket de destino: o privado no modo privado, senão o R2_BUCKET de sempre.
+pub fn bucket() -> Result<String, RacedayError> {
+    if ativo() {
+        return env::var("R2_PRIVATE_BUCKET").map_err(|_| RacedayError::Entrada(
+            "❌ R2_PRIVATE_BUCKET não definida (obrigatória com RACEDAY_PRIVATE=1)".to_string()));
+    }
+    Ok(env::var("R2_BUCKET").unwrap_or_else(|_| "raceday-data".to_string()))
+}
+
+fn validade() -> Duration {
+    let horas = env::var("SIGNED_URL_TTL_HOURS").ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|h| *h > 0)
+        .unwrap_or(TTL_PADRAO_HORAS)
+        .min(TTL_MAXIMO_HORAS);
+    Duration::from_secs(horas * 3600)
+}
+
+/// Link para compartilhar um artefato: URL pública ou, no modo privado, assinada.
+pub async fn link(key: &str) -> Result<String, Box<dyn Error>> {
+    if crate::offline::ativo() {
+        return Ok(crate::offline::caminho(key).display().to_string());
+    }
+    if !ativo() {
+        return Ok(format!("{}/{}", crate::refresh::base_url(), key));
+    }
+    if !crate::backends::usa_s3() {
+        return Err("❌ O modo privado exige o backend S3/R2 (URLs assinadas)".into());
+    }
+    let (client, bucket) = crate::storage::cliente_r2().await?;
+    let assinada = client.get_object()
+        .bucket(bucket)
+        .key(key)
+        .presigned(PresigningConfig::expires_in(validade())?)
+        .await?;
+    Ok(assinada.uri().to_string())
+}
@@ -0,0 +1,74 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use crate::ranking::PilotoDisplay;
+
+// --- API PÚBLICA (JSON ENXUTO + GZIP) ---
+// O JSON bruto da prova (races/...) traz as voltas, sessões e tudo que o
+// preparador extrai, e pode passar de alguns MB. Para o site do clube publicamos
+// só metadados + classificação em api/races/<clube>_<pista>_<ts>.json, mais
+// uma cópia .json.gz.
+
+pub fn gerar(data: &Value, ranking: &[PilotoDisplay], best_lap_overall: f64) -> Value {
+    json!({
+        "club": data["club"],
+        "track": data["track"],
+        "event": {
+            "title": data["event"]["title"],
+            "date": data["event"]["date"],
+            "timestamp": data["event"]["timestamp"],
+            "slug": data["event"]["slug"],
+        },
+        "report_link": data["report_link"],
+        "best_lap": best_lap_overall,
+        "ranking": ranking.iter().enumerate().map(|(i, p)| json!({
+            "position": i + 1,
+            "name": p.nome,
+            "class": p.classe,
+            "laps": p.total_laps,
+            "gap": p.gap,
+            "best_lap": p.best_time,
+            "laps_per_heat": p.laps_per_heat,
+            "average_lap": p.average_lap_time,
+            "penalties": p.penalties,
+            "deslots": p.deslots,
+            "performance_index": p.performance_index,
+            "extra": p.extra,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// Chave da variante pública a partir do nome do JSON bruto (races/x.json -> api/races/x.json).
+pub fn chave(r2_key_json: &str) -> String {
+    format!("api/{}", r2_key_json)
+}
+
+/// Grava e publica a variante pública e a cópia gzip. Retorna quantos arquivos foram enviados.
+pub async fn publicar(local_dir: &Path, key: &str, api: &Value, somente_alterados: bool) -> Result<usize, Box<dyn Error>> {
+    let nome = key.rsplit('/').next().unwrap_or(key);
+    let json = serde_json::to_vec(api)?;
+
+    let local_json = local_dir.join(nome);
+    fs::write(&local_json, &json)?;
+
+    let mut gz = GzEncoder::new(Vec::new(), Compression::best());
+    gz.write_all(&json)?;
+    let local_gz = local_dir.join(format!("{}.gz", nome));
+    fs::write(&local_gz, gz.finish()?)?;
+
+    let mut enviados = 0;
+    for (local, key) in [(local_json, key.to_string()), (local_gz, format!("{}.gz", key))] {
+        if somente_alterados {
+            if crate::storage::upload_se_mudou(&local, &key).await? { enviados += 1; }
+        } else {
+            crate::storage::upload_to_r2(&local, &key).await?;
+            enviados += 1;
+        }
+    }
+    Ok(enviados)
+}
@@ -0,0 +1,39 @@
+use image::{GrayImage, Luma};
+use qrcode::{Color, QrCode};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+// --- QR CODE DO RELATÓRIO ---
+// Depois do envio, o endereço público do relatório vira um QR code em PNG
+// (<pasta de saída>/qr.png e <base>_qr.png no bucket), para a organização
+// colocar na TV do clube e os pilotos abrirem o resultado direto no celular.
+// No modo offline não há endereço público e o QR code não é gerado.
+
+/// Pixels por módulo do QR code e margem (em módulos) exigida pelos leitores.
+const ESCALA: u32 = 12;
+const MARGEM: u32 = 4;
+
+pub fn gerar(url: &str, destino: &Path) -> Result<(), Box<dyn Error>> {
+    let codigo = QrCode::new(url.as_bytes())?;
+    let modulos = codigo.width() as u32;
+    let cores = codigo.to_colors();
+    let lado = (modulos + 2 * MARGEM) * ESCALA;
+
+    let imagem = GrayImage::from_fn(lado, lado, |x, y| {
+        let (mx, my) = (x / ESCALA, y / ESCALA);
+        let escuro = mx >= MARGEM && my >= MARGEM && mx < modulos + MARGEM && my < modulos + MARGEM
+            && cores[((my - MARGEM) * modulos + (mx - MARGEM)) as usize] == Color::Dark;
+        Luma([if escuro { 0 } else { 255 }])
+    });
+    imagem.save(destino)?;
+    Ok(())
+}
+
+/// Gera o QR code de `url` e publica em <base>_qr.png. Retorna o arquivo local e a chave.
+pub async fn publicar(url: &str, r2_key_base: &str) -> Result<(PathBuf, String), Box<dyn Error>> {
+    let local = crate::pasta_saida().join("qr.png");
+    gerar(url, &local)?;
+    let key = format!("{}_qr.png", r2_key_base);
+    crate::storage::upload_to_r2(&local, &key).await?;
+    Ok((local, key))
+}
@@ -0,0 +1,487 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::history::{calcular_indice, Historico};
+
+// --- PROCESSAMENTO DO RANKING ---
+
+/// Linha da classificação de uma prova, como aparece no relatório.
+#[derive(Serialize, Deserialize, Default)]
+pub struct PilotoDisplay {
+    pub nome: String,
+    pub total_laps: i64,
+    pub penalties: i64,
+    pub deslots: i64,
+    pub zona: String,
+    pub gap: String,
+    pub sessions: i64,
+    pub best_time: String,
+    /// Nome legado de `laps_per_heat` (voltas por bateria, não é tempo de volta)
+    pub average_time: String,
+    /// Média de voltas por bateria disputada ("12,5")
+    #[serde(default)]
+    pub laps_per_heat: String,
+    /// Tempo médio de volta em segundos, dos tempos volta a volta ("---" sem eles)
+    #[serde(default)]
+    pub average_lap_time: String,
+    /// Tempo médio de volta em cada fenda, mesmo formato
+    #[serde(default)]
+    pub average_lap_per_slot: HashMap<String, String>,
+    pub is_overall_best: bool,
+    pub best_slot_name: String,
+    /// Cor da melhor fenda (config::fendas), vazia para usar o CSS do template
+    #[serde(default)]
+    pub best_slot_color: String,
+    pub laps_per_slot: HashMap<String, String>,
+    pub times_per_slot: HashMap<String, String>,
+    pub lap_times: Vec<f64>,
+    /// Tempos volta a volta em cada fenda (número da fenda), para o top de voltas do histórico
+    #[serde(skip)]
+    pub tempos_por_fenda: HashMap<String, Vec<f64>>,
+    pub performance_index: f64,
+    /// Valores crus para os filtros dos templates (filters.rs): melhor volta e
+    /// volta média em segundos (0 sem tempo), gap e média de voltas por bateria
+    #[serde(default)]
+    pub best_lap_secs: f64,
+    #[serde(default)]
+    pub average_lap_secs: f64,
+    #[serde(default)]
+    pub gap_laps: f64,
+    #[serde(default)]
+    pub laps_per_heat_avg: f64,
+    /// Categoria de idade (Júnior/Sênior/Master), vazia sem data de nascimento no cadastro
+    #[serde(default)]
+    pub categoria: String,
+    /// Todas as categorias do piloto (idade + cadastro), para os sub-rankings
+    #[serde(default)]
+    pub categorias: Vec<String>,
+    /// Classe do carro (Classic, LMP...), do preparador ou do arquivo de inscritos (entrants.rs)
+    #[serde(default)]
+    pub classe: String,
+    /// Convidado: aparece no relatório, mas não pontua no campeonato (entrants.rs)
+    #[serde(default)]
+    pub convidado: bool,
+    /// Situação fora da classificação normal: "DNF" (abandonou), "DNS" (não largou),
+    /// "DSQ" (desclassificado); vazia para quem terminou
+    #[serde(default)]
+    pub status: String,
+    /// Consistência: desvio padrão das voltas por bateria e o coeficiente de variação (%)
+    #[serde(default)]
+    pub desvio_voltas: f64,
+    #[serde(default)]
+    pub cv_voltas: f64,
+    /// Desvio padrão (s) e coeficiente de variação (%) dos tempos de volta, 0 sem tempos volta a volta
+    #[serde(default)]
+    pub desvio_tempo: f64,
+    #[serde(default)]
+    pub cv_tempo: f64,
+    /// 0 a 100: 100 menos o CV dos tempos de volta (ou das voltas por bateria, sem tempos)
+    #[serde(default)]
+    pub indice_consistencia: f64,
+    /// Pontos somados nas baterias, só na classificação por pontos (classification.rs)
+    #[serde(default)]
+    pub pontos_baterias: f64,
+    /// O que as penalidades custaram pelas regras do clube (penalties.rs)
+    #[serde(default)]
+    pub penalidade: crate::penalties::Aplicada,
+    /// Métricas próprias do clube, devolvidas pelo script de METRICS_SCRIPT (metrics.rs)
+    #[serde(default)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
+/// Situações aceitas em data["pilots"][id]["status"], na ordem em que vão para o
+/// fim da classificação.
+pub const STATUS: [&str; 3] = ["DNF", "DNS", "DSQ"];
+
+fn status_piloto(p_info: &Value) -> String {
+    let Some(status) = p_info["status"].as_str().map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()) else { return String::new() };
+    if STATUS.contains(&status.as_str()) { return status; }
+    erro!("⚠️ Status desconhecido para {}: '{}' (use {})", p_info["name"].as_str().unwrap_or("---"), status, STATUS.join(", "));
+    String::new()
+}
+
+impl PilotoDisplay {
+    /// Terminou a prova ou abandonou: DNS e DSQ não pontuam nem entram nas médias.
+    pub fn largou_valido(&self) -> bool {
+        self.status.is_empty() || self.status == "DNF"
+    }
+
+    /// Pontua no campeonato: largou (ou abandonou) e não é convidado.
+    pub fn pontua(&self) -> bool {
+        self.largou_valido() && !self.convidado
+    }
+
+    /// Posição para exibir nas tabelas: o status no lugar do número.
+    pub fn posicao_exibida(&self, indice: usize) -> String {
+        if self.status.is_empty() { (indice + 1).to_string() } else { self.status.clone() }
+    }
+}
+
+/// Média e desvio padrão populacional; (0, 0) para lista vazia.
+fn media_desvio(valores: &[f64]) -> (f64, f64) {
+    if valores.is_empty() { return (0.0, 0.0); }
+    let n = valores.len() as f64;
+    let media = valores.iter().sum::<f64>() / n;
+    let variancia = valores.iter().map(|v| (v - media).powi(2)).sum::<f64>() / n;
+    (media, variancia.sqrt())
+}
+
+/// (desvio padrão, coeficiente de variação em %), arredondados para exibição.
+fn dispersao(valores: &[f64]) -> (f64, f64) {
+    let (media, desvio) = media_desvio(valores);
+    let cv = if media > 0.0 { desvio / media * 100.0 } else { 0.0 };
+    ((desvio * 1000.0).round() / 1000.0, (cv * 10.0).round() / 10.0)
+}
+
+/// Fendas usadas nas baterias, como (nome da bateria, sessão, chave da fenda).
+fn fendas_usadas(data: &Value) -> impl Iterator<Item = (&str, &Value, &String)> {
+    data["races"].as_array().into_iter().flatten().flat_map(|race| {
+        let nome = race["name"].as_str().unwrap_or("?");
+        race["sessions"].as_array().into_iter().flatten()
+            .flat_map(move |s| s["slots"].as_object().into_iter().flatten().map(move |(fenda, _)| (nome, &s["session"], fenda)))
+    })
+}
+
+/// O p_id de uma fenda é o piloto `id`? O JSON pode trazê-lo como texto ("7") ou número (7).
+pub fn mesmo_piloto(p_id: &Value, id: &str) -> bool {
+    match p_id {
+        Value::String(s) => s == id,
+        Value::Number(n) => n.as_i64().is_some_and(|n| id.parse() == Ok(n)),
+        _ => false,
+    }
+}
+
+/// Número de fendas da prova: metadata.slots, ou a maior fenda usada nas
+/// baterias quando o JSON não traz (6 se não houver nenhuma).
+pub fn total_fendas(data: &Value) -> usize {
+    data["metadata"]["slots"].as_u64().filter(|n| *n > 0).map(|n| n as usize)
+        .or_else(|| fendas_usadas(data).filter_map(|(_, _, f)| f.parse::<usize>().ok()).max())
+        .unwrap_or(6)
+}
+
+/// Confere metadata.slots com as fendas das baterias: valor inválido, fenda que
+/// não é um número de 1 a N ou fenda além de metadata.slots é erro, para o
+/// relatório não sair com colunas faltando.
+pub fn validar_fendas(data: &Value) -> Result<usize, String> {
+    let declarado = match &data["metadata"]["slots"] {
+        Value::Null => None,
+        v => Some(v.as_u64().filter(|n| *n > 0)
+            .ok_or_else(|| format!("❌ metadata.slots inválido: {} (esperado um número inteiro de fendas maior que zero)", v))? as usize),
+    };
+    let mut maior = 0;
+    for (bateria, sessao, fenda) in fendas_usadas(data) {
+        let n = fenda.parse::<usize>().ok().filter(|n| *n > 0)
+            .ok_or_else(|| format!("❌ Fenda '{}' inválida em {} / sessão {} (esperado 1, 2, 3...)", fenda, bateria, sessao))?;
+        if let Some(total) = declarado.filter(|t| n > *t) {
+            return Err(format!("❌ {} / sessão {} usa a fenda {}, mas metadata.slots = {}. Confira o número de fendas no cronometrador.", bateria, sessao, n, total));
+        }
+        maior = maior.max(n);
+    }
+    Ok(declarado.unwrap_or(maior.max(1)))
+}
+
+/// Monta o ranking de exibição a partir do JSON do preparador.
+/// Retorna o ranking ordenado e a melhor volta absoluta da prova (999.999 se não houver).
+pub fn montar_ranking(data: &Value) -> (Vec<PilotoDisplay>, f64) {
+    let mut ranking: Vec<PilotoDisplay> = Vec::new();
+    let mut best_lap_overall = 999.999;
+    let fendas = crate::config::fendas_pista(total_fendas(data));
+
+    if let Some(pilots_map) = data["pilots"].as_object() {
+        for (id, p_info) in pilots_map {
+            let mut laps_map = HashMap::new();
+            let mut times_map = HashMap::new();
+            let mut tempos_fenda: HashMap<String, Vec<f64>> = HashMap::new();
+            let mut total_voltas = 0;
+            let mut melhor_tempo_piloto = 999.999;
+            let mut best_slot_idx = 1;
+            let mut sessions_count = 0;
+            let mut total_deslots = 0;
+            let mut lap_times = Vec::new();
+            let mut voltas_baterias: Vec<f64> = Vec::new();
+
+            if let Some(races) = data["races"].as_array() {
+                for race in races {
+                    if let Some(sessions) = race["sessions"].as_array() {
+                        for session in sessions {
+                            if let Some(slots) = session["slots"].as_object() {
+                                for (slot_idx, s_data) in slots {
+                                    if mesmo_piloto(&s_data["p_id"], id) {
+                                        let l = s_data["laps"].as_i64().unwrap_or(0);
+                                        let t = s_data["best"].as_f64().unwrap_or(0.0);
+                                        if l > 0 {
+                                            sessions_count += 1;
+                                            voltas_baterias.push(l as f64);
+                                        }
+                                        total_deslots += s_data["deslots"].as_i64().unwrap_or(0);
+                                        if let Some(tempos) = s_data["lap_times"].as_array() {
+                                            let validos: Vec<f64> = tempos.iter().filter_map(|t| t.as_f64()).filter(|t| *t > 0.0).collect();
+                                            tempos_fenda.entry(slot_idx.clone()).or_default().extend(&validos);
+                                            lap_times.extend(validos);
+                                        }
+                                        total_voltas += l;
+                                        laps_map.insert(slot_idx.clone(), l.to_string());
+                                        times_map.insert(slot_idx.clone(), if t > 0.0 { format!("{:.3}", t) } else { "---".into() });
+                                        if t > 0.0 && t < melhor_tempo_piloto {
+                                            melhor_tempo_piloto = t;
+                                            best_slot_idx = slot_idx.parse().unwrap_or(1);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if melhor_tempo_piloto < best_lap_overall && melhor_tempo_piloto > 0.0 { best_lap_overall = melhor_tempo_piloto; }
+            let display_best = if melhor_tempo_piloto >= 900.0 { "0.000".into() } else { format!("{:.3}", melhor_tempo_piloto) };
+            
+            // Aqui corrigimos para usar o total_laps OFICIAL do ranking se disponível
+            let mut final_laps = total_voltas;
+            let mut final_gap = "0".to_string();
+            // let mut final_zona = "000".to_string();
+
+            if let Some(p_off) = data["official_ranking"].as_array().and_then(|r| r.iter().find(|x| x["p_id"].as_str().unwrap_or("") == id)) {
+                final_laps = p_off["laps"].as_i64().unwrap_or(total_voltas);
+                final_gap = p_off["gap"].as_str().unwrap_or("0").to_string();
+            }
+
+            // Saídas de pista: o total oficial (gp_result_deslots) prevalece sobre a soma das baterias
+            let final_deslots = data["raw_results"]["deslots"][id].as_str()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .unwrap_or(total_deslots);
+
+            let media = if sessions_count > 0 { final_laps as f64 / sessions_count as f64 } else { 0.0 };
+
+            let formatar_media = |tempos: &[f64]| match media_desvio(tempos) {
+                (m, _) if m > 0.0 => format!("{:.3}", m),
+                _ => "---".to_string(),
+            };
+            let average_lap_per_slot: HashMap<String, String> = tempos_fenda.iter()
+                .map(|(fenda, tempos)| (fenda.clone(), formatar_media(tempos)))
+                .collect();
+
+            // --- CONSISTÊNCIA ---
+            let (desvio_voltas, cv_voltas) = dispersao(&voltas_baterias);
+            let (desvio_tempo, cv_tempo) = dispersao(&lap_times);
+            let cv_base = if lap_times.len() > 1 { cv_tempo } else { cv_voltas };
+            let indice_consistencia = if sessions_count > 0 { (100.0 - cv_base).clamp(0.0, 100.0) } else { 0.0 };
+
+            let average_lap_secs = media_desvio(&lap_times).0;
+            let gap_laps = final_gap.trim().replace(',', ".").parse().unwrap_or(0.0);
+
+            ranking.push(PilotoDisplay {
+                nome: p_info["name"].as_str().unwrap_or("---").to_string(),
+                total_laps: final_laps,
+                // O .INI traz as penalidades como texto
+                penalties: data["raw_results"]["penaltys"][id].as_i64()
+                    .or_else(|| data["raw_results"]["penaltys"][id].as_str().and_then(|v| v.trim().parse().ok()))
+                    .unwrap_or(0),
+                deslots: final_deslots,
+                zona: data["raw_results"]["zones"][id].as_str().unwrap_or("000").to_string(),
+                gap: final_gap,
+                sessions: sessions_count,
+                best_time: display_best,
+                average_time: format!("{:.1}", media).replace(".", ","),
+                laps_per_heat: format!("{:.1}", media).replace(".", ","),
+                average_lap_time: formatar_media(&lap_times),
+                average_lap_per_slot,
+                is_overall_best: false,
+                best_slot_name: (best_slot_idx as usize).checked_sub(1).and_then(|i| fendas.get(i)).map(|f| f.nome.clone()).unwrap_or_else(|| "---".to_string()),
+                best_slot_color: (best_slot_idx as usize).checked_sub(1).and_then(|i| fendas.get(i)).map(|f| f.cor.clone()).unwrap_or_default(),
+                laps_per_slot: laps_map,
+                times_per_slot: times_map,
+                lap_times,
+                tempos_por_fenda: tempos_fenda,
+                performance_index: 0.0,
+                best_lap_secs: if melhor_tempo_piloto >= 900.0 { 0.0 } else { melhor_tempo_piloto },
+                average_lap_secs,
+                gap_laps,
+                laps_per_heat_avg: media,
+                categoria: p_info["category"].as_str().unwrap_or("").to_string(),
+                categorias: crate::registry::categorias_piloto(p_info),
+                classe: p_info["class"].as_str().unwrap_or("").trim().to_string(),
+                convidado: p_info["guest"].as_bool().unwrap_or(false),
+                status: status_piloto(p_info),
+                desvio_voltas,
+                cv_voltas,
+                desvio_tempo,
+                cv_tempo,
+                indice_consistencia: (indice_consistencia * 10.0).round() / 10.0,
+                pontos_baterias: 0.0,
+                penalidade: Default::default(),
+                extra: serde_json::Map::new(),
+            });
+        }
+    }
+
+    crate::penalties::aplicar(&mut ranking, data);
+    // Sem o ranking oficial do cronometrador o gap é calculado aqui, já com as penalidades
+    if data["official_ranking"].as_array().is_none_or(|r| r.is_empty()) {
+        calcular_gaps(&mut ranking, data);
+    }
+    crate::classification::criterio().classificar(&mut ranking, data);
+    // DNF, DNS e DSQ vão para o fim, nessa ordem, mantendo a ordem do critério entre eles
+    ranking.sort_by_key(|p| STATUS.iter().position(|s| *s == p.status).map(|i| i + 1).unwrap_or(0));
+    let best_lap_str = format!("{:.3}", best_lap_overall);
+    for p in &mut ranking { if p.best_time == best_lap_str && best_lap_overall < 900.0 { p.is_overall_best = true; } }
+    crate::metrics::aplicar(data, &mut ranking);
+
+    (ranking, best_lap_overall)
+}
+
+/// Fração da última volta percorrida por piloto (pelo nome), na última sessão em
+/// que ele correu com tempos volta a volta: é ali que a prova dele termina. A sessão
+/// dura o mesmo para todos: o tempo que sobrou depois da última volta completa (a
+/// sessão medida pelo piloto que mais rodou nela) dividido pela volta média do piloto.
+fn fracoes_ultima_volta(data: &Value) -> HashMap<String, f64> {
+    let mut fracoes: HashMap<String, f64> = HashMap::new();
+    for session in data["races"].as_array().into_iter().flatten().flat_map(|r| r["sessions"].as_array().into_iter().flatten()) {
+        let tempos: Vec<(String, f64, usize)> = session["slots"].as_object().into_iter().flatten()
+            .filter_map(|(_, s)| {
+                let voltas: Vec<f64> = s["lap_times"].as_array()?.iter().filter_map(|t| t.as_f64()).filter(|t| *t > 0.0).collect();
+                if voltas.is_empty() { return None; }
+                let id = s["p_id"].as_str().map(|s| s.to_string()).unwrap_or_else(|| s["p_id"].to_string());
+                Some((id, voltas.iter().sum::<f64>(), voltas.len()))
+            })
+            .collect();
+        let duracao = tempos.iter().map(|(_, total, _)| *total).fold(0.0, f64::max);
+        for (id, total, n) in tempos {
+            let Some(nome) = data["pilots"][&id]["name"].as_str() else { continue };
+            let media = total / n as f64;
+            // Sessões posteriores substituem as anteriores
+            fracoes.insert(nome.to_string(), ((duracao - total) / media).clamp(0.0, 0.999));
+        }
+    }
+    fracoes
+}
+
+/// Gap em voltas para o líder quando o .INI não traz o ranking oficial: voltas de
+/// diferença mais, com tempos volta a volta, a distância dentro da última volta.
+/// Roda depois das penalidades: as voltas já foram descontadas do total e o tempo
+/// que não chegou a uma volta inteira sai da distância.
+fn calcular_gaps(ranking: &mut [PilotoDisplay], data: &Value) {
+    let fracoes = fracoes_ultima_volta(data);
+    let distancia = |p: &PilotoDisplay| p.total_laps as f64 + fracoes.get(&p.nome).copied().unwrap_or(0.0) - p.penalidade.fracao_volta;
+    let lider = ranking.iter().filter(|p| p.largou_valido()).map(distancia).fold(f64::MIN, f64::max);
+    for p in ranking.iter_mut() {
+        let gap = ((lider - distancia(p)).max(0.0) * 1000.0).round() / 1000.0;
+        p.gap_laps = gap;
+        p.gap = format!("{:.3}", gap);
+    }
+}
+
+/// Índice de performance normalizado entre pistas.
+/// Referência: recorde da pista no histórico, ou a melhor volta desta prova se for mais rápida.
+pub fn aplicar_indice(ranking: &mut [PilotoDisplay], historico: &Historico, club: &str, track: &str, best_lap_overall: f64) {
+    let recorde = match historico.recorde_pista(club, track) {
+        Some(r) if best_lap_overall >= 900.0 || r < best_lap_overall => r,
+        _ => best_lap_overall,
+    };
+    let laps_vencedor = ranking.first().map(|p| p.total_laps).unwrap_or(0);
+    for p in ranking.iter_mut() {
+        let best = p.best_time.parse::<f64>().unwrap_or(0.0);
+        p.performance_index = if p.largou_valido() { calcular_indice(best, recorde, p.total_laps, laps_vencedor) } else { 0.0 };
+    }
+}
+
+/// Classificação de cada classe, na ordem em que as classes aparecem na geral.
+/// Vazio quando a prova tem uma classe só (a geral já é a classificação dela).
+pub fn rankings_por_classe(ranking: &[PilotoDisplay]) -> Vec<Value> {
+    let mut classes: Vec<&str> = Vec::new();
+    for p in ranking.iter().filter(|p| !p.classe.is_empty()) {
+        if !classes.contains(&p.classe.as_str()) { classes.push(&p.classe); }
+    }
+    if classes.len() < 2 { return Vec::new(); }
+    // Gap para o líder da classe: pelo gap oficial (com fração de volta) ou, sem ele, pelas voltas
+    let usa_gap = ranking.iter().any(|p| p.gap_laps > 0.0);
+    classes.iter().map(|classe| {
+        let pilotos: Vec<&PilotoDisplay> = ranking.iter().filter(|p| p.classe == *classe).collect();
+        let lider = pilotos[0];
+        json!({
+            "classe": classe,
+            "pilotos": pilotos.iter().map(|p| json!({
+                "nome": p.nome,
+                "posicao_geral": ranking.iter().position(|r| std::ptr::eq(r, *p)).map(|i| i + 1),
+                "total_laps": p.total_laps,
+                "gap_laps": if usa_gap { (p.gap_laps - lider.gap_laps).max(0.0) } else { (lider.total_laps - p.total_laps).max(0) as f64 },
+                "best_time": p.best_time,
+                "is_overall_best": p.is_overall_best,
+            })).collect::<Vec<_>>(),
+        })
+    }).collect()
+}
+
+/// Os três primeiros de cada categoria (registry::categorias_sub_ranking), na
+/// ordem da classificação geral.
+pub fn podios_por_categoria(ranking: &[PilotoDisplay]) -> Vec<Value> {
+    let mut presentes: Vec<String> = Vec::new();
+    for c in ranking.iter().flat_map(|p| &p.categorias) {
+        if !presentes.contains(c) { presentes.push(c.clone()); }
+    }
+    crate::registry::categorias_sub_ranking(&presentes).iter()
+        .filter_map(|cat| {
+            let podio: Vec<&PilotoDisplay> = ranking.iter().filter(|p| p.categorias.contains(cat)).take(3).collect();
+            (!podio.is_empty()).then(|| json!({
+                "categoria": cat,
+                "vagas": 3 - podio.len(),
+                "pilotos": podio.iter().map(|p| json!({ "nome": p.nome, "total_laps": p.total_laps, "best_time": p.best_time })).collect::<Vec<_>>(),
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mesmo_piloto, montar_ranking};
+    use serde_json::json;
+
+    #[test]
+    fn ordena_pelo_ranking_oficial() {
+        let data = json!({
+            "metadata": { "slots": 2 },
+            "pilots": { "1": { "name": "Ana" }, "2": { "name": "Bruno" } },
+            "races": [{ "sessions": [{ "slots": {
+                "1": { "p_id": "1", "laps": 20, "best": 5.1 },
+                "2": { "p_id": 2, "laps": 22, "best": 4.9 },
+            } }] }],
+            "official_ranking": [
+                { "p_id": "2", "laps": 22, "gap": "0" },
+                { "p_id": "1", "laps": 20, "gap": "2" },
+            ],
+        });
+        let (ranking, melhor) = montar_ranking(&data);
+        let nomes: Vec<&str> = ranking.iter().map(|p| p.nome.as_str()).collect();
+        assert_eq!(nomes, ["Bruno", "Ana"]);
+        assert_eq!(ranking[0].total_laps, 22);
+        assert_eq!(ranking[1].gap, "2");
+        assert!(ranking[0].is_overall_best);
+        assert_eq!(melhor, 4.9);
+    }
+
+    #[test]
+    fn gap_nativo_so_com_a_fracao_da_ultima_sessao() {
+        let sessao = json!({ "slots": {
+            "1": { "p_id": "1", "laps": 3, "best": 1.0, "lap_times": [1.0, 1.0, 1.0] },
+            "2": { "p_id": "2", "laps": 2, "best": 1.2, "lap_times": [1.2, 1.2] },
+        } });
+        let data = json!({
+            "metadata": { "slots": 2 },
+            "pilots": { "1": { "name": "Ana" }, "2": { "name": "Bruno" } },
+            "races": [{ "sessions": [sessao.clone(), sessao] }],
+        });
+        let (ranking, _) = montar_ranking(&data);
+        // 6 contra 4 voltas, e Bruno andou meia volta a mais na última sessão (não uma em cada)
+        assert_eq!(ranking[0].gap, "0.000");
+        assert_eq!(ranking[1].gap, "1.500");
+    }
+
+    #[test]
+    fn p_id_em_texto_ou_numero() {
+        assert!(mesmo_piloto(&json!("7"), "7"));
+        assert!(mesmo_piloto(&json!(7), "7"));
+        assert!(!mesmo_piloto(&json!(7), "8"));
+        assert!(!mesmo_piloto(&json!(null), "7"));
+    }
+}
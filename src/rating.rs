@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+
+use crate::error::RacedayError;
+use crate::storage::slug_chave;
+
+// --- RATING DOS PILOTOS (ELO) ---
+// Opcional: RATING_ENABLED=1 (ou [rating] enabled = true no raceday.toml).
+// Depois de cada prova o rating de cada piloto é atualizado por confronto direto
+// com cada adversário (Elo multijogador): terminar à frente vale 1, atrás 0 e
+// o mesmo número de voltas 0,5; a soma dos ganhos é dividida pelo número de
+// adversários, para o K valer por prova e não por confronto.
+//   RATING_K        fator K (padrão 32)
+//   RATING_INITIAL  rating de quem estreia (padrão 1500)
+// Os ratings ficam em ratings/<clube>.json com a lista das provas já aplicadas
+// (reprocessar uma prova não conta duas vezes) e aparecem no index.html e nas
+// páginas de piloto. A escrita é condicional, como no manifesto das provas.
+
+const K_PADRAO: f64 = 32.0;
+const INICIAL_PADRAO: f64 = 1500.0;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RatingPiloto {
+    pub rating: f64,
+    pub provas: usize,
+    /// Variação na última prova disputada
+    pub variacao: f64,
+    /// Timestamp da última prova disputada
+    pub atualizado: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Ratings {
+    #[serde(default)]
+    pub pilotos: BTreeMap<String, RatingPiloto>,
+    /// Timestamps das provas já contabilizadas
+    #[serde(default)]
+    pub provas: Vec<String>,
+}
+
+/// Linha da tabela de ratings, como aparece no index.html.
+#[derive(Serialize)]
+pub struct LinhaRating {
+    pub posicao: usize,
+    pub nome: String,
+    pub rating: i64,
+    pub variacao: i64,
+    pub provas: usize,
+}
+
+pub fn ativo() -> bool {
+    env::var("RATING_ENABLED").is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes" | "sim"))
+}
+
+fn parametro(nome: &str, padrao: f64) -> f64 {
+    env::var(nome).ok().and_then(|v| v.trim().parse().ok()).unwrap_or(padrao)
+}
+
+pub fn chave(club: &str) -> String {
+    format!("ratings/{}.json", slug_chave(club))
+}
+
+/// Probabilidade de `a` terminar à frente de `b`.
+fn esperado(a: f64, b: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((b - a) / 400.0))
+}
+
+/// Aplica a prova (`ordem`: piloto e voltas, na ordem de chegada).
+/// Retorna `false` se ela já tinha sido contabilizada.
+fn aplicar(ratings: &mut Ratings, ts: &str, ordem: &[(String, i64)]) -> bool {
+    if ratings.provas.iter().any(|p| p == ts) { return false; }
+    let k = parametro("RATING_K", K_PADRAO);
+    let inicial = parametro("RATING_INITIAL", INICIAL_PADRAO);
+
+    // Todos os confrontos usam o rating de antes da prova
+    let antes: Vec<f64> = ordem.iter()
+        .map(|(nome, _)| ratings.pilotos.get(nome).map(|r| r.rating).unwrap_or(inicial))
+        .collect();
+    let adversarios = ordem.len().saturating_sub(1).max(1) as f64;
+
+    for (i, (nome, voltas)) in ordem.iter().enumerate() {
+        let ganho: f64 = ordem.iter().enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(j, (_, voltas_adv))| {
+                let resultado = if voltas == voltas_adv { 0.5 } else if i < j { 1.0 } else { 0.0 };
+                resultado - esperado(antes[i], antes[j])
+            })
+            .sum();
+        let variacao = k * ganho / adversarios;
+        let piloto = ratings.pilotos.entry(nome.clone()).or_insert(RatingPiloto {
+            rating: inicial,
+            provas: 0,
+            variacao: 0.0,
+            atualizado: String::new(),
+        });
+        piloto.rating = antes[i] + variacao;
+        piloto.variacao = variacao;
+        piloto.provas += 1;
+        piloto.atualizado = ts.to_string();
+    }
+    ratings.provas.push(ts.to_string());
+    true
+}
+
+pub async fn carregar(club: &str) -> Result<Ratings, Box<dyn Error>> {
+    let key = chave(club);
+    match crate::storage::baixar_r2(&key).await? {
+        Some(bytes) => Ok(serde_json::from_slice(&bytes).map_err(|e| format!("❌ {} inválido: {}", key, e))?),
+        None => Ok(Ratings::default()),
+    }
+}
+
+/// Ratings em ordem decrescente, arredondados para exibição.
+pub fn tabela(ratings: &Ratings) -> Vec<LinhaRating> {
+    let mut pilotos: Vec<(&String, &RatingPiloto)> = ratings.pilotos.iter().collect();
+    pilotos.sort_by(|a, b| b.1.rating.total_cmp(&a.1.rating));
+    pilotos.into_iter().enumerate().map(|(i, (nome, r))| LinhaRating {
+        posicao: i + 1,
+        nome: nome.clone(),
+        rating: r.rating.round() as i64,
+        variacao: r.variacao.round() as i64,
+        provas: r.provas,
+    }).collect()
+}
+
+/// Contabiliza a prova em ratings/<clube>.json (escrita condicional).
+/// Retorna os ratings atualizados, ou `None` se a prova já constava.
+pub async fn atualizar(club: &str, ts: &str, ordem: &[(String, i64)]) -> Result<Option<Ratings>, Box<dyn Error>> {
+    let key = chave(club);
+    let local = crate::pasta_saida().join("last_ratings.json");
+    Ok(crate::storage::atualizar_condicional(&key, &local, |atual| {
+        let mut ratings: Ratings = match atual {
+            Some(bytes) => serde_json::from_slice(bytes).map_err(|e| RacedayError::Leitura(format!("❌ {} inválido: {}", key, e)))?,
+            None => Ratings::default(),
+        };
+        if !aplicar(&mut ratings, ts, ordem) { return Ok(None); }
+        Ok(Some((serde_json::to_string_pretty(&ratings)?, ratings)))
+    }).await?)
+}
@@ -0,0 +1,154 @@
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use tera::Tera;
+
+use crate::history::Historico;
+use crate::{marshals, parallel, pilots, public_api};
+
+// --- REBUILD: REGERA AS PÁGINAS DERIVADAS A PARTIR DOS JSONs PUBLICADOS ---
+// Uso: raceday rebuild [--only reports,pilots,marshals,api,standings,index]
+// Útil depois de mudar template ou identidade visual. Só reenvia o que mudou.
+//   standings  classificação de cada clube e temporada com provas publicadas (season.rs)
+//   index      manifesto races/index.json e o index.html dos relatórios
+
+pub const ARTEFATOS: [&str; 6] = ["reports", "pilots", "marshals", "api", "standings", "index"];
+
+/// `artefatos`: os de --only (já validados contra ARTEFATOS); vazio regera todos.
+pub async fn executar(artefatos: &[String]) -> Result<(), Box<dyn Error>> {
+    let quer = |a: &str| artefatos.is_empty() || artefatos.iter().any(|x| x == a);
+
+    let historico = crate::privacy::historico_publico(&Historico::carregar());
+    crate::assets::publicar().await?;
+    let mut tera = Tera::default();
+    crate::assets::registrar(&mut tera);
+    crate::templates::carregar(&mut tera, "report.html", "report")?;
+    let local_dir = crate::pasta_saida().join("rebuild");
+    fs::create_dir_all(&local_dir)?;
+
+    let mut enviados = 0;
+    let mut verificados = 0;
+
+    if ["reports", "marshals", "api", "standings", "index"].iter().any(|a| quer(a)) {
+        let chaves = crate::storage::listar_r2("races/").await?;
+        saida!("🔁 Regerando a partir de {} JSON(s) publicados...", chaves.len());
+
+        let mut provas: Vec<(String, Value)> = Vec::new();
+        for key in chaves.iter().filter(|k| k.ends_with(".json") && k.as_str() != crate::manifest::CHAVE) {
+            let Some(bytes) = crate::storage::baixar_r2(key).await? else { continue };
+            match crate::schema::ler_publicada(key, &bytes) {
+                Ok(mut v) => {
+                    // Preferências de privacidade mudadas depois da publicação também valem no rebuild
+                    crate::privacy::anonimizar_prova(&mut v);
+                    provas.push((key.clone(), v));
+                }
+                Err(e) => erro!("⚠️ JSON inválido em {}: {}", key, e),
+            }
+        }
+
+        let curvas = if quer("marshals") { marshals::curvas_configuradas() } else { None };
+        if curvas.is_some() { marshals::carregar_template(&mut tera)?; }
+
+        // Render em paralelo (CPU); downloads e uploads seguem sequenciais.
+        // Os erros viram String porque Box<dyn Error> não atravessa threads.
+        let tera_ref = &tera;
+        let renderizadas = parallel::mapear(&provas, |(_, data)| {
+            let club = data["club"].as_str().unwrap_or("");
+            let track = data["track"].as_str().unwrap_or("");
+            let (mut ranking, best_lap_overall) = crate::ranking::montar_ranking(data);
+            crate::ranking::aplicar_indice(&mut ranking, &historico, club, track, best_lap_overall);
+            let relatorio = quer("reports").then(|| {
+                crate::report::renderizar_relatorio(tera_ref, data, &ranking, best_lap_overall, club, track).map_err(|e| e.to_string())
+            });
+            let api = quer("api").then(|| public_api::gerar(data, &ranking, best_lap_overall));
+            let fiscais = curvas.as_ref().map(|curvas| {
+                let escala = marshals::gerar_escala(data, curvas);
+                marshals::renderizar(tera_ref, data, club, track, curvas, &escala).map_err(|e| e.to_string())
+            });
+            let vencedor = ranking.first().map(|p| p.nome.clone());
+            (relatorio, fiscais, api, vencedor)
+        });
+
+        let mut manifesto = Vec::new();
+        let mut temporadas: BTreeSet<(String, String)> = BTreeSet::new();
+        for ((key, data), (relatorio, fiscais, api, vencedor)) in provas.iter().zip(renderizadas) {
+            let chave_base = crate::storage::base_relatorio(
+                data["club"].as_str().unwrap_or(""),
+                data["track"].as_str().unwrap_or(""),
+                data["event"]["slug"].as_str().unwrap_or("race"),
+                data["event"]["timestamp"].as_str().unwrap_or("000"));
+            let base = chave_base.trim_start_matches("reports/");
+
+            if let Some(html) = relatorio {
+                let local = local_dir.join(format!("{}.html", base));
+                fs::write(&local, html?)?;
+                verificados += 1;
+                if crate::storage::upload_se_mudou(&local, &format!("{}.html", chave_base)).await? { enviados += 1; }
+            }
+
+            if let Some(html) = fiscais {
+                let local = local_dir.join(format!("{}_fiscais.html", base));
+                fs::write(&local, html?)?;
+                verificados += 1;
+                if crate::storage::upload_se_mudou(&local, &format!("{}_fiscais.html", chave_base)).await? { enviados += 1; }
+            }
+
+            if let Some(api) = api {
+                verificados += 2;
+                enviados += public_api::publicar(&local_dir, &public_api::chave(key), &api, true).await?;
+            }
+
+            let club = data["club"].as_str().unwrap_or("");
+            let ts = data["event"]["timestamp"].as_str().unwrap_or("000");
+            if !club.is_empty() {
+                temporadas.insert((club.to_string(), ts.get(0..4).unwrap_or("").to_string()));
+            }
+            manifesto.push(crate::manifest::EntradaManifesto {
+                club: club.to_string(),
+                track: data["track"].as_str().unwrap_or("").to_string(),
+                slug: data["event"]["slug"].as_str().unwrap_or("race").to_string(),
+                timestamp: ts.to_string(),
+                title: data["event"]["title"].as_str().unwrap_or("Corrida").to_string(),
+                date: data["event"]["date"].as_str().unwrap_or("").to_string(),
+                json_key: key.clone(),
+                html_key: format!("{}.html", chave_base),
+                winner: vencedor,
+            });
+        }
+
+        if quer("standings") {
+            for (club, ano) in &temporadas {
+                crate::season::executar(ano, club, None).await?;
+                verificados += 2;
+            }
+        }
+
+        if quer("index") {
+            let total = crate::manifest::registrar_todas(&manifesto).await?;
+            saida!("📚 {} regravado: {} prova(s)", crate::manifest::CHAVE, total);
+            let relatorios = crate::site_index::publicar().await?;
+            saida!("🏠 index.html com {} relatório(s)", relatorios);
+            verificados += 2;
+        }
+    }
+
+    if quer("pilots") {
+        // Uma rodada por clube e temporada presentes no histórico
+        let temporadas: BTreeSet<(String, String)> = historico.eventos.iter()
+            .map(|e| (e.club.clone(), e.timestamp.get(0..4).unwrap_or("").to_string()))
+            .collect();
+        for (club, temporada) in &temporadas {
+            let nomes: BTreeSet<String> = historico.eventos.iter()
+                .filter(|e| &e.club == club && e.timestamp.starts_with(temporada.as_str()))
+                .flat_map(|e| e.resultados.iter().map(|r| r.nome.clone()))
+                .collect();
+            let nomes: Vec<String> = nomes.into_iter().collect();
+            verificados += nomes.len();
+            enviados += pilots::publicar_paginas(&mut tera, &historico, club, &nomes, temporada, true).await?;
+        }
+    }
+
+    saida!("\n✨ Rebuild concluído: {} artefato(s) verificados, {} reenviado(s).", verificados, enviados);
+    Ok(())
+}
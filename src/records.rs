@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use crate::error::RacedayError;
+use crate::storage::slug_chave;
+
+// --- RECORDES DA PISTA ---
+// records/<clube>_<pista>.json guarda a melhor volta de todos os tempos na pista,
+// no geral e em cada fenda. Antes de gerar o relatório a prova é comparada com o
+// arquivo; os recordes quebrados ficam em data["records"] (o rebuild os preserva)
+// e viram insights. Depois do envio o arquivo é regravado com If-Match, como o
+// manifesto: a mescla fica sempre com o menor tempo, então duas provas
+// publicadas ao mesmo tempo não apagam o recorde uma da outra.
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Recorde {
+    pub tempo: f64,
+    pub piloto: String,
+    pub timestamp: String,
+    pub titulo: String,
+    /// Recorde que este substituiu, para reprocessar a prova e chegar ao mesmo resultado
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anterior: Option<Box<Recorde>>,
+}
+
+impl Recorde {
+    fn sem_anterior(&self) -> Recorde {
+        Recorde { anterior: None, ..self.clone() }
+    }
+}
+
+/// `novo` no lugar de `velho`, lembrando o recorde substituído. Se `velho` já era
+/// desta mesma prova (reprocessamento), o anterior continua o de antes dela.
+fn substituir(velho: Option<&Recorde>, mut novo: Recorde) -> Recorde {
+    novo.anterior = match velho {
+        Some(v) if v.timestamp == novo.timestamp => v.anterior.clone(),
+        Some(v) => Some(Box::new(v.sem_anterior())),
+        None => None,
+    };
+    novo
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Recordes {
+    pub geral: Option<Recorde>,
+    /// Por número da fenda ("1", "2", ...)
+    #[serde(default)]
+    pub fendas: BTreeMap<String, Recorde>,
+}
+
+/// Recorde quebrado nesta prova (`fenda` vazia: recorde geral da pista).
+#[derive(Serialize, Deserialize)]
+pub struct RecordeBatido {
+    pub fenda: String,
+    pub nome_fenda: String,
+    pub piloto: String,
+    pub tempo: f64,
+    /// Recorde anterior (`None`: primeira prova registrada na pista/fenda)
+    pub anterior: Option<Recorde>,
+}
+
+pub fn chave(club: &str, track: &str) -> String {
+    format!("records/{}_{}.json", slug_chave(club), slug_chave(track))
+}
+
+/// Melhor volta da prova em cada fenda, com o piloto que a fez.
+fn melhores_da_prova(data: &Value) -> BTreeMap<String, Recorde> {
+    let timestamp = data["event"]["timestamp"].as_str().unwrap_or("").to_string();
+    let titulo = data["event"]["title"].as_str().unwrap_or("Corrida").to_string();
+    let mut melhores: BTreeMap<String, Recorde> = BTreeMap::new();
+    for race in data["races"].as_array().into_iter().flatten() {
+        for session in race["sessions"].as_array().into_iter().flatten() {
+            for (fenda, s) in session["slots"].as_object().into_iter().flatten() {
+                let tempo = s["best"].as_f64().unwrap_or(0.0);
+                if tempo <= 0.0 || melhores.get(fenda).is_some_and(|r| r.tempo <= tempo) { continue; }
+                melhores.insert(fenda.clone(), Recorde {
+                    tempo,
+                    piloto: s["name"].as_str().unwrap_or("---").to_string(),
+                    timestamp: timestamp.clone(),
+                    titulo: titulo.clone(),
+                    anterior: None,
+                });
+            }
+        }
+    }
+    melhores
+}
+
+fn melhor_geral(fendas: &BTreeMap<String, Recorde>) -> Option<&Recorde> {
+    fendas.values().min_by(|a, b| a.tempo.total_cmp(&b.tempo))
+}
+
+pub async fn carregar(club: &str, track: &str) -> Result<Recordes, Box<dyn Error>> {
+    let key = chave(club, track);
+    match crate::storage::baixar_r2(&key).await? {
+        Some(bytes) => Ok(serde_json::from_slice(&bytes).map_err(|e| format!("❌ {} inválido: {}", key, e))?),
+        None => Ok(Recordes::default()),
+    }
+}
+
+/// Recordes que a prova quebra em relação ao arquivo atual. Outra volta da
+/// mesma prova não conta como recorde anterior: no reprocessamento vale o
+/// recorde que ela substituiu, e o resultado é o mesmo da primeira vez.
+pub fn detectar(atuais: &Recordes, data: &Value) -> Vec<RecordeBatido> {
+    let fendas = crate::config::fendas_pista(crate::ranking::total_fendas(data));
+    let ts = data["event"]["timestamp"].as_str().unwrap_or("");
+    let anterior = |r: Option<&Recorde>| match r {
+        Some(r) if r.timestamp == ts => r.anterior.as_deref().cloned(),
+        r => r.map(Recorde::sem_anterior),
+    };
+    let bate = |novo: &Recorde, velho: &Option<Recorde>| velho.as_ref().is_none_or(|v| novo.tempo < v.tempo);
+
+    let melhores = melhores_da_prova(data);
+    let mut batidos = Vec::new();
+    if let Some(novo) = melhor_geral(&melhores) {
+        let velho = anterior(atuais.geral.as_ref());
+        if bate(novo, &velho) {
+            batidos.push(RecordeBatido { fenda: String::new(), nome_fenda: String::new(), piloto: novo.piloto.clone(), tempo: novo.tempo, anterior: velho });
+        }
+    }
+    for (fenda, novo) in &melhores {
+        let velho = anterior(atuais.fendas.get(fenda));
+        if bate(novo, &velho) {
+            let nome_fenda = fenda.parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|i| fendas.get(i))
+                .map(|f| f.nome.clone())
+                .unwrap_or_else(|| format!("Fenda {}", fenda));
+            batidos.push(RecordeBatido { fenda: fenda.clone(), nome_fenda, piloto: novo.piloto.clone(), tempo: novo.tempo, anterior: velho });
+        }
+    }
+    batidos
+}
+
+/// Mescla a prova no arquivo de recordes, ficando com o menor tempo de cada fenda.
+fn mesclar(atual: Option<&[u8]>, data: &Value) -> Result<Recordes, Box<dyn Error>> {
+    let mut recordes: Recordes = match atual {
+        Some(bytes) => serde_json::from_slice(bytes)?,
+        None => Recordes::default(),
+    };
+    for (fenda, novo) in melhores_da_prova(data) {
+        let velho = recordes.fendas.get(&fenda);
+        if velho.is_none_or(|r| novo.tempo < r.tempo) {
+            let novo = substituir(velho, novo);
+            recordes.fendas.insert(fenda, novo);
+        }
+    }
+    let melhor = melhor_geral(&recordes.fendas).map(Recorde::sem_anterior);
+    if let Some(novo) = melhor.filter(|n| recordes.geral.as_ref().is_none_or(|v| n.tempo < v.tempo)) {
+        recordes.geral = Some(substituir(recordes.geral.as_ref(), novo));
+    }
+    Ok(recordes)
+}
+
+/// Regrava records/<clube>_<pista>.json com a prova (escrita condicional).
+pub async fn registrar(club: &str, track: &str, data: &Value) -> Result<(), Box<dyn Error>> {
+    let key = chave(club, track);
+    let local = crate::pasta_saida().join("last_records.json");
+    crate::storage::atualizar_condicional(&key, &local, |atual| {
+        let recordes = mesclar(atual, data).map_err(|e| RacedayError::Leitura(format!("❌ {} inválido: {}", key, e)))?;
+        Ok(Some((serde_json::to_string_pretty(&recordes)?, ())))
+    }).await?;
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::error::Error;
+use std::fs;
+
+use crate::history::{classificacao, provisorios, EventoHistorico, Historico};
+use crate::pilots::slug_piloto;
+
+// --- REFRESH-INDEX: ÍNDICE, FEED, SITEMAP E CLASSIFICAÇÃO ---
+// Uso: raceday refresh-index
+// Só lê o histórico local (nenhuma corrida é reprocessada), pensado para rodar no cron:
+//   0 3 * * * cd /opt/raceday && ./raceday refresh-index
+
+const BASE_URL_PADRAO: &str = "https://pub-598608239bfb446e841ac7b4290a0223.r2.dev";
+const ITENS_FEED: usize = 20;
+const MESES: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+pub fn base_url() -> String {
+    env::var("PUBLIC_BASE_URL").unwrap_or_else(|_| BASE_URL_PADRAO.to_string()).trim_end_matches('/').to_string()
+}
+
+pub fn escapar_xml(texto: &str) -> String {
+    texto.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+/// "20251218210742" -> "18 Dec 2025 21:07:42 +0000" (RFC 822, exigido pelo RSS)
+fn data_rfc822(ts: &str) -> String {
+    let parte = |i: usize, j: usize| ts.get(i..j).unwrap_or("00");
+    let mes = parte(4, 6).parse::<usize>().ok().and_then(|m| MESES.get(m.wrapping_sub(1))).unwrap_or(&"Jan");
+    format!("{} {} {} {}:{}:{} +0000", parte(6, 8), mes, parte(0, 4), parte(8, 10), parte(10, 12), parte(12, 14))
+}
+
+fn chave_relatorio(e: &EventoHistorico) -> String {
+    format!("{}.html", crate::storage::base_relatorio(&e.club, &e.track, &e.slug, &e.timestamp))
+}
+
+pub fn link_relatorio(e: &EventoHistorico) -> String {
+    if !e.report_link.is_empty() { return e.report_link.clone(); }
+    format!("{}/{}", base_url(), chave_relatorio(e))
+}
+
+/// Mesmo formato do index.json consumido pelo Dashboard (mais recentes primeiro).
+fn gerar_index(historico: &Historico) -> String {
+    let itens: Vec<serde_json::Value> = historico.eventos.iter().rev().map(|e| serde_json::json!({
+        "id": e.timestamp,
+        "title": e.titulo,
+        "track": e.track,
+        "club": e.club,
+        "date": e.data,
+        "slug": e.slug,
+        "timestamp": e.timestamp,
+        "report_link": link_relatorio(e),
+    })).collect();
+    serde_json::to_string_pretty(&itens).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn gerar_feed(historico: &Historico) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n");
+    xml.push_str("  <title>RaceDay - Resultados</title>\n");
+    xml.push_str(&format!("  <link>{}/index.json</link>\n", base_url()));
+    xml.push_str("  <description>Resultados das provas de autorama</description>\n");
+    for e in historico.eventos.iter().rev().take(ITENS_FEED) {
+        let vencedor = e.resultados.first().map(|r| format!(" — vitória de {}", r.nome)).unwrap_or_default();
+        let link = link_relatorio(e);
+        xml.push_str("  <item>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escapar_xml(&format!("{} ({}){}", e.titulo, e.track, vencedor))));
+        xml.push_str(&format!("    <link>{}</link>\n", escapar_xml(&link)));
+        xml.push_str(&format!("    <guid>{}</guid>\n", escapar_xml(&link)));
+        xml.push_str(&format!("    <pubDate>{}</pubDate>\n", data_rfc822(&e.timestamp)));
+        xml.push_str("  </item>\n");
+    }
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+fn gerar_sitemap(historico: &Historico) -> String {
+    let mut urls: BTreeSet<String> = historico.eventos.iter().map(link_relatorio).collect();
+    for e in &historico.eventos {
+        let club_slug = e.club.to_lowercase().replace(" ", "_");
+        for r in &e.resultados {
+            urls.insert(format!("{}/pilots/{}_{}.html", base_url(), club_slug, slug_piloto(&r.nome)));
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for url in urls {
+        xml.push_str(&format!("  <url><loc>{}</loc></url>\n", escapar_xml(&url)));
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// Classificação de cada clube na temporada corrente do histórico.
+fn gerar_standings(historico: &Historico) -> String {
+    let temporadas: BTreeSet<(String, String)> = historico.eventos.iter()
+        .map(|e| (e.club.clone(), e.timestamp.get(0..4).unwrap_or("").to_string()))
+        .collect();
+    let tabelas: Vec<serde_json::Value> = temporadas.iter().map(|(club, temporada)| {
+        let eventos: Vec<&EventoHistorico> = historico.eventos.iter()
+            .filter(|e| &e.club == club && e.timestamp.starts_with(temporada.as_str()))
+            .collect();
+        let pendentes = provisorios(&eventos);
+        let linhas: Vec<serde_json::Value> = classificacao(&eventos).into_iter().enumerate()
+            .map(|(i, (nome, pontos))| serde_json::json!({
+                "pos": i + 1,
+                "name": nome,
+                "points": (pontos * 10.0).round() / 10.0,
+                "provisional": pendentes.contains(&nome),
+            }))
+            .collect();
+        serde_json::json!({ "club": club, "season": temporada, "races": eventos.len(), "standings": linhas })
+    }).collect();
+    serde_json::to_string_pretty(&tabelas).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Top voltas de todos os tempos por clube e pista.
+fn gerar_leaderboards(historico: &Historico) -> String {
+    let pistas: BTreeSet<(String, String)> = historico.eventos.iter()
+        .map(|e| (e.club.clone(), e.track.clone()))
+        .collect();
+    let tabelas: Vec<serde_json::Value> = pistas.iter().map(|(club, track)| serde_json::json!({
+        "club": club,
+        "track": track,
+        "top_laps": historico.top_voltas(club, track, crate::top_laps::TOP_VOLTAS),
+    })).collect();
+    serde_json::to_string_pretty(&tabelas).unwrap_or_else(|_| "[]".to_string())
+}
+
+pub async fn executar() -> Result<(), Box<dyn Error>> {
+    let mut historico = crate::privacy::historico_publico(&Historico::carregar());
+    if historico.eventos.is_empty() {
+        saida!("ℹ️ Histórico vazio ({}). Nada a atualizar.", crate::history::caminho_historico().display());
+        return Ok(());
+    }
+
+    // Modo privado: índice e feed com links assinados (só em memória, o histórico não é salvo)
+    let privado = crate::private_mode::ativo();
+    if privado {
+        for e in historico.eventos.iter_mut() {
+            e.report_link = crate::private_mode::link(&chave_relatorio(e)).await?;
+        }
+    }
+
+    let local_dir = crate::pasta_saida().join("refresh");
+    fs::create_dir_all(&local_dir)?;
+    let mut artefatos = vec![
+        ("index.json", gerar_index(&historico)),
+        ("feed.xml", gerar_feed(&historico)),
+        ("standings.json", gerar_standings(&historico)),
+        ("leaderboards.json", gerar_leaderboards(&historico)),
+    ];
+    // Resultados privados não devem ser indexados por buscadores
+    if !privado {
+        artefatos.push(("sitemap.xml", gerar_sitemap(&historico)));
+    }
+
+    let mut enviados = 0;
+    for (key, conteudo) in &artefatos {
+        let local = local_dir.join(key);
+        fs::write(&local, conteudo)?;
+        if crate::storage::upload_se_mudou(&local, key).await? { enviados += 1; }
+    }
+
+    crate::site_index::publicar().await?;
+
+    if let Some(projeto) = crate::pages::projeto().filter(|_| enviados > 0) {
+        crate::pages::implantar(&projeto, "refresh-index").await?;
+    }
+
+    saida!("✨ Índice atualizado a partir de {} prova(s): {} de {} arquivo(s) reenviado(s).", historico.eventos.len(), enviados, artefatos.len());
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::pilots::slug_piloto;
+
+// --- CADASTRO DE PILOTOS ---
+// JSON local (PILOT_REGISTRY, padrão history/pilots.json) com dados opcionais de
+// cada piloto, pelo nome usado no cronometrador:
+// { "João Côrtes": { "birthdate": "2010-04-12", "license": "SP-0042", "membership_expiry": "2025-12-31" } }
+// "categories" acrescenta categorias além da idade ("Feminino", "Novatos"...), cada
+// uma com o seu pódio no relatório e a sua tabela na temporada. SUB_RANKINGS
+// escolhe quais categorias ganham sub-ranking (padrão: todas as que aparecerem):
+//   SUB_RANKINGS="Júnior,Master,Feminino"
+
+/// Idade (na data da prova) a partir da qual o piloto deixa de ser júnior / vira master.
+const IDADE_SENIOR: u32 = 18;
+const IDADE_MASTER: u32 = 40;
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct CadastroPiloto {
+    /// Data de nascimento no formato AAAA-MM-DD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birthdate: Option<String>,
+    /// Número de licença / associado
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Validade da associação (AAAA-MM-DD, inclusive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub membership_expiry: Option<String>,
+    /// Outras grafias do nome (outros clubes, apelidos), para unificar o piloto na federação
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Categorias além da de idade (ex.: "Feminino"), para os sub-rankings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub struct Cadastro {
+    pub pilotos: HashMap<String, CadastroPiloto>,
+}
+
+pub fn caminho_cadastro() -> PathBuf {
+    env::var_os("PILOT_REGISTRY").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("history").join("pilots.json"))
+}
+
+impl Cadastro {
+    pub fn carregar() -> Cadastro {
+        fs::read_to_string(caminho_cadastro()).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn piloto(&self, nome: &str) -> Option<&CadastroPiloto> {
+        self.pilotos.get(nome.trim())
+    }
+
+    /// Nome do cadastro para um nome ou apelido qualquer, ignorando acentos e maiúsculas.
+    /// Sem correspondência, devolve o próprio nome.
+    pub fn nome_canonico(&self, nome: &str) -> String {
+        let alvo = slug_piloto(nome);
+        self.pilotos.iter()
+            .find(|(canonico, c)| slug_piloto(canonico) == alvo || c.aliases.iter().any(|a| slug_piloto(a) == alvo))
+            .map(|(canonico, _)| canonico.clone())
+            .unwrap_or_else(|| nome.trim().to_string())
+    }
+}
+
+/// Idade completa na data da prova. Datas em "AAAAMMDD..." (timestamp) ou "AAAA-MM-DD".
+fn idade_em(nascimento: &str, data_prova: &str) -> Option<u32> {
+    let digitos = |s: &str| -> Option<(u32, u32)> {
+        let d: String = s.chars().filter(|c| c.is_ascii_digit()).take(8).collect();
+        if d.len() < 8 { return None; }
+        Some((d[0..4].parse().ok()?, d[4..8].parse().ok()?))
+    };
+    let (ano_n, mes_dia_n) = digitos(nascimento)?;
+    let (ano_p, mes_dia_p) = digitos(data_prova)?;
+    let anos = ano_p.checked_sub(ano_n)?;
+    Some(if mes_dia_p < mes_dia_n { anos.saturating_sub(1) } else { anos })
+}
+
+pub fn categoria(nascimento: &str, data_prova: &str) -> Option<&'static str> {
+    let idade = idade_em(nascimento, data_prova)?;
+    Some(if idade < IDADE_SENIOR { "Júnior" } else if idade < IDADE_MASTER { "Sênior" } else { "Master" })
+}
+
+/// "2025-12-31" / "20251231..." -> 20251231, para comparar datas como números.
+fn data_numerica(data: &str) -> Option<u32> {
+    let d: String = data.chars().filter(|c| c.is_ascii_digit()).take(8).collect();
+    if d.len() < 8 { return None; }
+    d.parse().ok()
+}
+
+/// Verificação de elegibilidade dos pilotos que pontuaram: avisa associações vencidas
+/// (ou sem cadastro) e devolve os nomes cujos pontos ficam provisórios.
+pub fn validar_elegibilidade(nomes: &[String], data_prova: &str) -> Vec<String> {
+    let cadastro = Cadastro::carregar();
+    if cadastro.pilotos.is_empty() { return Vec::new(); }
+    let Some(dia_prova) = data_numerica(data_prova) else { return Vec::new() };
+
+    let mut provisorios = Vec::new();
+    for nome in nomes {
+        match cadastro.piloto(nome) {
+            None => erro!("⚠️ {} não está no cadastro de pilotos ({})", nome, caminho_cadastro().display()),
+            Some(c) => {
+                let licenca = c.license.as_deref().unwrap_or("sem licença");
+                match c.membership_expiry.as_deref() {
+                    Some(validade) if data_numerica(validade).map(|v| v < dia_prova).unwrap_or(false) => {
+                        erro!("⚠️ Associação de {} ({}) vencida em {}: pontos provisórios", nome, licenca, validade);
+                        provisorios.push(nome.clone());
+                    }
+                    None => erro!("⚠️ {} ({}) sem validade de associação no cadastro", nome, licenca),
+                    _ => {}
+                }
+            }
+        }
+    }
+    provisorios
+}
+
+/// Grava a categoria de idade de cada piloto em data["pilots"][id]["category"] e
+/// todas as categorias (idade + "categories" do cadastro) em ["categories"].
+/// Fica no JSON publicado para que o rebuild não dependa do cadastro local.
+pub fn anotar_categorias(data: &mut Value) {
+    let cadastro = Cadastro::carregar();
+    if cadastro.pilotos.is_empty() { return; }
+    let data_prova = data["event"]["timestamp"].as_str().unwrap_or("").to_string();
+    if let Some(pilotos) = data["pilots"].as_object_mut() {
+        for info in pilotos.values_mut() {
+            let Some(c) = info["name"].as_str().and_then(|n| cadastro.piloto(n)).cloned() else { continue };
+            let idade = c.birthdate.as_deref().and_then(|n| categoria(n, &data_prova));
+            if let Some(cat) = idade {
+                info["category"] = Value::String(cat.to_string());
+            }
+            let mut todas: Vec<String> = idade.map(|c| c.to_string()).into_iter().collect();
+            todas.extend(c.categories.iter().map(|c| c.trim().to_string()).filter(|c| !c.is_empty()));
+            if !todas.is_empty() {
+                info["categories"] = serde_json::to_value(&todas).unwrap_or_default();
+            }
+        }
+    }
+}
+
+/// Categorias de um piloto no JSON da prova: ["categories"] ou, em provas
+/// anteriores a ele, só a de idade (["category"]).
+pub fn categorias_piloto(info: &Value) -> Vec<String> {
+    match info["categories"].as_array() {
+        Some(lista) => lista.iter().filter_map(|c| c.as_str()).map(|c| c.to_string()).collect(),
+        None => info["category"].as_str().filter(|c| !c.is_empty()).map(|c| vec![c.to_string()]).unwrap_or_default(),
+    }
+}
+
+/// Categorias que ganham sub-ranking, na ordem de exibição: as de SUB_RANKINGS ou,
+/// sem ela, as de idade seguidas das demais na ordem em que aparecem.
+pub fn categorias_sub_ranking(presentes: &[String]) -> Vec<String> {
+    if let Ok(lista) = env::var("SUB_RANKINGS") {
+        return lista.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect();
+    }
+    let mut ordem: Vec<String> = ["Júnior", "Sênior", "Master"].iter()
+        .map(|c| c.to_string())
+        .filter(|c| presentes.contains(c))
+        .collect();
+    for c in presentes {
+        if !ordem.contains(c) { ordem.push(c.clone()); }
+    }
+    ordem
+}
@@ -0,0 +1,137 @@
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::{primitives::ByteStream, Client};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+// --- RÉPLICAS DOS ARTEFATOS PUBLICADOS ---
+// Tudo que vai para o R2 também é copiado para os destinos de REPLICA_TARGETS,
+// separados por vírgula:
+//   dir:/mnt/nas/raceday            pasta local ou compartilhamento de rede (mesmas chaves)
+//   s3:<bucket>@<endpoint>          outro bucket S3/R2 (mesmas credenciais AWS_*)
+// Uma falha na réplica não interrompe a publicação: entra no resumo do final.
+
+enum Destino {
+    Pasta(PathBuf),
+    Bucket { bucket: String, endpoint: String },
+}
+
+impl Destino {
+    fn nome(&self) -> String {
+        match self {
+            Destino::Pasta(dir) => format!("dir:{}", dir.display()),
+            Destino::Bucket { bucket, endpoint } => format!("s3:{}@{}", bucket, endpoint),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Placar {
+    ok: usize,
+    falhas: usize,
+}
+
+fn destinos() -> &'static [Destino] {
+    static DESTINOS: OnceLock<Vec<Destino>> = OnceLock::new();
+    DESTINOS.get_or_init(|| {
+        env::var("REPLICA_TARGETS").unwrap_or_default()
+            .split(',')
+            .map(|d| d.trim())
+            .filter(|d| !d.is_empty())
+            .filter_map(|d| {
+                let destino = interpretar(d);
+                if destino.is_none() { erro!("⚠️ Destino de réplica inválido em REPLICA_TARGETS: {}", d); }
+                destino
+            })
+            .collect()
+    })
+}
+
+fn interpretar(d: &str) -> Option<Destino> {
+    if let Some(dir) = d.strip_prefix("dir:") {
+        Some(Destino::Pasta(PathBuf::from(dir)))
+    } else if let Some((bucket, endpoint)) = d.strip_prefix("s3:").and_then(|r| r.split_once('@')) {
+        Some(Destino::Bucket { bucket: bucket.to_string(), endpoint: endpoint.to_string() })
+    } else {
+        None
+    }
+}
+
+fn placar() -> &'static Mutex<BTreeMap<String, Placar>> {
+    static PLACAR: OnceLock<Mutex<BTreeMap<String, Placar>>> = OnceLock::new();
+    PLACAR.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+fn anotar(destino: String, sucesso: bool) {
+    let mut placar = placar().lock().unwrap();
+    let entrada = placar.entry(destino).or_default();
+    if sucesso { entrada.ok += 1 } else { entrada.falhas += 1 }
+}
+
+async fn copiar(destino: &Destino, file_path: &Path, target_key: &str, content_type: &str, cache_control: Option<&str>) -> Result<(), Box<dyn Error>> {
+    match destino {
+        Destino::Pasta(dir) => {
+            // Chaves usam '/', o PathBuf converte para o separador do sistema
+            let alvo = target_key.split('/').fold(dir.clone(), |p, parte| p.join(parte));
+            if let Some(pai) = alvo.parent() { fs::create_dir_all(pai)?; }
+            fs::copy(file_path, alvo)?;
+        }
+        Destino::Bucket { bucket, endpoint } => {
+            let config = aws_config::defaults(BehaviorVersion::latest())
+                .endpoint_url(endpoint)
+                .region(aws_config::Region::new("auto"))
+                .load().await;
+            crate::throttle::aguardar().await;
+            Client::new(&config).put_object()
+                .bucket(bucket)
+                .key(target_key)
+                .body(ByteStream::from_path(file_path).await?)
+                .content_type(content_type)
+                .set_cache_control(cache_control.map(|c| c.to_string()))
+                .send().await?;
+        }
+    }
+    Ok(())
+}
+
+/// Copia o arquivo recém-publicado no R2 para todas as réplicas configuradas.
+pub async fn replicar(file_path: &Path, target_key: &str, content_type: &str, cache_control: Option<&str>) {
+    for destino in destinos() {
+        match copiar(destino, file_path, target_key, content_type, cache_control).await {
+            Ok(()) => anotar(destino.nome(), true),
+            Err(e) => {
+                erro!("⚠️ Falha ao replicar {} em {}: {}", target_key, destino.nome(), e);
+                anotar(destino.nome(), false);
+            }
+        }
+    }
+}
+
+/// Envia um arquivo avulso para um destino no mesmo formato de REPLICA_TARGETS
+/// (ex.: o bucket de outro clube), sem passar pelo resumo das réplicas.
+pub async fn enviar(destino: &str, file_path: &Path, target_key: &str, content_type: &str) -> Result<(), Box<dyn Error>> {
+    let destino = interpretar(destino.trim()).ok_or_else(|| format!("destino inválido: {}", destino))?;
+    copiar(&destino, file_path, target_key, content_type, None).await
+}
+
+/// Conta os envios ao backend principal no mesmo resumo das réplicas.
+pub fn anotar_primario(sucesso: bool) {
+    if destinos().is_empty() { return; }
+    if let Ok(backend) = crate::backends::atual() { anotar(format!("{} (principal)", backend.nome()), sucesso); }
+}
+
+pub fn imprimir_resumo() {
+    let placar = placar().lock().unwrap();
+    if placar.is_empty() { return; }
+    saida!("📋 Destinos de publicação:");
+    for (destino, p) in placar.iter() {
+        if p.falhas == 0 {
+            saida!("   ✅ {}: {} arquivo(s)", destino, p.ok);
+        } else {
+            saida!("   ❌ {}: {} ok, {} falha(s)", destino, p.ok, p.falhas);
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use tera::{Context, Tera};
+
+use crate::ranking::PilotoDisplay;
+
+// --- RELATÓRIO HTML DA PROVA (GRÁFICOS + TEMPLATE) ---
+
+/// Cor do `idx`-ésimo piloto (config::cores_grafico), repetindo a lista quando há mais pilotos que cores.
+pub fn cor_grafico(cores: &[String], idx: usize) -> &str {
+    if cores.is_empty() { return "#CCCCCC"; }
+    &cores[idx % cores.len()]
+}
+
+/// Janela da média móvel usada no gráfico de ritmo.
+pub const JANELA_RITMO: usize = 5;
+
+pub fn gerar_json_grafico(ranking: &[PilotoDisplay], slots_count: i64) -> String {
+    let mut datasets = Vec::new();
+
+    let fendas = crate::config::fendas_pista(slots_count.max(0) as usize);
+    let cores = crate::config::cores_grafico();
+
+    for (idx, piloto) in ranking.iter().enumerate() {
+        let mut data_pontos = Vec::new();
+        for s in 1..=slots_count {
+            let voltas = piloto.laps_per_slot.get(&s.to_string())
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(0);
+            data_pontos.push(voltas);
+        }
+
+        datasets.push(serde_json::json!({
+            "label": piloto.nome,
+            "data": data_pontos,
+            "borderColor": cor_grafico(&cores, idx),
+            "backgroundColor": cor_grafico(&cores, idx),
+            "fill": false,
+            "tension": 0.1
+        }));
+    }
+
+    let labels_clube: Vec<String> = fendas.iter()
+        .take(slots_count as usize)
+        .map(|f| f.nome.clone())
+        .collect();
+
+    serde_json::json!({
+        "labels": labels_clube,
+        "datasets": datasets
+    }).to_string()
+}
+
+/// Média móvel simples: um ponto por volta a partir da `janela`-ésima.
+fn media_movel(tempos: &[f64], janela: usize) -> Vec<f64> {
+    if janela == 0 || tempos.len() < janela { return Vec::new(); }
+    tempos.windows(janela)
+        .map(|w| (w.iter().sum::<f64>() / janela as f64 * 1000.0).round() / 1000.0)
+        .collect()
+}
+
+/// Gráfico de ritmo (média móvel de voltas) para visualizar desgaste de pneu/trança e tráfego.
+/// Retorna `None` quando o arquivo não traz os tempos volta a volta.
+pub fn gerar_json_ritmo(ranking: &[PilotoDisplay]) -> Option<String> {
+    let mut datasets = Vec::new();
+    let mut max_pontos = 0;
+    let cores = crate::config::cores_grafico();
+
+    for (idx, piloto) in ranking.iter().enumerate() {
+        let pontos = media_movel(&piloto.lap_times, JANELA_RITMO);
+        if pontos.is_empty() { continue; }
+        max_pontos = max_pontos.max(pontos.len());
+
+        datasets.push(serde_json::json!({
+            "label": piloto.nome,
+            "data": pontos,
+            "borderColor": cor_grafico(&cores, idx),
+            "backgroundColor": cor_grafico(&cores, idx),
+            "fill": false,
+            "pointRadius": 0,
+            "tension": 0.3
+        }));
+    }
+
+    if datasets.is_empty() { return None; }
+
+    // Cada ponto representa a volta que fecha a janela
+    let labels: Vec<String> = (JANELA_RITMO..JANELA_RITMO + max_pontos).map(|v| v.to_string()).collect();
+    Some(serde_json::json!({
+        "labels": labels,
+        "datasets": datasets
+    }).to_string())
+}
+
+/// Gráfico volta a volta de uma sessão (bateria), com o JSON pronto para o Chart.js.
+#[derive(Serialize)]
+pub struct GraficoSessao {
+    pub titulo: String,
+    pub dados: String,
+}
+
+/// Um gráfico por sessão com tempos volta a volta: número da volta x tempo, uma
+/// linha por piloto, na mesma cor do gráfico de voltas por fenda. Sessões sem
+/// `lap_times` ficam de fora (lista vazia se o arquivo não traz os tempos).
+pub fn gerar_json_sessoes(data: &Value, ranking: &[PilotoDisplay]) -> Vec<GraficoSessao> {
+    let cores = crate::config::cores_grafico();
+    let cor = |nome: &str| ranking.iter().position(|p| p.nome == nome)
+        .map(|i| cor_grafico(&cores, i))
+        .unwrap_or("#CCCCCC");
+    let mut graficos = Vec::new();
+
+    for race in data["races"].as_array().into_iter().flatten() {
+        for session in race["sessions"].as_array().into_iter().flatten() {
+            let mut datasets = Vec::new();
+            let mut max_voltas = 0;
+            for s in session["slots"].as_object().into_iter().flatten().map(|(_, s)| s) {
+                let tempos: Vec<f64> = s["lap_times"].as_array().into_iter().flatten()
+                    .filter_map(|t| t.as_f64())
+                    .filter(|t| *t > 0.0)
+                    .collect();
+                if tempos.is_empty() { continue; }
+                max_voltas = max_voltas.max(tempos.len());
+                let nome = s["name"].as_str().unwrap_or("---");
+                datasets.push(serde_json::json!({
+                    "label": nome,
+                    "data": tempos,
+                    "borderColor": cor(nome),
+                    "backgroundColor": cor(nome),
+                    "fill": false,
+                    "pointRadius": 2,
+                    "tension": 0.1
+                }));
+            }
+            if datasets.is_empty() { continue; }
+
+            let labels: Vec<String> = (1..=max_voltas).map(|v| v.to_string()).collect();
+            graficos.push(GraficoSessao {
+                titulo: format!("{} • {} {}", race["name"].as_str().map(|n| n.to_string()).unwrap_or_else(|| crate::i18n::t("texts.heat")), crate::i18n::t("texts.session"), session["session"].as_u64().unwrap_or(0)),
+                dados: serde_json::json!({ "labels": labels, "datasets": datasets }).to_string(),
+            });
+        }
+    }
+    graficos
+}
+
+/// Célula do mapa de calor: voltas do piloto na fenda e a posição relativa ao grid.
+#[derive(Serialize)]
+pub struct CelulaCalor {
+    pub voltas: i64,
+    /// 0 (menos voltas na fenda) a 100 (mais voltas)
+    pub intensidade: u32,
+    /// Cor de fundo já calculada (vermelho -> verde)
+    pub cor: String,
+}
+
+#[derive(Serialize)]
+pub struct LinhaCalor {
+    pub nome: String,
+    pub celulas: Vec<CelulaCalor>,
+}
+
+/// Mapa de calor pilotos x fendas, normalizado por fenda (mínimo e máximo do
+/// grid naquela fenda), para o template só desenhar a tabela.
+pub fn gerar_mapa_calor(ranking: &[PilotoDisplay], slots_count: i64) -> Vec<LinhaCalor> {
+    let voltas = |p: &PilotoDisplay, s: i64| p.laps_per_slot.get(&s.to_string())
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(0);
+    let faixas: Vec<(i64, i64)> = (1..=slots_count).map(|s| {
+        let todas = ranking.iter().map(|p| voltas(p, s));
+        (todas.clone().min().unwrap_or(0), todas.max().unwrap_or(0))
+    }).collect();
+
+    ranking.iter().map(|p| LinhaCalor {
+        nome: p.nome.clone(),
+        celulas: (1..=slots_count).zip(&faixas).map(|(s, (min, max))| {
+            let v = voltas(p, s);
+            let relativo = if max > min { (v - min) as f64 / (max - min) as f64 } else { 1.0 };
+            CelulaCalor {
+                voltas: v,
+                intensidade: (relativo * 100.0).round() as u32,
+                // Matiz 0 (vermelho) a 120 (verde)
+                cor: format!("hsla({:.0}, 70%, 50%, 0.35)", relativo * 120.0),
+            }
+        }).collect(),
+    }).collect()
+}
+
+/// Renderiza o relatório HTML da prova (template "report" já carregado no Tera).
+pub fn renderizar_relatorio(tera: &Tera, data: &Value, ranking: &[PilotoDisplay], best_lap_overall: f64, club: &str, track: &str) -> Result<String, Box<dyn Error>> {
+    Ok(tera.render("report", &contexto_relatorio(data, ranking, best_lap_overall, club, track))?)
+}
+
+/// Contexto do template "report". `ao_vivo` começa falso; o modo live liga para
+/// exibir o aviso de resultado provisório e recarregar a página sozinha.
+pub fn contexto_relatorio(data: &Value, ranking: &[PilotoDisplay], best_lap_overall: f64, club: &str, track: &str) -> Context {
+    // --- CÁLCULO MELHORES TEMPOS POR SLOT ---
+    let mut best_times_per_slot: HashMap<String, String> = HashMap::new();
+    for p in ranking {
+        for (slot, time_str) in &p.times_per_slot {
+            if let Ok(t) = time_str.parse::<f64>() {
+                let current_best_str = best_times_per_slot.get(slot).cloned().unwrap_or("999.999".to_string());
+                let current_best = current_best_str.parse::<f64>().unwrap_or(999.999);
+                if t < current_best && t > 0.0 {
+                    best_times_per_slot.insert(slot.clone(), format!("{:.3}", t));
+                }
+            }
+        }
+    }
+
+    let mut context = Context::new();
+    context.insert("insights", &crate::insights::gerar(ranking, data));
+    context.insert("best_times_per_slot", &best_times_per_slot);
+    context.insert("overall_best_time_formatted", &format!("{:.3}", best_lap_overall));
+    context.insert("club", club); 
+    context.insert("track", track);
+    context.insert("event", &data["event"]); 
+    // metadata.slots sempre preenchido: os templates fazem um laço de 1 a slots
+    let total_fendas = crate::ranking::total_fendas(data);
+    let mut metadata = data["metadata"].clone();
+    if metadata.is_object() || metadata.is_null() {
+        metadata["slots"] = total_fendas.into();
+    }
+    context.insert("metadata", &metadata);
+    context.insert("gallery", &data["gallery"]);
+    context.insert("videos", &data["videos"]);
+    context.insert("ranking_display", ranking); 
+    context.insert("fendas", &crate::config::fendas_pista(total_fendas));
+    context.insert("tema", &crate::theme::tema());
+    context.insert("mais_consistente", &ranking.iter()
+        .filter(|p| p.sessions > 0 && p.largou_valido())
+        .max_by(|a, b| a.indice_consistencia.total_cmp(&b.indice_consistencia))
+        .map(|p| &p.nome));
+    context.insert("classes", &crate::ranking::rankings_por_classe(ranking));
+    context.insert("podios_categoria", &crate::ranking::podios_por_categoria(ranking));
+    context.insert("dados_ritmo", &gerar_json_ritmo(ranking));
+    context.insert("janela_ritmo", &JANELA_RITMO);
+    context.insert("graficos_sessoes", &gerar_json_sessoes(data, ranking));
+    context.insert("mapa_calor", &gerar_mapa_calor(ranking, total_fendas as i64));
+    context.insert("dados_grafico", &gerar_json_grafico(ranking, total_fendas as i64));
+    context.insert("slots", &crate::report_plugins::secoes(&crate::report_plugins::EventoRelatorio {
+        club, track,
+        event: &data["event"],
+        metadata: &data["metadata"],
+        ranking,
+        best_lap: best_lap_overall,
+    }));
+    context.insert("classificacao", crate::classification::criterio().nome());
+    context.insert("correcoes", &data["corrections"]);
+    context.insert("ao_vivo", &false);
+
+    context
+}
@@ -0,0 +1,141 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::ranking::PilotoDisplay;
+
+// --- SEÇÕES EXTRAS DO RELATÓRIO (PLUGINS) ---
+// O relatório tem pontos de inserção nomeados (SLOTS). Cada plugin recebe o
+// modelo da prova e devolve HTML para um desses pontos: sorteio de brindes,
+// anúncio da loja, patrocinadores... sem mexer no renderer.
+//
+// Plugins em Rust implementam SecaoRelatorio e chamam registrar() antes da
+// publicação. Plugins externos (qualquer linguagem) vêm de REPORT_PLUGINS:
+//   REPORT_PLUGINS="after_ranking=python plugins/sorteio.py,footer=bin/loja.exe"
+// O script recebe o JSON do EventoRelatorio no stdin e escreve o HTML no stdout.
+// Saída vazia não insere nada; falha ou tempo esgotado (REPORT_PLUGIN_TIMEOUT_SECS,
+// padrão 10) só gera aviso, o relatório sai sem a seção.
+
+pub const SLOTS: [&str; 5] = ["header", "after_ranking", "after_charts", "after_insights", "footer"];
+
+/// Modelo da prova entregue aos plugins.
+#[derive(Serialize)]
+pub struct EventoRelatorio<'a> {
+    pub club: &'a str,
+    pub track: &'a str,
+    pub event: &'a Value,
+    pub metadata: &'a Value,
+    pub ranking: &'a [PilotoDisplay],
+    pub best_lap: f64,
+}
+
+pub struct Secao {
+    pub slot: String,
+    pub html: String,
+}
+
+pub trait SecaoRelatorio: Send + Sync {
+    fn nome(&self) -> &str;
+    fn gerar(&self, evento: &EventoRelatorio) -> Option<Secao>;
+}
+
+struct Script {
+    slot: String,
+    comando: Vec<String>,
+    timeout: Duration,
+}
+
+impl SecaoRelatorio for Script {
+    fn nome(&self) -> &str {
+        &self.comando[0]
+    }
+
+    fn gerar(&self, evento: &EventoRelatorio) -> Option<Secao> {
+        match self.executar(evento) {
+            Ok(html) if html.trim().is_empty() => None,
+            Ok(html) => Some(Secao { slot: self.slot.clone(), html }),
+            Err(e) => {
+                erro!("⚠️ Plugin de relatório {} falhou: {}", self.nome(), e);
+                None
+            }
+        }
+    }
+}
+
+impl Script {
+    fn executar(&self, evento: &EventoRelatorio) -> Result<String, String> {
+        let entrada = serde_json::to_vec(evento).map_err(|e| e.to_string())?;
+        let mut processo = Command::new(&self.comando[0])
+            .args(&self.comando[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| e.to_string())?;
+
+        // stdin e stdout em threads para o plugin não travar com buffers cheios
+        let mut stdin = processo.stdin.take().ok_or("sem stdin")?;
+        let escrita = std::thread::spawn(move || { let _ = stdin.write_all(&entrada); });
+        let mut stdout = processo.stdout.take().ok_or("sem stdout")?;
+        let leitura = std::thread::spawn(move || { let mut s = String::new(); stdout.read_to_string(&mut s).map(|_| s) });
+
+        let inicio = Instant::now();
+        let status = loop {
+            if let Some(status) = processo.try_wait().map_err(|e| e.to_string())? { break status; }
+            if inicio.elapsed() > self.timeout {
+                let _ = processo.kill();
+                let _ = processo.wait();
+                return Err(format!("tempo limite de {}s excedido", self.timeout.as_secs()));
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        let _ = escrita.join();
+        let html = leitura.join().map_err(|_| "falha ao ler stdout")?.map_err(|e| e.to_string())?;
+        if !status.success() { return Err(format!("saiu com {}", status)); }
+        Ok(html)
+    }
+}
+
+fn registro() -> &'static Mutex<Vec<Box<dyn SecaoRelatorio>>> {
+    static REGISTRO: OnceLock<Mutex<Vec<Box<dyn SecaoRelatorio>>>> = OnceLock::new();
+    REGISTRO.get_or_init(|| {
+        let timeout = Duration::from_secs(env::var("REPORT_PLUGIN_TIMEOUT_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(10));
+        let mut plugins: Vec<Box<dyn SecaoRelatorio>> = Vec::new();
+        for item in env::var("REPORT_PLUGINS").unwrap_or_default().split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let Some((slot, linha)) = item.split_once('=') else {
+                erro!("⚠️ Plugin inválido em REPORT_PLUGINS (esperado slot=comando): {}", item);
+                continue;
+            };
+            let comando = crate::prep::dividir_comando(linha);
+            if !SLOTS.contains(&slot.trim()) || comando.is_empty() {
+                erro!("⚠️ Plugin ignorado: slot \"{}\" desconhecido (use {})", slot.trim(), SLOTS.join(", "));
+                continue;
+            }
+            plugins.push(Box::new(Script { slot: slot.trim().to_string(), comando, timeout }));
+        }
+        Mutex::new(plugins)
+    })
+}
+
+/// Registra uma seção extra implementada em Rust.
+pub fn registrar(plugin: Box<dyn SecaoRelatorio>) {
+    registro().lock().unwrap().push(plugin);
+}
+
+/// HTML de cada slot (todos os SLOTS presentes, vazios quando nenhum plugin respondeu).
+pub fn secoes(evento: &EventoRelatorio) -> HashMap<&'static str, String> {
+    let mut slots: HashMap<&'static str, String> = SLOTS.iter().map(|s| (*s, String::new())).collect();
+    for plugin in registro().lock().unwrap().iter() {
+        let Some(secao) = plugin.gerar(evento) else { continue };
+        match SLOTS.iter().find(|s| **s == secao.slot) {
+            Some(slot) => slots.get_mut(slot).unwrap().push_str(&secao.html),
+            None => erro!("⚠️ Plugin {} usou o slot desconhecido \"{}\"", plugin.nome(), secao.slot),
+        }
+    }
+    slots
+}
@@ -0,0 +1,274 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+
+// --- ESTRUTURA DO JSON DO PREPARADOR ---
+// O ranking lê o JSON da prova campo a campo e, no que faltar, cai em zero ou
+// "---" sem avisar. Os tipos abaixo descrevem o que o parser nativo (ini.rs)
+// produz e o que um preparador externo (prep.rs) precisa entregar. Cada prova é
+// conferida contra eles logo depois do preparo:
+//   sem --strict   o primeiro problema vira aviso, com o caminho do campo
+//   --strict       (RACEDAY_STRICT=1) o problema interrompe a execução
+// Campos a mais são aceitos: o preparador pode mandar classe, categoria, status...
+
+/// Id do piloto: texto no .INI, número em alguns preparadores.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum IdPiloto {
+    Texto(String),
+    Numero(u64),
+}
+
+impl IdPiloto {
+    pub fn texto(&self) -> String {
+        match self {
+            IdPiloto::Texto(s) => s.clone(),
+            IdPiloto::Numero(n) => n.to_string(),
+        }
+    }
+}
+
+/// Gap oficial: texto no .INI ("1.25"), número em alguns preparadores.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Gap {
+    Texto(String),
+    Numero(f64),
+}
+
+#[derive(Deserialize)]
+pub struct Evento {
+    pub title: String,
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub date: String,
+    pub timestamp: String,
+}
+
+#[derive(Deserialize, Default)]
+pub struct Metadados {
+    pub slots: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct EntradaOficial {
+    pub p_id: IdPiloto,
+    pub name: String,
+    pub laps: i64,
+    #[serde(default)]
+    pub gap: Option<Gap>,
+    #[serde(default)]
+    pub best_lap: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct PilotoPrep {
+    pub name: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub guest: Option<bool>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SlotPrep {
+    pub p_id: IdPiloto,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub laps: i64,
+    pub best: f64,
+    #[serde(default)]
+    pub deslots: Option<i64>,
+    #[serde(default)]
+    pub lap_times: Option<Vec<f64>>,
+}
+
+#[derive(Deserialize)]
+pub struct SessaoPrep {
+    pub session: u64,
+    pub slots: BTreeMap<String, SlotPrep>,
+}
+
+#[derive(Deserialize)]
+pub struct BateriaPrep {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub sessions: Vec<SessaoPrep>,
+}
+
+/// O JSON da prova como sai do preparador.
+#[derive(Deserialize)]
+pub struct ProvaPrep {
+    pub event: Evento,
+    #[serde(default)]
+    pub metadata: Metadados,
+    #[serde(default)]
+    pub official_ranking: Option<Vec<EntradaOficial>>,
+    pub pilots: BTreeMap<String, PilotoPrep>,
+    pub races: Vec<BateriaPrep>,
+    #[serde(default)]
+    pub raw_results: Option<BTreeMap<String, BTreeMap<String, Value>>>,
+}
+
+pub fn estrito() -> bool {
+    env::var("RACEDAY_STRICT").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Confere tipos e referências: cada p_id das baterias e do ranking oficial
+/// precisa estar em "pilots". O erro traz o caminho do campo.
+pub fn conferir(data: &Value) -> Result<ProvaPrep, String> {
+    let prova: ProvaPrep = serde_path_to_error::deserialize(data)
+        .map_err(|e| format!("{}: {}", e.path(), e.inner()))?;
+    for (b, bateria) in prova.races.iter().enumerate() {
+        for (s, sessao) in bateria.sessions.iter().enumerate() {
+            for (fenda, slot) in &sessao.slots {
+                if !prova.pilots.contains_key(&slot.p_id.texto()) {
+                    return Err(format!("races[{}].sessions[{}].slots.{}.p_id: piloto {} não está em pilots", b, s, fenda, slot.p_id.texto()));
+                }
+            }
+        }
+    }
+    for (i, entrada) in prova.official_ranking.iter().flatten().enumerate() {
+        if !prova.pilots.contains_key(&entrada.p_id.texto()) {
+            return Err(format!("official_ranking[{}].p_id: piloto {} não está em pilots", i, entrada.p_id.texto()));
+        }
+    }
+    Ok(prova)
+}
+
+/// Confere o JSON do preparador: com --strict o problema é erro; sem ela, aviso.
+pub fn validar(data: &Value) -> Result<(), String> {
+    match conferir(data) {
+        Ok(_) => Ok(()),
+        Err(e) if estrito() => Err(format!("❌ JSON do preparador fora do formato em {}", e)),
+        Err(e) => {
+            erro!("⚠️ JSON do preparador fora do formato em {} (use --strict para interromper)", e);
+            Ok(())
+        }
+    }
+}
+
+// --- VERSÃO DO JSON PUBLICADO (races/) ---
+// O JSON de cada prova em races/ leva "schema_version", para o site do clube e
+// o próprio RaceDay saberem o que esperar:
+//   1   sem o campo: tudo o que foi publicado antes da versão (ProvaPrep)
+//   2   schema_version, metadata.slots sempre preenchido e official_ranking
+//       sempre presente (vazio sem o ranking do cronometrador) (ProvaV2)
+// Ao ler do bucket, versões antigas são adaptadas em memória para a atual e
+// versões mais novas que este binário são recusadas.
+
+pub const SCHEMA_VERSION: u64 = 2;
+
+#[derive(Deserialize)]
+pub struct MetadadosV2 {
+    pub slots: u64,
+}
+
+/// O JSON publicado em races/ na versão 2.
+#[derive(Deserialize)]
+pub struct ProvaV2 {
+    pub schema_version: u64,
+    pub event: Evento,
+    pub metadata: MetadadosV2,
+    pub official_ranking: Vec<EntradaOficial>,
+    pub pilots: BTreeMap<String, PilotoPrep>,
+    pub races: Vec<BateriaPrep>,
+    #[serde(default)]
+    pub raw_results: Option<BTreeMap<String, BTreeMap<String, Value>>>,
+}
+
+/// Versão do documento: o campo, ou 1 para os publicados antes dele.
+pub fn versao(data: &Value) -> Result<u64, String> {
+    match &data["schema_version"] {
+        Value::Null => Ok(1),
+        v => v.as_u64().filter(|n| *n >= 1).ok_or_else(|| format!("schema_version inválido: {}", v)),
+    }
+}
+
+/// Passa o documento da versão 1 para a 2.
+fn v1_para_v2(data: &mut Value) {
+    let slots = crate::ranking::total_fendas(data);
+    if !data["metadata"].is_object() {
+        data["metadata"] = serde_json::json!({});
+    }
+    data["metadata"]["slots"] = slots.into();
+    if !data["official_ranking"].is_array() {
+        data["official_ranking"] = Value::Array(Vec::new());
+    }
+    data["schema_version"] = 2.into();
+}
+
+/// Marca o documento com a versão atual antes de publicar.
+pub fn carimbar(data: &mut Value) {
+    if versao(data).is_ok_and(|v| v < SCHEMA_VERSION) {
+        migrar(data, SCHEMA_VERSION).unwrap_or_default();
+    }
+    data["schema_version"] = SCHEMA_VERSION.into();
+}
+
+/// Leva o documento até a versão `alvo`. Não volta versões nem passa da atual.
+pub fn migrar(data: &mut Value, alvo: u64) -> Result<u64, String> {
+    let mut atual = versao(data)?;
+    if atual > SCHEMA_VERSION {
+        return Err(format!("schema_version {} é mais novo que este RaceDay (até {}): atualize o binário", atual, SCHEMA_VERSION));
+    }
+    if alvo < atual || alvo > SCHEMA_VERSION {
+        return Err(format!("não há migração da versão {} para a {}", atual, alvo));
+    }
+    while atual < alvo {
+        match atual {
+            1 => v1_para_v2(data),
+            _ => unreachable!("migração sem passo para a versão {}", atual),
+        }
+        atual += 1;
+    }
+    Ok(atual)
+}
+
+/// JSON de prova baixado de races/ (`chave`), já na versão atual. Versão mais
+/// nova que este binário é erro; fora do formato da versão atual, só aviso.
+pub fn ler_publicada(chave: &str, bytes: &[u8]) -> Result<Value, String> {
+    let mut data: Value = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+    migrar(&mut data, SCHEMA_VERSION)?;
+    if let Err(e) = serde_path_to_error::deserialize::<_, ProvaV2>(&data) {
+        erro!("⚠️ {} fora do formato da versão {} em {}: {}", chave, SCHEMA_VERSION, e.path(), e.inner());
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{migrar, SCHEMA_VERSION};
+    use serde_json::json;
+
+    #[test]
+    fn migra_v1_para_v2() {
+        let mut data = json!({
+            "event": { "timestamp": "20260101" },
+            "pilots": {},
+            "races": [{ "sessions": [{ "slots": { "1": {}, "4": {} } }] }],
+        });
+        assert_eq!(migrar(&mut data, 2), Ok(2));
+        assert_eq!(data["schema_version"], 2);
+        assert_eq!(data["metadata"]["slots"], 4);
+        assert_eq!(data["official_ranking"], json!([]));
+    }
+
+    #[test]
+    fn recusa_versao_mais_nova() {
+        let mut data = json!({ "schema_version": SCHEMA_VERSION + 1 });
+        assert!(migrar(&mut data, SCHEMA_VERSION).is_err());
+    }
+
+    #[test]
+    fn nao_volta_versao() {
+        let mut data = json!({ "schema_version": 2 });
+        assert!(migrar(&mut data, 1).is_err());
+    }
+}
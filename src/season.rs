@@ -0,0 +1,359 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use tera::{Context, Tera};
+
+use crate::config::Pontuacao;
+use crate::ranking::montar_ranking;
+use crate::storage::slug_chave;
+
+// --- CLASSIFICAÇÃO DA TEMPORADA ---
+// Uso: raceday season --club "DG SLOT RACING" [--year 2025] [--track ESPANHA]
+// Baixa do bucket todos os JSONs de prova do clube no ano (races/<clube>_...),
+// soma por piloto vitórias, pódios, voltas, melhor volta e presença, imprime a
+// tabela e publica reports/seasons/<clube>_<ano>.html ao lado dos relatórios.
+// Os pontos de campeonato seguem a tabela de config::pontuacao (CHAMPIONSHIP_POINTS,
+// FASTEST_LAP_BONUS) e saem por etapa e acumulados também em <clube>_<ano>.json.
+// Com CHAMPIONSHIP_DROP_WORST=N os N piores resultados de cada piloto (etapas em
+// que não correu contam como zero) saem da soma líquida, que define a posição.
+// Pilotos com categoria (Júnior, Master, Feminino... ver registry.rs) também
+// aparecem no sub-ranking da categoria, na ordem da classificação geral.
+// Convidados (entrants.rs), DNS e DSQ ficam fora: quem chegou atrás deles pontua
+// como se tivesse chegado uma posição à frente. DNF pontua pela posição.
+// PENALTY_POINTS (penalties.rs) desconta pontos por penalidade na etapa.
+
+#[derive(Serialize)]
+struct Etapa {
+    numero: usize,
+    titulo: String,
+    data: String,
+    pista: String,
+}
+
+#[derive(Serialize, Default, Clone)]
+struct LinhaTemporada {
+    posicao: usize,
+    nome: String,
+    /// Soma líquida, sem os piores resultados descartados
+    pontos: f64,
+    /// Soma de todas as etapas
+    pontos_brutos: f64,
+    /// Pontos em cada etapa, na ordem do calendário (`None`: não correu)
+    pontos_por_etapa: Vec<Option<f64>>,
+    /// Etapas descartadas na soma líquida
+    descartadas: Vec<bool>,
+    /// Total acumulado depois de cada etapa
+    acumulado: Vec<f64>,
+    provas: usize,
+    vitorias: usize,
+    podios: usize,
+    voltas: i64,
+    melhor_volta: f64,
+    /// Presença em % das provas da temporada
+    presenca: u32,
+    /// Categorias em que o piloto correu na temporada (sub-rankings)
+    categorias: Vec<String>,
+}
+
+/// Tabela de uma categoria: posição dentro dela e a linha da geral.
+#[derive(Serialize)]
+struct SubRanking {
+    categoria: String,
+    linhas: Vec<LinhaSubRanking>,
+}
+
+#[derive(Serialize)]
+struct LinhaSubRanking {
+    posicao: usize,
+    posicao_geral: usize,
+    nome: String,
+    pontos: f64,
+    provas: usize,
+    vitorias: usize,
+    podios: usize,
+}
+
+/// "races/dg_espanha_20250614200000.json" -> "20250614200000"
+pub fn timestamp_da_chave(key: &str) -> Option<&str> {
+    let ts = key.strip_suffix(".json")?.rsplit('_').next()?;
+    (ts.len() == 14 && ts.chars().all(|c| c.is_ascii_digit())).then_some(ts)
+}
+
+pub fn chave_temporada(club: &str, ano: &str) -> String {
+    format!("reports/seasons/{}_{}.html", slug_chave(club), ano)
+}
+
+/// Marca os `n` piores resultados (ausência = 0, descartada primeiro) e calcula a soma líquida.
+fn descartar(linha: &mut LinhaTemporada, n: usize) {
+    let mut ordem: Vec<(usize, f64)> = linha.pontos_por_etapa.iter()
+        .map(|p| p.unwrap_or(0.0))
+        .enumerate()
+        .collect();
+    // Empate: descarta a etapa mais antiga
+    ordem.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    linha.descartadas = vec![false; linha.pontos_por_etapa.len()];
+    for (etapa, _) in ordem.iter().take(n) {
+        linha.descartadas[*etapa] = true;
+    }
+    linha.pontos = ordem.iter().skip(n).map(|(_, p)| p).sum();
+}
+
+fn acumular(provas: &[Value], pontuacao: &Pontuacao) -> Vec<LinhaTemporada> {
+    let mut linhas: HashMap<String, LinhaTemporada> = HashMap::new();
+    for (etapa, data) in provas.iter().enumerate() {
+        let (ranking, _) = montar_ranking(data);
+        for (i, p) in ranking.iter().filter(|p| p.pontua()).enumerate() {
+            let linha = linhas.entry(p.nome.clone()).or_insert_with(|| LinhaTemporada {
+                nome: p.nome.clone(),
+                melhor_volta: f64::MAX,
+                pontos_por_etapa: vec![None; provas.len()],
+                ..Default::default()
+            });
+            let pontos = pontuacao.pontos(i + 1, p.is_overall_best) - p.penalidade.pontos;
+            linha.pontos_por_etapa[etapa] = Some(pontos);
+            linha.pontos_brutos += pontos;
+            linha.provas += 1;
+            if i == 0 { linha.vitorias += 1; }
+            if i < 3 { linha.podios += 1; }
+            linha.voltas += p.total_laps;
+            for c in &p.categorias {
+                if !linha.categorias.contains(c) { linha.categorias.push(c.clone()); }
+            }
+            if let Some(t) = p.best_time.parse::<f64>().ok().filter(|t| *t > 0.0 && *t < linha.melhor_volta) {
+                linha.melhor_volta = t;
+            }
+        }
+    }
+
+    let mut tabela: Vec<LinhaTemporada> = linhas.into_values().collect();
+    for linha in tabela.iter_mut() {
+        descartar(linha, pontuacao.descartes);
+    }
+    tabela.sort_by(|a, b| b.pontos.total_cmp(&a.pontos)
+        .then(b.vitorias.cmp(&a.vitorias))
+        .then(b.podios.cmp(&a.podios))
+        .then(b.voltas.cmp(&a.voltas))
+        .then(a.melhor_volta.total_cmp(&b.melhor_volta)));
+    for (i, linha) in tabela.iter_mut().enumerate() {
+        linha.posicao = i + 1;
+        linha.presenca = (linha.provas as f64 * 100.0 / provas.len() as f64).round() as u32;
+        if linha.melhor_volta == f64::MAX { linha.melhor_volta = 0.0; }
+        linha.acumulado = linha.pontos_por_etapa.iter()
+            .scan(0.0, |soma, p| { *soma += p.unwrap_or(0.0); Some(*soma) })
+            .collect();
+    }
+    tabela
+}
+
+/// Sub-ranking de cada categoria, recortado da classificação geral.
+fn sub_rankings(tabela: &[LinhaTemporada]) -> Vec<SubRanking> {
+    let mut presentes: Vec<String> = Vec::new();
+    for c in tabela.iter().flat_map(|l| &l.categorias) {
+        if !presentes.contains(c) { presentes.push(c.clone()); }
+    }
+    crate::registry::categorias_sub_ranking(&presentes).into_iter()
+        .map(|categoria| {
+            let linhas: Vec<LinhaSubRanking> = tabela.iter()
+                .filter(|l| l.categorias.contains(&categoria))
+                .enumerate()
+                .map(|(i, l)| LinhaSubRanking {
+                    posicao: i + 1,
+                    posicao_geral: l.posicao,
+                    nome: l.nome.clone(),
+                    pontos: l.pontos,
+                    provas: l.provas,
+                    vitorias: l.vitorias,
+                    podios: l.podios,
+                })
+                .collect();
+            SubRanking { categoria, linhas }
+        })
+        .filter(|s| !s.linhas.is_empty())
+        .collect()
+}
+
+fn prefixo(club: &str, track: Option<&str>) -> String {
+    match track {
+        Some(t) => format!("races/{}_{}_", slug_chave(club), slug_chave(t)),
+        None => format!("races/{}_", slug_chave(club)),
+    }
+}
+
+/// JSONs de prova publicados em `prefixo`, um por chave.
+async fn chaves_provas(prefixo: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    Ok(crate::storage::listar_r2(prefixo).await?.into_iter()
+        .filter(|k| k.as_str() != crate::manifest::CHAVE)
+        .filter(|k| timestamp_da_chave(k).is_some())
+        .collect())
+}
+
+/// Baixa as provas, já anonimizadas e em ordem cronológica (as chaves vêm
+/// ordenadas por pista antes da data).
+async fn baixar_provas(chaves: &[String]) -> Result<Vec<Value>, Box<dyn Error>> {
+    let mut provas = Vec::new();
+    for key in chaves {
+        let Some(bytes) = crate::storage::baixar_r2(key).await? else { continue };
+        match crate::schema::ler_publicada(key, &bytes) {
+            Ok(mut data) => {
+                crate::privacy::anonimizar_prova(&mut data);
+                provas.push((timestamp_da_chave(key).unwrap_or_default().to_string(), data));
+            }
+            Err(e) => erro!("⚠️ JSON inválido em {}: {}", key, e),
+        }
+    }
+    provas.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(provas.into_iter().map(|(_, data)| data).collect())
+}
+
+/// Campeão de uma temporada do clube, pela mesma classificação do `season`.
+#[derive(Serialize)]
+pub struct Campeao {
+    pub ano: String,
+    pub nome: String,
+    pub pontos: f64,
+    pub provas: usize,
+    /// Temporada do ano corrente: o líder ainda pode mudar
+    pub em_andamento: bool,
+}
+
+/// Campeões de todas as temporadas com provas do clube no bucket, da mais recente
+/// para a mais antiga.
+pub async fn campeoes(club: &str) -> Result<Vec<Campeao>, Box<dyn Error>> {
+    let mut por_ano: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for key in chaves_provas(&prefixo(club, None)).await? {
+        let ano = timestamp_da_chave(&key).and_then(|ts| ts.get(0..4)).unwrap_or_default().to_string();
+        por_ano.entry(ano).or_default().push(key);
+    }
+    let ano_atual = crate::history::agora_timestamp().get(0..4).unwrap_or_default().to_string();
+    let mut campeoes = Vec::new();
+    for (ano, chaves) in por_ano.into_iter().rev() {
+        let provas = baixar_provas(&chaves).await?;
+        if let Some(lider) = acumular(&provas, &crate::config::pontuacao()).into_iter().next() {
+            campeoes.push(Campeao { em_andamento: ano == ano_atual, ano, nome: lider.nome, pontos: lider.pontos, provas: provas.len() });
+        }
+    }
+    Ok(campeoes)
+}
+
+pub async fn executar(ano: &str, club: &str, track: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let prefixo = prefixo(club, track);
+    let mut chaves: Vec<String> = chaves_provas(&prefixo).await?.into_iter()
+        .filter(|k| timestamp_da_chave(k).is_some_and(|ts| ts.starts_with(ano)))
+        .collect();
+    chaves.sort();
+    if chaves.is_empty() {
+        return Err(format!("❌ Nenhuma prova de {} em {} no bucket ({}*)", club, ano, prefixo).into());
+    }
+
+    let provas = baixar_provas(&chaves).await?;
+    let etapas: Vec<Etapa> = provas.iter().enumerate().map(|(i, data)| Etapa {
+        numero: i + 1,
+        titulo: data["event"]["title"].as_str().unwrap_or("Corrida").to_string(),
+        data: data["event"]["date"].as_str().unwrap_or("").to_string(),
+        pista: data["track"].as_str().unwrap_or("").to_string(),
+    }).collect();
+
+    let tabela = acumular(&provas, &crate::config::pontuacao());
+    saida!("🏆 Temporada {} de {} — {} prova(s)", ano, club, provas.len());
+    saida!("{:>3}  {:<28} {:>7} {:>7} {:>5} {:>4} {:>4} {:>7} {:>9} {:>5}", "Pos", "Piloto", "Líq", "Bruto", "Provas", "Vit", "Pód", "Voltas", "Melhor", "Pres");
+    for l in &tabela {
+        saida!("{:>3}. {:<28} {:>7} {:>7} {:>5} {:>4} {:>4} {:>7} {:>9.3} {:>4}%", l.posicao, l.nome, l.pontos, l.pontos_brutos, l.provas, l.vitorias, l.podios, l.voltas, l.melhor_volta, l.presenca);
+    }
+    let descartes = crate::config::pontuacao().descartes;
+    if descartes > 0 {
+        saida!("   Líquido: descartados os {} pior(es) resultado(s) de cada piloto", descartes);
+    }
+    let subs = sub_rankings(&tabela);
+    for s in &subs {
+        saida!("\n🏅 {}", s.categoria);
+        for l in &s.linhas {
+            saida!("{:>3}. {:<28} {:>7}  (geral: {}º)", l.posicao, l.nome, l.pontos, l.posicao_geral);
+        }
+    }
+
+    let mut tera = Tera::default();
+    crate::assets::registrar(&mut tera);
+    crate::templates::carregar(&mut tera, "season.html", "season")?;
+    let mut context = Context::new();
+    context.insert("clube", club);
+    context.insert("pista", &track.unwrap_or(""));
+    context.insert("ano", ano);
+    context.insert("total_provas", &provas.len());
+    context.insert("etapas", &etapas);
+    context.insert("descartes", &descartes);
+    context.insert("classificacao", &tabela);
+    context.insert("sub_rankings", &subs);
+
+    let local_dir = crate::pasta_saida().join("seasons");
+    fs::create_dir_all(&local_dir)?;
+    let key = chave_temporada(club, ano);
+    let local = local_dir.join(format!("{}_{}.html", slug_chave(club), ano));
+    fs::write(&local, tera.render("season", &context)?)?;
+    crate::storage::upload_se_mudou(&local, &key).await?;
+
+    let standings = serde_json::json!({ "club": club, "season": ano, "rounds": etapas, "standings": tabela, "sub_standings": subs });
+    let local_json = local.with_extension("json");
+    fs::write(&local_json, serde_json::to_string_pretty(&standings)?)?;
+    crate::storage::upload_se_mudou(&local_json, &key.replace(".html", ".json")).await?;
+
+    saida!("☁️ Classificação publicada: {}/{}", crate::refresh::base_url(), key);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn linha(pontos: &[Option<f64>]) -> LinhaTemporada {
+        LinhaTemporada { pontos_por_etapa: pontos.to_vec(), ..Default::default() }
+    }
+
+    /// Etapa com o resultado oficial na ordem dada (mais voltas primeiro).
+    fn etapa(nomes: &[&str]) -> Value {
+        let pilots: serde_json::Map<String, Value> = nomes.iter().enumerate()
+            .map(|(i, n)| ((i + 1).to_string(), json!({ "name": n })))
+            .collect();
+        let oficial: Vec<Value> = nomes.iter().enumerate()
+            .map(|(i, n)| json!({ "p_id": (i + 1).to_string(), "name": n, "laps": 100 - i as i64, "gap": "0", "best_lap": 0.0 }))
+            .collect();
+        json!({ "metadata": { "slots": 0 }, "pilots": pilots, "official_ranking": oficial, "races": [] })
+    }
+
+    #[test]
+    fn descarta_os_piores_e_ausencias_primeiro() {
+        let mut l = linha(&[Some(10.0), None, Some(6.0), Some(8.0)]);
+        descartar(&mut l, 2);
+        assert_eq!(l.descartadas, vec![false, true, true, false]);
+        assert_eq!(l.pontos, 18.0);
+    }
+
+    #[test]
+    fn empate_descarta_a_etapa_mais_antiga() {
+        let mut l = linha(&[Some(5.0), Some(9.0), Some(5.0)]);
+        descartar(&mut l, 1);
+        assert_eq!(l.descartadas, vec![true, false, false]);
+        assert_eq!(l.pontos, 14.0);
+
+        // Sem descartes a soma líquida é a bruta
+        let mut l = linha(&[Some(5.0), None]);
+        descartar(&mut l, 0);
+        assert_eq!((l.descartadas, l.pontos), (vec![false, false], 5.0));
+    }
+
+    #[test]
+    fn soma_liquida_define_a_posicao() {
+        let provas = vec![etapa(&["Ana", "Bia"]), etapa(&["Ana", "Bia"]), etapa(&["Bia"])];
+        let pontuacao = Pontuacao { posicoes: vec![10.0, 6.0], melhor_volta: 0.0, descartes: 1 };
+        let tabela = acumular(&provas, &pontuacao);
+
+        // Ana 10+10 (ausência descartada), Bia 6+6+10 = 22 bruto, 16 líquido
+        let resumo: Vec<(&str, f64, f64)> = tabela.iter().map(|l| (l.nome.as_str(), l.pontos, l.pontos_brutos)).collect();
+        assert_eq!(resumo, vec![("Ana", 20.0, 20.0), ("Bia", 16.0, 22.0)]);
+        assert_eq!(tabela[0].descartadas, vec![false, false, true]);
+        assert_eq!(tabela[0].acumulado, vec![10.0, 20.0, 20.0]);
+        assert_eq!((tabela[1].posicao, tabela[1].presenca, tabela[1].vitorias), (2, 100, 1));
+    }
+}
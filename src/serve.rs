@@ -0,0 +1,195 @@
+use std::error::Error;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::audit;
+use crate::auth::{Credenciais, Identidade, Papel};
+use crate::history::{classificacao, Historico};
+
+// --- SERVE: SERVIDOR LOCAL (REDE DO EVENTO) ---
+// Uso: raceday serve [--port 8080] [--bind 127.0.0.1]
+//   /                      arquivos gerados em temp_out/ (relatórios, páginas)
+//   /api/races             lista de provas do histórico
+//   /api/races/<timestamp> resultado de uma prova
+//   /api/standings         classificação por clube
+//   POST /api/races/<timestamp>/penalties    {"pilot", "laps", "reason"}          (papel control)
+//   POST /api/races/<timestamp>/corrections  {"pilot", "total_laps"?, "best_lap"?, "reason"} (papel control)
+// Autenticação e papéis em auth.rs; toda escrita vai para a trilha de auditoria.
+
+pub const PORTA_PADRAO: u16 = 8080;
+
+type Resposta = Response<Cursor<Vec<u8>>>;
+
+fn cabecalho(nome: &str, valor: &str) -> Header {
+    Header::from_bytes(nome.as_bytes(), valor.as_bytes()).expect("cabeçalho HTTP inválido")
+}
+
+fn json(status: u16, corpo: &serde_json::Value) -> Resposta {
+    Response::from_data(corpo.to_string().into_bytes())
+        .with_status_code(status)
+        .with_header(cabecalho("Content-Type", "application/json; charset=utf-8"))
+}
+
+fn nao_encontrado() -> Resposta {
+    json(404, &serde_json::json!({ "error": "não encontrado" }))
+}
+
+fn tipo_arquivo(caminho: &Path) -> &'static str {
+    match caminho.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("json") => "application/json; charset=utf-8",
+        Some("xml") => "application/xml",
+        Some("css") => "text/css",
+        Some("jpg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("csv") => "text/csv; charset=utf-8",
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Resolve a URL dentro da pasta de saída, recusando "..", raiz e prefixos de unidade.
+fn arquivo_local(raiz: &Path, url: &str) -> Option<PathBuf> {
+    let relativo = Path::new(url.split('?').next()?.trim_start_matches('/'));
+    if relativo.components().any(|c| !matches!(c, Component::Normal(_))) { return None; }
+    let caminho = raiz.join(relativo);
+    let caminho = if caminho.is_dir() { caminho.join("index.html") } else { caminho };
+    caminho.is_file().then_some(caminho)
+}
+
+fn rota_api(caminho: &str) -> Option<Resposta> {
+    let historico = Historico::carregar();
+    let resposta = match caminho.trim_end_matches('/') {
+        "/api/races" => json(200, &serde_json::json!(historico.eventos.iter().rev().map(|e| serde_json::json!({
+            "timestamp": e.timestamp,
+            "club": e.club,
+            "track": e.track,
+            "title": e.titulo,
+            "date": e.data,
+        })).collect::<Vec<_>>())),
+        "/api/standings" => {
+            let clubes: std::collections::BTreeSet<&str> = historico.eventos.iter().map(|e| e.club.as_str()).collect();
+            json(200, &serde_json::json!(clubes.into_iter().map(|club| {
+                let eventos: Vec<_> = historico.eventos.iter().filter(|e| e.club == club).collect();
+                serde_json::json!({ "club": club, "standings": classificacao(&eventos) })
+            }).collect::<Vec<_>>()))
+        }
+        outro => {
+            let ts = outro.strip_prefix("/api/races/")?;
+            match historico.eventos.iter().find(|e| e.timestamp == ts) {
+                Some(e) => json(200, &serde_json::json!(e)),
+                None => nao_encontrado(),
+            }
+        }
+    };
+    Some(resposta)
+}
+
+/// Aplica uma penalidade ou correção no histórico e registra na auditoria.
+fn alterar_prova(ts: &str, acao: &str, corpo: &serde_json::Value, identidade: &Identidade) -> Result<Resposta, Box<dyn Error>> {
+    let Some(piloto) = corpo["pilot"].as_str() else {
+        return Ok(json(400, &serde_json::json!({ "error": "campo \"pilot\" obrigatório" })));
+    };
+    let mut historico = Historico::carregar();
+    let Some(evento) = historico.eventos.iter_mut().find(|e| e.timestamp == ts) else { return Ok(nao_encontrado()) };
+    let Some(resultado) = evento.resultados.iter_mut().find(|r| r.nome == piloto) else {
+        return Ok(json(404, &serde_json::json!({ "error": format!("piloto '{}' não está nesta prova", piloto) })));
+    };
+
+    match acao {
+        "penalties" => {
+            let Some(voltas) = corpo["laps"].as_i64().filter(|v| *v > 0) else {
+                return Ok(json(400, &serde_json::json!({ "error": "\"laps\" deve ser um inteiro positivo" })));
+            };
+            resultado.total_laps -= voltas;
+            resultado.penalidades += voltas;
+        }
+        _ => {
+            if let Some(voltas) = corpo["total_laps"].as_i64() { resultado.total_laps = voltas; }
+            if let Some(tempo) = corpo["best_lap"].as_f64() { resultado.best_lap = tempo; }
+        }
+    }
+    evento.reclassificar();
+    let atualizado = serde_json::json!(evento);
+
+    // Auditoria antes de salvar: uma alteração sem registro é pior que uma alteração perdida
+    audit::registrar(&identidade.nome, acao, ts, corpo)?;
+    historico.salvar()?;
+    saida!("🛡️ {} aplicou '{}' na prova {} ({})", identidade.nome, acao, ts, piloto);
+    Ok(json(200, &atualizado))
+}
+
+fn atender(mut request: Request, credenciais: &Credenciais, raiz: &Path) -> Result<(), Box<dyn Error>> {
+    let authorization = request.headers().iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .map(|h| h.value.as_str().to_string());
+
+    let Some(identidade) = credenciais.autenticar(authorization.as_deref()) else {
+        let resposta = json(401, &serde_json::json!({ "error": "não autorizado" }))
+            .with_header(cabecalho("WWW-Authenticate", "Basic realm=\"raceday\""));
+        return Ok(request.respond(resposta)?);
+    };
+
+    let url = request.url().to_string();
+    let caminho = url.split('?').next().unwrap_or("/");
+
+    if *request.method() == Method::Post {
+        let alvo = caminho.strip_prefix("/api/races/").and_then(|r| r.trim_end_matches('/').split_once('/'));
+        let Some((ts, acao @ ("penalties" | "corrections"))) = alvo else {
+            return Ok(request.respond(nao_encontrado())?);
+        };
+        if identidade.papel != Papel::Controle {
+            return Ok(request.respond(json(403, &serde_json::json!({ "error": "token somente leitura" })))?);
+        }
+        let mut corpo = String::new();
+        request.as_reader().read_to_string(&mut corpo)?;
+        let resposta = match serde_json::from_str::<serde_json::Value>(&corpo) {
+            Ok(corpo) => alterar_prova(ts, acao, &corpo, &identidade)?,
+            Err(e) => json(400, &serde_json::json!({ "error": format!("JSON inválido: {}", e) })),
+        };
+        return Ok(request.respond(resposta)?);
+    }
+
+    if caminho.starts_with("/api/") {
+        let resposta = rota_api(caminho).unwrap_or_else(nao_encontrado);
+        return Ok(request.respond(resposta)?);
+    }
+
+    match arquivo_local(raiz, caminho) {
+        Some(arquivo) => {
+            let resposta = Response::from_data(fs::read(&arquivo)?)
+                .with_header(cabecalho("Content-Type", tipo_arquivo(&arquivo)));
+            Ok(request.respond(resposta)?)
+        }
+        None => Ok(request.respond(nao_encontrado())?),
+    }
+}
+
+pub async fn executar(bind: &str, porta: u16) -> Result<(), Box<dyn Error>> {
+    let credenciais = Credenciais::carregar();
+    let bind = bind.to_string();
+
+    if !credenciais.configuradas() && bind != "127.0.0.1" && bind != "localhost" {
+        return Err(format!("❌ Recusando abrir {} sem autenticação: defina SERVE_USERS ou SERVE_TOKENS", bind).into());
+    }
+
+    let raiz = crate::pasta_saida();
+    let server = Server::http((bind.as_str(), porta)).map_err(|e| format!("❌ Não foi possível abrir {}:{}: {}", bind, porta, e))?;
+    saida!("🌐 Servindo {} em http://{}:{} ({})", raiz.display(), bind, porta,
+        if credenciais.configuradas() { "com autenticação" } else { "somente local, sem autenticação" });
+
+    // tiny_http é bloqueante: o laço roda fora das threads do runtime async
+    tokio::task::spawn_blocking(move || {
+        for request in server.incoming_requests() {
+            if let Err(e) = atender(request, &credenciais, &raiz) {
+                erro!("⚠️ Falha ao responder requisição: {}", e);
+            }
+        }
+    }).await?;
+    Ok(())
+}
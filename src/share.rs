@@ -0,0 +1,50 @@
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::i18n::{t, tf};
+use crate::ranking::PilotoDisplay;
+
+// --- TEXTO PARA O WHATSAPP ---
+// Resumo curto (top 5, melhor volta e link do relatório) para colar no grupo
+// logo depois da prova. Usa a formatação do WhatsApp (*negrito*, _itálico_),
+// é impresso no terminal e gravado em <pasta de saída>/whatsapp.txt.
+
+const TOP: usize = 5;
+
+pub fn gerar(data: &Value, ranking: &[PilotoDisplay], club: &str, report_url: &str) -> String {
+    let titulo = data["event"]["title"].as_str().map(|s| s.to_string()).unwrap_or_else(|| t("texts.race"));
+    let mut linhas = vec![format!("🏁 *{}* — {}", titulo, club)];
+    if let Some(date) = data["event"]["date"].as_str().filter(|d| !d.is_empty()) {
+        linhas.push(format!("_{}_", date));
+    }
+    linhas.push(String::new());
+    for (i, p) in ranking.iter().take(TOP).enumerate() {
+        let posicao = match i {
+            0 => "🥇".to_string(),
+            1 => "🥈".to_string(),
+            2 => "🥉".to_string(),
+            _ => format!("{}º", i + 1),
+        };
+        let posicao = if p.status.is_empty() { posicao } else { p.status.clone() };
+        linhas.push(format!("{} {} — {}", posicao, p.nome, tf("texts.n_laps", &[("voltas", p.total_laps.to_string())])));
+    }
+    if let Some(top) = ranking.iter().find(|p| p.is_overall_best) {
+        linhas.push(String::new());
+        linhas.push(format!("⏱️ {}: *{}* {}s", t("texts.best_lap"), top.nome, top.best_time));
+    }
+    linhas.push(String::new());
+    linhas.push(format!("📄 {}: {}", t("texts.full_result"), report_url));
+    linhas.join("\n") + "\n"
+}
+
+/// Grava o texto em whatsapp.txt e o imprime para copiar. Retorna o arquivo.
+pub fn salvar(data: &Value, ranking: &[PilotoDisplay], club: &str, report_url: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let texto = gerar(data, ranking, club, report_url);
+    let local = crate::pasta_saida().join("whatsapp.txt");
+    fs::write(&local, &texto)?;
+    saida!("\n📬 Texto para o WhatsApp ({}):\n", local.display());
+    saida!("{}", texto);
+    Ok(local)
+}
@@ -0,0 +1,139 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::error::Error;
+use std::fs;
+use tera::{Context, Tera};
+
+use crate::history::{EventoHistorico, Historico};
+
+// --- ÍNDICE DOS RELATÓRIOS (index.html) ---
+// Cada prova gera um HTML isolado; o index.html na raiz do bucket é a porta de
+// entrada. Ele é refeito a partir da listagem de reports/ (não do histórico
+// local, que pode estar incompleto em outro PC), agrupado por clube e pista,
+// das provas mais recentes para as mais antigas. O histórico só completa os
+// nomes: clube, pista e título de cada chave.
+
+#[derive(Serialize)]
+struct Clube {
+    nome: String,
+    pistas: Vec<Pista>,
+    /// Ratings Elo do clube (vazio com RATING_ENABLED desligado)
+    ratings: Vec<crate::rating::LinhaRating>,
+    /// Chave do Hall da Fama, quando o `portal` já o publicou
+    hall: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Pista {
+    nome: String,
+    provas: Vec<Prova>,
+}
+
+#[derive(Serialize)]
+struct Prova {
+    data: String,
+    titulo: String,
+    link: String,
+    #[serde(skip)]
+    timestamp: String,
+}
+
+/// Relatórios principais: reports/<base>.html, sem a escala de fiscais, a versão
+/// de impressão, as visões extras (views.rs) nem as fotos da galeria.
+fn e_relatorio(key: &str) -> bool {
+    key.strip_prefix("reports/")
+        .map(|nome| !nome.contains('/') && nome.ends_with(".html")
+            && !nome.ends_with("_fiscais.html") && !nome.ends_with("_impressao.html")
+            && !crate::views::e_visao(nome))
+        .unwrap_or(false)
+}
+
+/// "reports/dg_spain_gp_20251218210742.html" -> ("dg_spain_gp", "20251218210742")
+fn separar_timestamp(key: &str) -> (String, String) {
+    let nome = key.trim_start_matches("reports/").trim_end_matches(".html");
+    match nome.rsplit_once('_') {
+        Some((resto, ts)) if ts.len() == 14 && ts.chars().all(|c| c.is_ascii_digit()) => (resto.to_string(), ts.to_string()),
+        _ => (nome.to_string(), String::new()),
+    }
+}
+
+/// "20251218210742" -> "2025-12-18"
+fn data_do_timestamp(ts: &str) -> String {
+    match (ts.get(0..4), ts.get(4..6), ts.get(6..8)) {
+        (Some(a), Some(m), Some(d)) => format!("{}-{}-{}", a, m, d),
+        _ => String::new(),
+    }
+}
+
+fn agrupar(chaves: &[String], historico: &Historico) -> Vec<Clube> {
+    let por_chave: HashMap<String, &EventoHistorico> = historico.eventos.iter()
+        .map(|e| (format!("{}.html", crate::storage::base_relatorio(&e.club, &e.track, &e.slug, &e.timestamp)), e))
+        .collect();
+
+    let mut grupos: BTreeMap<String, BTreeMap<String, Vec<Prova>>> = BTreeMap::new();
+    for key in chaves.iter().filter(|k| e_relatorio(k)) {
+        let (club, track, prova) = match por_chave.get(key) {
+            Some(e) => (e.club.clone(), e.track.clone(), Prova {
+                data: if e.data.is_empty() { data_do_timestamp(&e.timestamp) } else { e.data.clone() },
+                titulo: e.titulo.clone(),
+                link: key.clone(),
+                timestamp: e.timestamp.clone(),
+            }),
+            // Publicado por outro PC: clube e pista não dá para separar do nome com segurança
+            None => {
+                let (nome, ts) = separar_timestamp(key);
+                ("Outros".to_string(), String::new(), Prova {
+                    data: data_do_timestamp(&ts),
+                    titulo: nome,
+                    link: key.clone(),
+                    timestamp: ts,
+                })
+            }
+        };
+        grupos.entry(club).or_default().entry(track).or_default().push(prova);
+    }
+
+    grupos.into_iter().map(|(nome, pistas)| Clube {
+        nome,
+        ratings: Vec::new(),
+        hall: None,
+        pistas: pistas.into_iter().map(|(nome, mut provas)| {
+            provas.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+            Pista { nome, provas }
+        }).collect(),
+    }).collect()
+}
+
+/// Lista reports/ no armazenamento, regera o index.html e o envia se mudou.
+/// Retorna o número de relatórios listados.
+pub async fn publicar() -> Result<usize, Box<dyn Error>> {
+    let chaves = crate::storage::listar_r2("reports/").await?;
+    let historico = crate::privacy::historico_publico(&Historico::carregar());
+    let mut clubes = agrupar(&chaves, &historico);
+    for clube in clubes.iter_mut().filter(|c| c.nome != "Outros") {
+        if crate::rating::ativo() {
+            clube.ratings = crate::rating::tabela(&crate::rating::carregar(&clube.nome).await?);
+        }
+        let hall = crate::portal::chave_hall(&clube.nome);
+        if crate::storage::listar_r2(&hall).await?.contains(&hall) {
+            clube.hall = Some(hall);
+        }
+    }
+    let total: usize = clubes.iter().flat_map(|c| &c.pistas).map(|p| p.provas.len()).sum();
+
+    let mut tera = Tera::default();
+    crate::assets::registrar(&mut tera);
+    crate::templates::carregar(&mut tera, "index.html", "index")?;
+    let mut context = Context::new();
+    context.insert("titulo", &env::var("CLUB").unwrap_or_else(|_| "RaceDay".to_string()));
+    context.insert("total", &total);
+    context.insert("clubes", &clubes);
+
+    let local_dir = crate::pasta_saida();
+    fs::create_dir_all(&local_dir)?;
+    let local = local_dir.join("index.html");
+    fs::write(&local, tera.render("index", &context)?)?;
+    crate::storage::upload_se_mudou(&local, "index.html").await?;
+    Ok(total)
+}
@@ -0,0 +1,240 @@
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::Client;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+use crate::error::RacedayError;
+
+// --- INFRAESTRUTURA DE NUVEM (R2) ---
+
+// Montar a config da AWS é caro (lê o ambiente, credenciais, conectores):
+// um único Client é criado na primeira chamada e compartilhado pelo processo.
+static CLIENTE: OnceCell<Client> = OnceCell::const_new();
+
+/// Client do R2 e bucket de destino. Variável ausente é erro de entrada.
+pub async fn cliente_r2() -> Result<(Client, String), RacedayError> {
+    let bucket = crate::private_mode::bucket()?;
+    let client = CLIENTE.get_or_try_init(|| async {
+        let endpoint = env::var("R2_ENDPOINT")
+            .map_err(|_| RacedayError::Entrada("❌ R2_ENDPOINT não definida".to_string()))?;
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .endpoint_url(endpoint)
+            .region(aws_config::Region::new("auto"))
+            .load().await;
+        Ok::<_, RacedayError>(Client::new(&config))
+    }).await?;
+
+    // O Client é um Arc por dentro: clonar é barato
+    Ok((client.clone(), bucket))
+}
+
+/// Envia o arquivo para a chave informada no backend configurado (R2 por padrão)
+/// e retorna quanto tempo o envio levou.
+pub async fn upload_to_r2(file_path: &Path, target_key: &str) -> Result<Duration, RacedayError> {
+    let inicio = Instant::now();
+    let backend = crate::backends::atual()?;
+
+    let content_type = match file_path.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html",
+        Some("xml") => "application/xml",
+        Some("jpg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("csv") => "text/csv; charset=utf-8",
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        Some("gz") => "application/gzip",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        _ => "application/json",
+    };
+    // Assets têm hash no nome (ver assets.rs): podem ficar em cache para sempre
+    let cache_control = target_key.starts_with("assets/").then_some("public, max-age=31536000, immutable");
+    detalhe!("☁️ {} -> {} ({}, {} bytes)", file_path.display(), target_key, content_type,
+        fs::metadata(file_path).map(|m| m.len()).unwrap_or(0));
+
+    if crate::offline::ativo() {
+        backend.enviar(file_path, target_key, content_type, cache_control).await?;
+        saida!("📴 Gravado localmente: {}", crate::offline::caminho(target_key).display());
+        return Ok(inicio.elapsed());
+    }
+
+    let (tentativas, base) = config_tentativas();
+    let mut tentativa = 0;
+    loop {
+        tentativa += 1;
+        crate::throttle::aguardar().await;
+        match backend.enviar(file_path, target_key, content_type, cache_control).await {
+            Ok(()) => break,
+            // Configuração faltando não melhora com nova tentativa
+            Err(e) if matches!(e.downcast_ref::<RacedayError>(), Some(RacedayError::Entrada(_))) => {
+                return Err(RacedayError::from(e));
+            }
+            Err(e) if tentativa >= tentativas => {
+                crate::replication::anotar_primario(false);
+                // O `main` usa a variante para sair com código próprio: o relatório foi
+                // gerado localmente, só a publicação falhou
+                return Err(RacedayError::Armazenamento { key: target_key.to_string(), tentativas, erro: e.to_string() });
+            }
+            Err(e) => {
+                let espera = espera_com_jitter(base, tentativa);
+                erro!("⚠️ Falha ao enviar {} (tentativa {}/{}): {}. Nova tentativa em {:.1}s",
+                    target_key, tentativa, tentativas, e, espera.as_secs_f64());
+                tokio::time::sleep(espera).await;
+            }
+        }
+    }
+    crate::replication::anotar_primario(true);
+
+    let duracao = inicio.elapsed();
+    // Com a chave como campo, para o --log-format json listar o que foi enviado
+    tracing::info!(key = target_key, duracao_ms = duracao.as_millis() as u64, "{}",
+        crate::console::texto(&format!("✅ Sincronizado em {}: {} ({:.1}s)", backend.nome(), target_key, duracao.as_secs_f64())));
+    crate::replication::replicar(file_path, target_key, content_type, cache_control).await;
+    Ok(duracao)
+}
+
+// --- NOVAS TENTATIVAS (WI-FI DA PISTA) ---
+//   R2_RETRIES        número total de tentativas por arquivo (padrão 4)
+//   R2_RETRY_BASE_MS  espera antes da 2ª tentativa, dobrando a cada falha (padrão 500)
+
+const TENTATIVAS_PADRAO: u32 = 4;
+const ESPERA_BASE_PADRAO_MS: u64 = 500;
+const ESPERA_MAXIMA: Duration = Duration::from_secs(30);
+
+fn config_tentativas() -> (u32, Duration) {
+    let tentativas = env::var("R2_RETRIES").ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .filter(|t| *t > 0)
+        .unwrap_or(TENTATIVAS_PADRAO);
+    let base = env::var("R2_RETRY_BASE_MS").ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(ESPERA_BASE_PADRAO_MS);
+    (tentativas, Duration::from_millis(base))
+}
+
+/// Backoff exponencial com "full jitter": um valor aleatório entre 0 e base·2^(n-1),
+/// para que os envios simultâneos não voltem todos ao mesmo tempo.
+fn espera_com_jitter(base: Duration, tentativa: u32) -> Duration {
+    let teto = base.saturating_mul(2u32.saturating_pow(tentativa - 1)).min(ESPERA_MAXIMA);
+    // Sem crate de números aleatórios: os nanossegundos do relógio bastam aqui
+    let ruido = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0) as f64 / 1e9;
+    teto.mul_f64(ruido)
+}
+
+/// Lista as chaves do backend com o prefixo informado (ex: "races/").
+pub async fn listar_r2(prefix: &str) -> Result<Vec<String>, RacedayError> {
+    Ok(crate::backends::atual()?.listar(prefix).await?)
+}
+
+/// Baixa um objeto do backend. `None` quando a chave ainda não existe.
+pub async fn baixar_r2(target_key: &str) -> Result<Option<Vec<u8>>, RacedayError> {
+    crate::throttle::aguardar().await;
+    Ok(crate::backends::atual()?.baixar(target_key).await?)
+}
+
+// --- ENVIO SÓ DO QUE MUDOU ---
+// O hash blake3 do arquivo gerado é comparado com o da chave publicada (metadado
+// no R2; nos outros backends, o conteúdo baixado). Rodar de novo no mesmo .INI
+// não reenvia nada nem dispara o rebuild do site.
+//   --force (RACEDAY_FORCE=1)   envia tudo mesmo sem alterações
+
+pub fn forcar() -> bool {
+    env::var("RACEDAY_FORCE").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// Verdadeiro quando a chave já está publicada com exatamente o conteúdo do arquivo.
+pub async fn inalterado(file_path: &Path, target_key: &str) -> Result<bool, RacedayError> {
+    if forcar() { return Ok(false); }
+    let local = blake3::hash(&fs::read(file_path)?).to_hex().to_string();
+    crate::throttle::aguardar().await;
+    let publicado = crate::backends::atual()?.hash_publicado(target_key).await?;
+    Ok(publicado.as_deref() == Some(local.as_str()))
+}
+
+/// Envia o arquivo só se o conteúdo for diferente do que já está publicado.
+pub async fn upload_se_mudou(file_path: &Path, target_key: &str) -> Result<bool, RacedayError> {
+    if inalterado(file_path, target_key).await? {
+        saida!("⏭️ Sem alterações: {}", target_key);
+        return Ok(false);
+    }
+    upload_to_r2(file_path, target_key).await?;
+    Ok(true)
+}
+
+// --- ESCRITA CONDICIONAL (LER, MESCLAR, REGRAVAR) ---
+// Arquivos compartilhados entre execuções (manifesto, recordes, ratings) são
+// baixados com a versão (ETag), mesclados e regravados só se ninguém tiver
+// gravado no meio do caminho; senão o ciclo recomeça a partir da versão nova.
+
+const TENTATIVAS_CONDICIONAIS: u32 = 5;
+
+/// Regrava `key` com o que `mesclar` devolver a partir do conteúdo atual (`None`
+/// se a chave não existe). `mesclar` devolve o JSON a gravar e o valor de
+/// retorno, ou `None` para não gravar nada (a função então devolve `None`).
+pub async fn atualizar_condicional<T>(
+    key: &str,
+    local: &Path,
+    mut mesclar: impl FnMut(Option<&[u8]>) -> Result<Option<(String, T)>, RacedayError>,
+) -> Result<Option<T>, RacedayError> {
+    let backend = crate::backends::atual()?;
+    for tentativa in 1..=TENTATIVAS_CONDICIONAIS {
+        crate::throttle::aguardar().await;
+        let atual = backend.baixar_versionado(key).await?;
+        let Some((conteudo, valor)) = mesclar(atual.as_ref().map(|(c, _)| c.as_slice()))? else { return Ok(None) };
+        fs::write(local, conteudo)?;
+
+        crate::throttle::aguardar().await;
+        let versao = atual.as_ref().map(|(_, v)| v.as_str());
+        if backend.enviar_se_versao(local, key, "application/json", versao).await? {
+            return Ok(Some(valor));
+        }
+        erro!("⚠️ {} alterado por outra execução (tentativa {}/{}); relendo...", key, tentativa, TENTATIVAS_CONDICIONAIS);
+        tokio::time::sleep(Duration::from_millis(200 * tentativa as u64)).await;
+    }
+    Err(RacedayError::Armazenamento {
+        key: key.to_string(),
+        tentativas: TENTATIVAS_CONDICIONAIS,
+        erro: "alterado por outra execução a cada tentativa".to_string(),
+    })
+}
+
+// --- CHAVES NO BUCKET ---
+
+/// Clube e pista entram nas chaves em minúsculas, com "_" no lugar dos espaços.
+pub fn slug_chave(nome: &str) -> String {
+    nome.to_lowercase().replace(" ", "_")
+}
+
+/// JSON bruto da prova, único por clube e pista: races/clube_pista_timestamp.json
+pub fn chave_prova(club: &str, track: &str, ts: &str) -> String {
+    format!("races/{}_{}_{}.json", slug_chave(club), slug_chave(track), ts)
+}
+
+/// Base dos arquivos do relatório, sem extensão: reports/clube_pista_corrida_timestamp
+/// (+ ".html", "_fiscais.html" ou as fotos da galeria).
+pub fn base_relatorio(club: &str, track: &str, race_slug: &str, ts: &str) -> String {
+    format!("reports/{}_{}_{}_{}", slug_chave(club), slug_chave(track), race_slug, ts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base_relatorio, chave_prova};
+
+    #[test]
+    fn chave_prova_em_minusculas_sem_espacos() {
+        assert_eq!(chave_prova("Clube X", "Pista", "20260101"), "races/clube_x_pista_20260101.json");
+    }
+
+    #[test]
+    fn base_relatorio_sem_extensao() {
+        assert_eq!(base_relatorio("Clube X", "Pista Nova", "gp_verao", "20260101"), "reports/clube_x_pista_nova_gp_verao_20260101");
+    }
+}
@@ -0,0 +1,122 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tera::Tera;
+
+// --- TEMPLATES EMBUTIDOS NO BINÁRIO ---
+// Os templates de templates/ vão compilados dentro do executável, então o
+// raceday.exe funciona sozinho, em qualquer pasta. Um arquivo com o mesmo nome
+// em TEMPLATES_DIR (ou em templates/, na pasta atual ou ao lado do executável)
+// continua tendo prioridade, para o clube personalizar o visual sem recompilar.
+//
+// Com TEMPLATES_REMOTE=1 os templates também vêm do bucket (prefixo templates/),
+// para ajustar o visual sem mexer no PC da cronometragem. Eles são baixados no
+// início de cada execução para TEMPLATES_CACHE (padrão <pasta de saída>/templates_cache);
+// sem rede ou no modo offline vale a última cópia baixada. Ordem de prioridade:
+//   TEMPLATES_DIR > bucket (cache) > templates/ local > embutido
+
+const PREFIXO_REMOTO: &str = "templates/";
+
+const EMBUTIDOS: [(&str, &str); 15] = [
+    ("challenge.html", include_str!("../templates/challenge.html")),
+    ("compare.html", include_str!("../templates/compare.html")),
+    ("digest.html", include_str!("../templates/digest.html")),
+    ("federation.html", include_str!("../templates/federation.html")),
+    ("hall_of_fame.html", include_str!("../templates/hall_of_fame.html")),
+    ("index.html", include_str!("../templates/index.html")),
+    ("marshals.html", include_str!("../templates/marshals.html")),
+    ("pilot.html", include_str!("../templates/pilot.html")),
+    ("portal_home.html", include_str!("../templates/portal_home.html")),
+    ("portal_track.html", include_str!("../templates/portal_track.html")),
+    ("report.html", include_str!("../templates/report.html")),
+    ("report_print.html", include_str!("../templates/report_print.html")),
+    ("report_tv.html", include_str!("../templates/report_tv.html")),
+    ("season.html", include_str!("../templates/season.html")),
+    ("top_laps.html", include_str!("../templates/top_laps.html")),
+];
+
+/// Template embutido pelo nome do arquivo ("report.html").
+pub fn embutido(arquivo: &str) -> Option<&'static str> {
+    EMBUTIDOS.iter().find(|(nome, _)| *nome == arquivo).map(|(_, texto)| *texto)
+}
+
+fn remoto_ativo() -> bool {
+    env::var("TEMPLATES_REMOTE").map(|v| v == "1" || v.eq_ignore_ascii_case("true")).unwrap_or(false)
+}
+
+fn pasta_cache() -> PathBuf {
+    env::var_os("TEMPLATES_CACHE").map(PathBuf::from).unwrap_or_else(|| crate::pasta_saida().join("templates_cache"))
+}
+
+/// Arquivo externo do template, na ordem de prioridade acima.
+fn caminho_externo(arquivo: &str) -> Option<PathBuf> {
+    let explicito = env::var_os("TEMPLATES_DIR").map(|d| PathBuf::from(d).join(arquivo));
+    let remoto = remoto_ativo().then(|| pasta_cache().join(arquivo));
+    let local = crate::recurso(&Path::new("templates").join(arquivo));
+    [explicito, remoto, Some(local)].into_iter().flatten().find(|p| p.is_file())
+}
+
+/// Carrega `arquivo` no Tera como `nome`: o arquivo externo quando existir,
+/// senão a cópia embutida.
+pub fn carregar(tera: &mut Tera, arquivo: &str, nome: &str) -> tera::Result<()> {
+    match (caminho_externo(arquivo), embutido(arquivo)) {
+        (Some(externo), _) => tera.add_template_file(&externo, Some(nome)),
+        (None, Some(texto)) => tera.add_raw_template(nome, texto),
+        (None, None) => tera.add_template_file(crate::template(arquivo), Some(nome)),
+    }
+}
+
+/// Atualiza o cache com os templates do bucket (TEMPLATES_REMOTE=1). Falhas só
+/// geram aviso: a execução segue com o cache anterior.
+pub async fn sincronizar() {
+    if !remoto_ativo() { return; }
+    if crate::offline::ativo() {
+        saida!("📴 Templates do bucket: usando o cache em {}", pasta_cache().display());
+        return;
+    }
+    match baixar_remotos().await {
+        Ok((total, novos)) => saida!("📄 Templates do bucket: {} arquivo(s), {} atualizado(s) em {}", total, novos, pasta_cache().display()),
+        Err(e) => erro!("⚠️ Templates do bucket indisponíveis ({}); usando o cache em {}", e, pasta_cache().display()),
+    }
+}
+
+async fn baixar_remotos() -> Result<(usize, usize), Box<dyn Error>> {
+    let pasta = pasta_cache();
+    fs::create_dir_all(&pasta)?;
+    let nomes: Vec<String> = crate::storage::listar_r2(PREFIXO_REMOTO).await?.into_iter()
+        .filter_map(|k| k.strip_prefix(PREFIXO_REMOTO).map(|n| n.to_string()))
+        .filter(|n| !n.is_empty() && !n.contains('/') && n.ends_with(".html"))
+        .collect();
+
+    let mut novos = 0;
+    for nome in &nomes {
+        let Some(conteudo) = crate::storage::baixar_r2(&format!("{}{}", PREFIXO_REMOTO, nome)).await? else { continue };
+        let destino = pasta.join(nome);
+        if fs::read(&destino).ok().as_deref() == Some(conteudo.as_slice()) { continue; }
+        fs::write(&destino, &conteudo)?;
+        novos += 1;
+    }
+    // Template removido do bucket volta a usar o local/embutido
+    for entrada in fs::read_dir(&pasta)?.filter_map(|e| e.ok()) {
+        let nome = entrada.file_name().to_string_lossy().to_string();
+        if nome.ends_with(".html") && !nomes.contains(&nome) {
+            fs::remove_file(entrada.path())?;
+        }
+    }
+    Ok((nomes.len(), novos))
+}
+
+/// Grava os templates embutidos em `pasta` (ponto de partida para personalizar).
+/// Arquivos existentes não são sobrescritos. Retorna quantos foram gravados.
+pub fn exportar(pasta: &Path) -> std::io::Result<usize> {
+    std::fs::create_dir_all(pasta)?;
+    let mut gravados = 0;
+    for (nome, texto) in EMBUTIDOS {
+        let destino = pasta.join(nome);
+        if destino.exists() { continue; }
+        std::fs::write(&destino, texto)?;
+        gravados += 1;
+    }
+    Ok(gravados)
+}
@@ -0,0 +1,81 @@
+use serde::Serialize;
+use std::env;
+use std::path::PathBuf;
+
+// --- TEMA DO CLUBE (LOGO, CORES E RODAPÉ) ---
+// O mesmo binário gera relatórios com a cara de cada clube, a partir do
+// raceday.toml ([theme]) ou das variáveis:
+//   CLUB_LOGO        caminho do logo (publicado como asset) ou URL pronta
+//   THEME_PRIMARY    cor principal (marcadores dos títulos, destaques)
+//   THEME_SECONDARY  cor secundária (nome do clube no cabeçalho)
+//   THEME_FOOTER     texto do rodapé
+// Sem CLUB_LOGO vale o logo.png da pasta de assets, se houver. O tema entra no
+// contexto do Tera como `tema`.
+
+/// Nome do logo no manifesto de assets quando vem de CLUB_LOGO.
+const NOME_LOGO: &str = "club-logo";
+
+#[derive(Serialize, Default)]
+pub struct Tema {
+    /// URL do logo, vazia sem logo
+    pub logo: String,
+    pub primaria: String,
+    pub secundaria: String,
+    pub rodape: String,
+}
+
+fn var(nome: &str) -> Option<String> {
+    env::var(nome).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn eh_url(valor: &str) -> bool {
+    valor.starts_with("http://") || valor.starts_with("https://")
+}
+
+/// Aceita só o que uma cor CSS pode ter (#hex, rgb(...), nomes), para o valor
+/// não escapar do bloco <style>.
+fn cor(nome: &str) -> String {
+    match var(nome) {
+        Some(c) if c.chars().all(|ch| ch.is_ascii_alphanumeric() || "#(),.% ".contains(ch)) => c,
+        Some(c) => {
+            erro!("⚠️ {} ignorada: '{}' não parece uma cor CSS", nome, c);
+            String::new()
+        }
+        None => String::new(),
+    }
+}
+
+/// CLUB_LOGO quando é um arquivo local: (nome no manifesto de assets, caminho).
+pub fn logo_local() -> Option<(String, PathBuf)> {
+    let caminho = PathBuf::from(var("CLUB_LOGO").filter(|v| !eh_url(v))?);
+    if !caminho.is_file() { return None; }
+    let nome = match caminho.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}.{}", NOME_LOGO, ext.to_lowercase()),
+        None => NOME_LOGO.to_string(),
+    };
+    Some((nome, caminho))
+}
+
+/// Arquivo local do logo (para o card do resultado): CLUB_LOGO ou logo.png dos assets.
+pub fn arquivo_logo() -> Option<PathBuf> {
+    var("CLUB_LOGO").filter(|v| !eh_url(v)).map(PathBuf::from)
+        .or_else(|| Some(crate::assets::pasta_assets().join("logo.png")))
+        .filter(|p| p.is_file())
+}
+
+/// Tema atual. O logo local só tem endereço depois de assets::publicar().
+pub fn tema() -> Tema {
+    let logo = match var("CLUB_LOGO") {
+        Some(url) if eh_url(&url) => url,
+        _ => logo_local().and_then(|(nome, _)| crate::assets::url(&nome))
+            .or_else(|| crate::assets::url("logo.png"))
+            .unwrap_or_default(),
+    };
+    Tema {
+        logo,
+        primaria: cor("THEME_PRIMARY"),
+        secundaria: cor("THEME_SECONDARY"),
+        rodape: var("THEME_FOOTER").unwrap_or_default(),
+    }
+}
+
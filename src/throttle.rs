@@ -0,0 +1,60 @@
+use std::env;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+// --- LIMITE DE REQUISIÇÕES AO ARMAZENAMENTO (TOKEN BUCKET) ---
+// Rebuilds em lote disparam centenas de chamadas seguidas e podem esbarrar no
+// rate limit do R2. Toda operação de armazenamento passa por `aguardar()`.
+//   STORAGE_MAX_RPS  requisições por segundo (sem a variável, não há limite)
+//   STORAGE_BURST    rajada máxima permitida (padrão: o próprio STORAGE_MAX_RPS)
+
+struct Balde {
+    fichas: f64,
+    ultimo: Instant,
+}
+
+struct Limite {
+    por_segundo: f64,
+    rajada: f64,
+}
+
+fn limite() -> Option<&'static Limite> {
+    static LIMITE: OnceLock<Option<Limite>> = OnceLock::new();
+    LIMITE.get_or_init(|| {
+        let por_segundo: f64 = env::var("STORAGE_MAX_RPS").ok()?.parse().ok().filter(|v: &f64| *v > 0.0)?;
+        let rajada = env::var("STORAGE_BURST").ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|v: &f64| *v >= 1.0)
+            .unwrap_or(por_segundo.max(1.0));
+        Some(Limite { por_segundo, rajada })
+    }).as_ref()
+}
+
+fn balde() -> &'static Mutex<Balde> {
+    static BALDE: OnceLock<Mutex<Balde>> = OnceLock::new();
+    BALDE.get_or_init(|| Mutex::new(Balde {
+        fichas: limite().map(|l| l.rajada).unwrap_or(0.0),
+        ultimo: Instant::now(),
+    }))
+}
+
+/// Espera até haver uma ficha disponível. O lock fica preso durante a espera,
+/// então as chamadas concorrentes são atendidas em ordem.
+pub async fn aguardar() {
+    let Some(limite) = limite() else { return };
+    let mut balde = balde().lock().await;
+
+    let agora = Instant::now();
+    balde.fichas = (balde.fichas + agora.duration_since(balde.ultimo).as_secs_f64() * limite.por_segundo).min(limite.rajada);
+    balde.ultimo = agora;
+
+    if balde.fichas < 1.0 {
+        let espera = (1.0 - balde.fichas) / limite.por_segundo;
+        tokio::time::sleep(Duration::from_secs_f64(espera)).await;
+        balde.fichas = 0.0;
+        balde.ultimo = Instant::now();
+    } else {
+        balde.fichas -= 1.0;
+    }
+}
@@ -0,0 +1,35 @@
+use std::error::Error;
+use std::fs;
+use tera::{Context, Tera};
+
+use crate::history::Historico;
+
+// --- TOP VOLTAS DE TODOS OS TEMPOS (UMA PÁGINA POR PISTA) ---
+// As voltas mais rápidas já registradas no histórico para a pista, volta a volta,
+// com piloto, tempo, data e fenda. Regerada a cada prova publicada.
+//   TOP_LAPS_KEY   chave no bucket, com {club} e {track} (padrão portal/{club}/{track}_top_laps.html)
+
+pub const TOP_VOLTAS: usize = 50;
+
+pub fn chave(club: &str, track: &str) -> String {
+    crate::portal::chave_layout("TOP_LAPS_KEY", "portal/{club}/{track}_top_laps.html", club, track)
+}
+
+/// Gera e envia a página da pista. `historico` já deve vir com os nomes públicos
+/// (privacy::historico_publico). Retorna a chave, ou `None` sem voltas registradas.
+pub async fn publicar(tera: &mut Tera, historico: &Historico, club: &str, track: &str) -> Result<Option<String>, Box<dyn Error>> {
+    let voltas = historico.top_voltas(club, track, TOP_VOLTAS);
+    if voltas.is_empty() { return Ok(None); }
+
+    crate::templates::carregar(tera, "top_laps.html", "top_laps")?;
+    let mut context = Context::new();
+    context.insert("club", club);
+    context.insert("track", track);
+    context.insert("voltas", &voltas);
+    let local = crate::pasta_saida().join("last_top_laps.html");
+    fs::write(&local, tera.render("top_laps", &context)?)?;
+
+    let key = chave(club, track);
+    crate::storage::upload_se_mudou(&local, &key).await?;
+    Ok(Some(key))
+}
@@ -0,0 +1,62 @@
+use serde::Serialize;
+
+// --- VÍDEOS INCORPORADOS (YOUTUBE / VIMEO) ---
+// Uso: --video <url>            vídeo do evento
+//      --video "2=<url>"        vídeo da bateria 2
+//      --video "Final=<url>"    rótulo livre
+
+#[derive(Serialize)]
+pub struct Video {
+    pub rotulo: String,
+    pub url: String,
+    pub embed_url: String,
+}
+
+/// Converte o link público no endereço do player incorporável.
+fn url_embed(url: &str) -> Option<String> {
+    let sem_protocolo = url.trim().trim_start_matches("https://").trim_start_matches("http://").trim_start_matches("www.");
+    let id_ate_separador = |s: &str| s.split(['?', '&', '/', '#']).next().unwrap_or("").to_string();
+
+    let id_youtube = if let Some(resto) = sem_protocolo.strip_prefix("youtu.be/") {
+        Some(id_ate_separador(resto))
+    } else if let Some(resto) = sem_protocolo.strip_prefix("youtube.com/live/") {
+        Some(id_ate_separador(resto))
+    } else if sem_protocolo.starts_with("youtube.com/watch") {
+        sem_protocolo.split(['?', '&']).find_map(|p| p.strip_prefix("v=")).map(id_ate_separador)
+    } else {
+        None
+    };
+    if let Some(id) = id_youtube.filter(|id| !id.is_empty()) {
+        return Some(format!("https://www.youtube.com/embed/{}", id));
+    }
+
+    let id_vimeo = sem_protocolo.strip_prefix("vimeo.com/").map(id_ate_separador)?;
+    if !id_vimeo.is_empty() && id_vimeo.chars().all(|c| c.is_ascii_digit()) {
+        return Some(format!("https://player.vimeo.com/video/{}", id_vimeo));
+    }
+    None
+}
+
+/// Converte os valores de `--video`, ignorando (com aviso) links não suportados.
+pub fn interpretar(valores: &[String]) -> Vec<Video> {
+    let mut videos = Vec::new();
+    for valor in valores {
+        let (rotulo, url) = match valor.split_once('=') {
+            Some((r, u)) if !r.contains("://") && !r.contains('?') => (r.trim(), u.trim()),
+            _ => ("", valor.trim()),
+        };
+        let rotulo = if rotulo.is_empty() {
+            "Evento".to_string()
+        } else if rotulo.chars().all(|c| c.is_ascii_digit()) {
+            format!("Bateria {}", rotulo)
+        } else {
+            rotulo.to_string()
+        };
+
+        match url_embed(url) {
+            Some(embed_url) => videos.push(Video { rotulo, url: url.to_string(), embed_url }),
+            None => erro!("⚠️ Link de vídeo não suportado (use YouTube ou Vimeo): {}", url),
+        }
+    }
+    videos
+}
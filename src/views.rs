@@ -0,0 +1,74 @@
+use serde_json::Value;
+use std::env;
+use std::error::Error;
+use std::fs;
+use tera::Tera;
+
+use crate::ranking::PilotoDisplay;
+
+// --- VISÕES EXTRAS DO RELATÓRIO (TV, CELULAR, ...) ---
+// Além do report.html, cada visão listada em REPORT_VIEWS é renderizada com o
+// mesmo contexto e publicada em <base>_<nome>.html:
+//   REPORT_VIEWS="tv=report_tv.html,mobile=report_mobile.html"
+// No raceday.toml: [views] tv = "report_tv.html". Os templates são procurados
+// como o principal (TEMPLATES_DIR ou templates/) e recebem também `visao` com o
+// nome da visão. templates/report_tv.html é um painel para a TV do clube.
+
+pub struct Visao {
+    pub nome: String,
+    pub template: String,
+}
+
+/// Nome vira parte da chave: só letras minúsculas, números, - e _.
+fn nome_valido(nome: &str) -> bool {
+    !nome.is_empty() && nome.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+pub fn configuradas() -> Vec<Visao> {
+    let mut visoes: Vec<Visao> = Vec::new();
+    for item in env::var("REPORT_VIEWS").unwrap_or_default().split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let Some((nome, template)) = item.split_once('=').map(|(n, t)| (n.trim(), t.trim())) else {
+            erro!("⚠️ Visão inválida em REPORT_VIEWS (esperado nome=template): {}", item);
+            continue;
+        };
+        if !nome_valido(nome) || template.is_empty() {
+            erro!("⚠️ Visão ignorada: \"{}\" (nome com a-z, 0-9, - ou _ e um template)", item);
+            continue;
+        }
+        if visoes.iter().any(|v| v.nome == nome) {
+            erro!("⚠️ Visão \"{}\" repetida em REPORT_VIEWS; vale a primeira", nome);
+            continue;
+        }
+        visoes.push(Visao { nome: nome.to_string(), template: template.to_string() });
+    }
+    visoes
+}
+
+/// Chave publicada de uma visão.
+pub fn chave(r2_key_base: &str, nome: &str) -> String {
+    format!("{}_{}.html", r2_key_base, nome)
+}
+
+/// O nome de arquivo termina com uma das visões configuradas (fica fora do índice).
+pub fn e_visao(nome_arquivo: &str) -> bool {
+    configuradas().iter().any(|v| nome_arquivo.ends_with(&format!("_{}.html", v.nome)))
+}
+
+/// Renderiza e publica cada visão. Retorna as chaves publicadas.
+pub async fn publicar(tera: &mut Tera, data: &Value, ranking: &[PilotoDisplay], best_lap_overall: f64, club: &str, track: &str, r2_key_base: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut chaves = Vec::new();
+    for visao in configuradas() {
+        let nome_template = format!("view_{}", visao.nome);
+        crate::templates::carregar(tera, &visao.template, &nome_template)
+            .map_err(|e| format!("❌ Template da visão {} ({}): {}", visao.nome, visao.template, e))?;
+        let mut context = crate::report::contexto_relatorio(data, ranking, best_lap_overall, club, track);
+        context.insert("visao", &visao.nome);
+
+        let local = crate::pasta_saida().join(format!("last_{}.html", visao.nome));
+        fs::write(&local, tera.render(&nome_template, &context)?)?;
+        let key = chave(r2_key_base, &visao.nome);
+        crate::storage::upload_to_r2(&local, &key).await?;
+        chaves.push(key);
+    }
+    Ok(chaves)
+}
@@ -0,0 +1,89 @@
+use fs_notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::pipeline::{self, Extras};
+
+// --- MODO VIGIA (PC DE CRONOMETRAGEM) ---
+// Uso: raceday watch <pasta>
+// Fica rodando o dia todo: cada .INI novo (ou reexportado) que o cronometrador
+// gravar na pasta passa pelo processamento completo. O arquivo só é processado
+// depois de WATCH_SETTLE_SECS (padrão 5) sem mudanças, porque o export é
+// gravado aos poucos. Erros são exibidos e o vigia continua; Ctrl+C encerra.
+
+const ESPERA_PADRAO_SECS: u64 = 5;
+
+fn e_ini(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("ini"))
+}
+
+/// Data de modificação + tamanho: identifica uma versão do arquivo.
+fn assinatura(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+pub async fn executar(dir: &Path, club: &str, track: &str) -> Result<(), Box<dyn Error>> {
+    let espera = Duration::from_secs(env::var("WATCH_SETTLE_SECS").ok().and_then(|v| v.parse().ok()).unwrap_or(ESPERA_PADRAO_SECS));
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = fs_notify::recommended_watcher(move |evento: fs_notify::Result<fs_notify::Event>| {
+        let _ = tx.send(evento);
+    })?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("❌ Não foi possível vigiar {}: {}", dir.display(), e))?;
+
+    // Arquivos que já estavam na pasta não são reprocessados, só versões novas
+    let mut processados: HashMap<PathBuf, (SystemTime, u64)> = crate::batch::descobrir(dir)?.into_iter()
+        .filter_map(|p| assinatura(&p).map(|a| (p, a)))
+        .collect();
+    let mut pendentes: HashMap<PathBuf, Instant> = HashMap::new();
+    let mut tique = tokio::time::interval(Duration::from_secs(1));
+    let (mut ok, mut falhas) = (0, 0);
+
+    saida!("👀 Vigiando {} ({} .INI já existente(s) ignorado(s)). Ctrl+C para sair.", dir.display(), processados.len());
+    loop {
+        tokio::select! {
+            evento = rx.recv() => {
+                match evento {
+                    Some(Ok(evento)) if matches!(evento.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                        for path in evento.paths.into_iter().filter(|p| e_ini(p)) {
+                            pendentes.insert(path, Instant::now());
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => erro!("⚠️ Erro do vigia: {}", e),
+                    None => break,
+                }
+            }
+            _ = tique.tick() => {
+                let prontos: Vec<PathBuf> = pendentes.iter()
+                    .filter(|(_, visto)| visto.elapsed() >= espera)
+                    .map(|(p, _)| p.clone())
+                    .collect();
+                for path in prontos {
+                    pendentes.remove(&path);
+                    let Some(versao) = assinatura(&path) else { continue };
+                    if processados.get(&path) == Some(&versao) { continue; }
+
+                    saida!("\n🏁 Novo export: {}", path.display());
+                    match pipeline::processar(&path, club, track, &Extras::default()).await {
+                        Ok(_) => { ok += 1; saida!("✨ {} publicado", path.display()); }
+                        Err(e) => { falhas += 1; erro!("{}", e); }
+                    }
+                    processados.insert(path, versao);
+                    crate::replication::imprimir_resumo();
+                    saida!("👀 Vigiando {} ({} ok, {} com falha até agora)", dir.display(), ok, falhas);
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    saida!("📊 Vigia encerrado: {} ok, {} com falha", ok, falhas);
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// --- WEBHOOKS DE EVENTOS AO VIVO ---
+// Só no modo live (live.rs): a cada parcial da mesma corrida comparamos com o
+// último estado salvo e disparamos avisos (Discord, operador do som) quando algo
+// relevante muda. O process, o batch e o rebuild não disparam nada.
+
+#[derive(Serialize, Deserialize, Default)]
+struct EstadoAoVivo {
+    lider: Option<String>,
+    melhor_volta: Option<f64>,
+    inicio_epoch: u64,
+    aviso_final_enviado: bool,
+}
+
+pub struct Evento {
+    pub tipo: &'static str,
+    pub mensagem: String,
+}
+
+/// Destinos lidos de WEBHOOK_URLS: "lead_change=https://...,best_lap=https://...,*=https://...".
+/// Uma URL sem prefixo recebe todos os eventos.
+fn destinos_configurados() -> Vec<(String, String)> {
+    interpretar_destinos(&env::var("WEBHOOK_URLS").unwrap_or_default())
+}
+
+fn interpretar_destinos(valor: &str) -> Vec<(String, String)> {
+    valor.split(',')
+        .map(|d| d.trim())
+        .filter(|d| !d.is_empty())
+        .map(|d| match d.split_once('=') {
+            Some((evento, url)) if !evento.starts_with("http") => (evento.trim().to_string(), url.trim().to_string()),
+            _ => ("*".to_string(), d.to_string()),
+        })
+        .collect()
+}
+
+fn agora_epoch() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Compara o estado atual da prova com o anterior e retorna os eventos novos.
+/// `encerrada`: o cronometrador já gravou o resultado final (sem aviso de reta final).
+pub fn detectar_eventos(timestamp: &str, lider: Option<&str>, melhor_volta: Option<(f64, &str)>, encerrada: bool) -> Vec<Evento> {
+    let state_path = crate::pasta_saida().join(format!("live_state_{}.json", timestamp));
+    let mut estado: EstadoAoVivo = fs::read_to_string(&state_path).ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    let duracao_min = env::var("RACE_DURATION_MIN").ok().and_then(|v| v.parse::<u64>().ok());
+    let eventos = comparar(&mut estado, agora_epoch(), lider, melhor_volta, encerrada, duracao_min);
+
+    let _ = fs::create_dir_all(crate::pasta_saida());
+    if let Ok(json) = serde_json::to_string_pretty(&estado) {
+        let _ = fs::write(&state_path, json);
+    }
+
+    eventos
+}
+
+/// Regras dos eventos, sem tocar em disco nem no ambiente.
+fn comparar(estado: &mut EstadoAoVivo, agora: u64, lider: Option<&str>, melhor_volta: Option<(f64, &str)>, encerrada: bool, duracao_min: Option<u64>) -> Vec<Evento> {
+    let primeira_leitura = estado.inicio_epoch == 0;
+    if primeira_leitura { estado.inicio_epoch = agora; }
+
+    let mut eventos = Vec::new();
+
+    if let Some(novo_lider) = lider {
+        if !primeira_leitura && estado.lider.as_deref() != Some(novo_lider) {
+            eventos.push(Evento {
+                tipo: "lead_change",
+                mensagem: format!("🏁 Troca de liderança: {} assume a ponta!", novo_lider),
+            });
+        }
+        estado.lider = Some(novo_lider.to_string());
+    }
+
+    if let Some((tempo, piloto)) = melhor_volta {
+        let bateu_recorde = estado.melhor_volta.map(|anterior| tempo < anterior).unwrap_or(true);
+        if bateu_recorde {
+            if !primeira_leitura {
+                eventos.push(Evento {
+                    tipo: "best_lap",
+                    mensagem: format!("🔥 Nova melhor volta da prova: {} com {:.3}s", piloto, tempo),
+                });
+            }
+            estado.melhor_volta = Some(tempo);
+        }
+    }
+
+    // Prova já terminada (ex.: o live aberto depois do fim): não há reta final a avisar
+    if encerrada { estado.aviso_final_enviado = true; }
+
+    // Aviso de reta final: RACE_DURATION_MIN conta a partir da primeira leitura da prova
+    if let Some(duracao_min) = duracao_min {
+        let decorrido = agora.saturating_sub(estado.inicio_epoch);
+        let restante = (duracao_min * 60).saturating_sub(decorrido);
+        if !estado.aviso_final_enviado && restante <= 300 {
+            eventos.push(Evento {
+                tipo: "final_minutes",
+                mensagem: "⏱️ Últimos 5 minutos de prova!".to_string(),
+            });
+            estado.aviso_final_enviado = true;
+        }
+    }
+
+    eventos
+}
+
+pub async fn disparar(eventos: &[Evento], club: &str, titulo: &str) {
+    let destinos = destinos_configurados();
+    if destinos.is_empty() || eventos.is_empty() || crate::offline::ativo() { return; }
+
+    let client = reqwest::Client::new();
+    for evento in eventos {
+        for (filtro, url) in &destinos {
+            if filtro != "*" && filtro != evento.tipo { continue; }
+            // "content" é o campo lido pelos webhooks do Discord
+            let payload = serde_json::json!({
+                "event": evento.tipo,
+                "club": club,
+                "race": titulo,
+                "content": evento.mensagem,
+            });
+            match client.post(url).json(&payload).timeout(Duration::from_secs(10)).send().await {
+                Ok(_) => saida!("📣 Webhook '{}' enviado: {}", evento.tipo, evento.mensagem),
+                Err(e) => erro!("⚠️ Falha no webhook '{}' ({}): {}", evento.tipo, url, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tipos(eventos: &[Evento]) -> Vec<&'static str> {
+        eventos.iter().map(|e| e.tipo).collect()
+    }
+
+    #[test]
+    fn destinos_com_e_sem_prefixo() {
+        let destinos = interpretar_destinos("lead_change=https://a/x, https://b/y?k=v ,*=https://c");
+        assert_eq!(destinos, vec![
+            ("lead_change".to_string(), "https://a/x".to_string()),
+            ("*".to_string(), "https://b/y?k=v".to_string()),
+            ("*".to_string(), "https://c".to_string()),
+        ]);
+        assert!(interpretar_destinos("").is_empty());
+    }
+
+    #[test]
+    fn primeira_leitura_nao_dispara() {
+        let mut estado = EstadoAoVivo::default();
+        let eventos = comparar(&mut estado, 1000, Some("Ana"), Some((5.1, "Ana")), false, None);
+        assert!(eventos.is_empty());
+        assert_eq!(estado.lider.as_deref(), Some("Ana"));
+        assert_eq!(estado.melhor_volta, Some(5.1));
+    }
+
+    #[test]
+    fn troca_de_lider_e_melhor_volta() {
+        let mut estado = EstadoAoVivo::default();
+        comparar(&mut estado, 1000, Some("Ana"), Some((5.1, "Ana")), false, None);
+        let eventos = comparar(&mut estado, 1060, Some("Bia"), Some((5.0, "Bia")), false, None);
+        assert_eq!(tipos(&eventos), vec!["lead_change", "best_lap"]);
+        // Mesmo estado de novo: nada a avisar
+        assert!(comparar(&mut estado, 1120, Some("Bia"), Some((5.2, "Ana")), false, None).is_empty());
+    }
+
+    #[test]
+    fn reta_final_uma_vez() {
+        let mut estado = EstadoAoVivo::default();
+        assert!(comparar(&mut estado, 1, None, None, false, Some(20)).is_empty());
+        assert_eq!(tipos(&comparar(&mut estado, 1 + 16 * 60, None, None, false, Some(20))), vec!["final_minutes"]);
+        assert!(comparar(&mut estado, 1 + 17 * 60, None, None, false, Some(20)).is_empty());
+    }
+
+    #[test]
+    fn prova_encerrada_sem_reta_final() {
+        let mut estado = EstadoAoVivo::default();
+        comparar(&mut estado, 1, None, None, false, Some(20));
+        assert!(comparar(&mut estado, 1 + 30 * 60, None, None, true, Some(20)).is_empty());
+        assert!(estado.aviso_final_enviado);
+    }
+}
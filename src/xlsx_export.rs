@@ -0,0 +1,128 @@
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use serde_json::Value;
+use std::error::Error;
+
+use crate::i18n::t;
+use crate::ranking::PilotoDisplay;
+
+// --- PLANILHA EXCEL (XLSX) ---
+// Publicada em <base>.xlsx ao lado do CSV, pronta para a secretaria do clube:
+// uma aba de resumo com a classificação e as voltas por fenda, e uma aba por
+// sessão de bateria com o detalhe de cada fenda. Voltas e tempos vão como
+// números (não texto), para somar e ordenar direto no Excel.
+
+/// Nome de aba válido no Excel: até 31 caracteres, sem []:*?/\
+fn nome_aba(nome: &str) -> String {
+    nome.chars().filter(|c| !"[]:*?/\\".contains(*c)).take(31).collect()
+}
+
+/// Número quando o texto for numérico ("1,5" ou "1.5"), senão texto.
+fn escrever_valor(aba: &mut Worksheet, linha: u32, coluna: u16, valor: &str) -> Result<(), XlsxError> {
+    match valor.replace(',', ".").parse::<f64>() {
+        Ok(n) => aba.write_number(linha, coluna, n).map(|_| ()),
+        Err(_) => aba.write_string(linha, coluna, valor).map(|_| ()),
+    }
+}
+
+fn cabecalho(aba: &mut Worksheet, colunas: &[String], negrito: &Format) -> Result<(), XlsxError> {
+    for (c, titulo) in colunas.iter().enumerate() {
+        aba.write_string_with_format(0, c as u16, titulo, negrito)?;
+    }
+    aba.set_freeze_panes(1, 0)?;
+    Ok(())
+}
+
+fn resumo(livro: &mut Workbook, ranking: &[PilotoDisplay], slots_count: usize, negrito: &Format, tempo: &Format) -> Result<(), XlsxError> {
+    let fendas = crate::config::fendas_pista(slots_count);
+    let aba = livro.add_worksheet();
+    aba.set_name(nome_aba(&t("texts.summary")))?;
+
+    let mut colunas: Vec<String> = vec![
+        t("report.pos"), t("report.pilot"), t("report.laps"), t("report.gap"),
+        t("texts.penalties"), t("report.deslots"), t("report.best_lap"), t("report.average_lap"),
+    ];
+    colunas.extend(fendas.iter().take(slots_count).map(|f| f.nome.clone()));
+    cabecalho(aba, &colunas, negrito)?;
+    aba.set_column_width(1, 28)?;
+
+    for (i, p) in ranking.iter().enumerate() {
+        let linha = i as u32 + 1;
+        match p.status.as_str() {
+            "" => aba.write_number(linha, 0, (i + 1) as f64)?,
+            status => aba.write_string(linha, 0, status)?,
+        };
+        aba.write_string(linha, 1, &p.nome)?;
+        aba.write_number(linha, 2, p.total_laps as f64)?;
+        escrever_valor(aba, linha, 3, &p.gap)?;
+        aba.write_number(linha, 4, p.penalties as f64)?;
+        aba.write_number(linha, 5, p.deslots as f64)?;
+        if let Some(t) = p.best_time.parse::<f64>().ok().filter(|t| *t > 0.0) {
+            aba.write_number_with_format(linha, 6, t, tempo)?;
+        }
+        if let Ok(t) = p.average_lap_time.parse::<f64>() {
+            aba.write_number_with_format(linha, 7, t, tempo)?;
+        }
+        for s in 1..=slots_count {
+            let voltas = p.laps_per_slot.get(&s.to_string()).and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+            aba.write_number(linha, 7 + s as u16, voltas)?;
+        }
+    }
+    Ok(())
+}
+
+fn sessao(livro: &mut Workbook, titulo: &str, slots: &serde_json::Map<String, Value>, fendas: &[crate::config::Fenda], negrito: &Format, tempo: &Format) -> Result<(), XlsxError> {
+    let aba = livro.add_worksheet();
+    aba.set_name(nome_aba(titulo))?;
+    let colunas: Vec<String> = vec![t("texts.slot"), t("report.pilot"), t("report.laps"), t("report.best_lap"), t("report.average_lap"), t("report.deslots")];
+    cabecalho(aba, &colunas, negrito)?;
+    aba.set_column_width(1, 28)?;
+
+    let mut ordem: Vec<(&String, &Value)> = slots.iter().collect();
+    ordem.sort_by_key(|(fenda, _)| fenda.parse::<usize>().unwrap_or(usize::MAX));
+    for (i, (fenda, s)) in ordem.into_iter().enumerate() {
+        let linha = i as u32 + 1;
+        let nome_fenda = fenda.parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|n| fendas.get(n))
+            .map(|f| f.nome.clone())
+            .unwrap_or_else(|| fenda.clone());
+        aba.write_string(linha, 0, &nome_fenda)?;
+        aba.write_string(linha, 1, s["name"].as_str().unwrap_or("---"))?;
+        aba.write_number(linha, 2, s["laps"].as_f64().unwrap_or(0.0))?;
+        if let Some(best) = s["best"].as_f64().filter(|t| *t > 0.0) {
+            aba.write_number_with_format(linha, 3, best, tempo)?;
+        }
+        let tempos: Vec<f64> = s["lap_times"].as_array().into_iter().flatten().filter_map(|t| t.as_f64()).filter(|t| *t > 0.0).collect();
+        if !tempos.is_empty() {
+            aba.write_number_with_format(linha, 4, tempos.iter().sum::<f64>() / tempos.len() as f64, tempo)?;
+        }
+        aba.write_number(linha, 5, s["deslots"].as_f64().unwrap_or(0.0))?;
+    }
+    Ok(())
+}
+
+pub fn gerar(data: &Value, ranking: &[PilotoDisplay], destino: &std::path::Path) -> Result<(), Box<dyn Error>> {
+    let slots_count = crate::ranking::total_fendas(data);
+    let negrito = Format::new().set_bold();
+    let tempo = Format::new().set_num_format("0.000");
+
+    let mut livro = Workbook::new();
+    resumo(&mut livro, ranking, slots_count, &negrito, &tempo)?;
+    let fendas = crate::config::fendas_pista(slots_count);
+    for race in data["races"].as_array().into_iter().flatten() {
+        for s in race["sessions"].as_array().into_iter().flatten() {
+            let Some(slots) = s["slots"].as_object() else { continue };
+            let titulo = format!("{} - {} {}", race["name"].as_str().map(|n| n.to_string()).unwrap_or_else(|| t("texts.heat")), t("texts.session"), s["session"].as_u64().unwrap_or(0));
+            sessao(&mut livro, &titulo, slots, &fendas, &negrito, &tempo)?;
+        }
+    }
+    livro.save(destino)?;
+    Ok(())
+}
+
+/// Grava e publica <base>.xlsx. Retorna a chave.
+pub async fn publicar(data: &Value, ranking: &[PilotoDisplay], r2_key_base: &str) -> Result<String, Box<dyn Error>> {
+    let local = crate::pasta_saida().join("last_upload.xlsx");
+    gerar(data, ranking, &local)?;
+    let key = format!("{}.xlsx", r2_key_base);
+    crate::storage::upload_to_r2(&local, &key).await?;
+    Ok(key)
+}